@@ -0,0 +1,167 @@
+// Audit log of destructive operations, stored as JSON lines under the app support dir
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::scanners::disk_size::get_path_size;
+
+/// Current log file and its single rotated predecessor
+const AUDIT_FILE: &str = "operations.log";
+const AUDIT_BACKUP_FILE: &str = "operations.log.1";
+
+/// Rotate the log once it grows past this size
+const MAX_AUDIT_BYTES: u64 = 1024 * 1024;
+
+/// One recorded destructive operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub timestamp: u64, // Unix timestamp
+    pub operation: String,
+    pub path: String,
+    pub size: u64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Append an entry to `log`, rotating it to `backup` first if it is too large
+fn append_to(log: &Path, backup: &Path, entry: &AuditEntry) -> Result<(), String> {
+    if fs::metadata(log).map(|m| m.len() >= MAX_AUDIT_BYTES).unwrap_or(false) {
+        fs::rename(log, backup).map_err(|e| e.to_string())?;
+    }
+
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+/// Read up to `limit` entries, newest first, from `log` and then `backup`
+fn read_recent_from(log: &Path, backup: &Path, limit: usize) -> Vec<AuditEntry> {
+    let mut entries = Vec::new();
+    for file in [log, backup] {
+        let Ok(contents) = fs::read_to_string(file) else {
+            continue;
+        };
+        entries.extend(
+            contents
+                .lines()
+                .rev()
+                .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok()),
+        );
+        if entries.len() >= limit {
+            break;
+        }
+    }
+    entries.truncate(limit);
+    entries
+}
+
+fn audit_paths() -> Result<(PathBuf, PathBuf), String> {
+    Ok((
+        crate::storage::app_support_file(AUDIT_FILE)?,
+        crate::storage::app_support_file(AUDIT_BACKUP_FILE)?,
+    ))
+}
+
+/// Record an operation. Failures to write the log are only logged, never returned,
+/// so auditing can't block the operation itself.
+pub fn record(operation: &str, path: &str, size: u64, result: &Result<(), String>) {
+    let entry = AuditEntry {
+        timestamp: now_timestamp(),
+        operation: operation.to_string(),
+        path: path.to_string(),
+        size,
+        success: result.is_ok(),
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = audit_paths().and_then(|(log, backup)| append_to(&log, &backup, &entry)) {
+        log::warn!("Failed to write audit log: {}", e);
+    }
+}
+
+/// Run a destructive operation on `path` and record it along with the path's
+/// prior size. Paths protected in settings are refused without running `op`.
+pub fn logged<F>(operation: &str, path: &str, op: F) -> Result<(), String>
+where
+    F: FnOnce() -> Result<(), String>,
+{
+    let size = get_path_size(Path::new(path));
+    let result = crate::path_guard::ensure_not_protected(Path::new(path)).and_then(|_| op());
+    record(operation, path, size, &result);
+    result
+}
+
+/// Most recent recorded operations, newest first
+pub fn recent_operations(limit: usize) -> Vec<AuditEntry> {
+    audit_paths()
+        .map(|(log, backup)| read_recent_from(&log, &backup, limit))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: 1,
+            operation: "delete_file".to_string(),
+            path: path.to_string(),
+            size: 10,
+            success: true,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_recent() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log = temp_dir.path().join(AUDIT_FILE);
+        let backup = temp_dir.path().join(AUDIT_BACKUP_FILE);
+
+        for path in ["/a", "/b", "/c"] {
+            append_to(&log, &backup, &entry(path)).unwrap();
+        }
+
+        let recent = read_recent_from(&log, &backup, 2);
+        let paths: Vec<&str> = recent.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/c", "/b"]);
+    }
+
+    #[test]
+    fn test_rotation_keeps_older_entries_readable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log = temp_dir.path().join(AUDIT_FILE);
+        let backup = temp_dir.path().join(AUDIT_BACKUP_FILE);
+
+        append_to(&log, &backup, &entry("/old")).unwrap();
+        // Pad the log past the rotation threshold
+        let file = OpenOptions::new().append(true).open(&log).unwrap();
+        file.set_len(MAX_AUDIT_BYTES + 1).unwrap();
+        append_to(&log, &backup, &entry("/new")).unwrap();
+
+        assert!(backup.exists());
+        let paths: Vec<String> = read_recent_from(&log, &backup, 10).into_iter().map(|e| e.path).collect();
+        assert_eq!(paths[0], "/new");
+        assert!(paths.contains(&"/old".to_string()));
+    }
+
+    #[test]
+    fn test_logged_returns_operation_result() {
+        let result = logged("delete_file", "/nonexistent/file", || Err("denied".to_string()));
+        assert_eq!(result, Err("denied".to_string()));
+    }
+}
@@ -0,0 +1,132 @@
+// Helpers for running an operation over many paths without stopping at the first failure
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::thread;
+
+use crate::scanners::disk_size::get_path_size;
+
+/// Result of deleting a single path as part of a batch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeleteOutcome {
+    pub path: String,
+    pub result: Result<(), String>,
+}
+
+/// Apply `op` to every path, recording each result rather than short-circuiting
+pub fn for_each_path<F>(paths: Vec<String>, mut op: F) -> Vec<DeleteOutcome>
+where
+    F: FnMut(&str) -> Result<(), String>,
+{
+    paths
+        .into_iter()
+        .map(|path| {
+            let result = op(&path);
+            DeleteOutcome { path, result }
+        })
+        .collect()
+}
+
+/// On-disk size of a single path, or why it couldn't be read
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PathSize {
+    pub path: String,
+    pub size: u64,
+    pub error: Option<String>,
+}
+
+/// Size one path, reporting inaccessible paths as errors instead of zero
+fn path_size(path: String) -> PathSize {
+    match std::fs::symlink_metadata(&path) {
+        Ok(_) => PathSize {
+            size: get_path_size(Path::new(&path)),
+            path,
+            error: None,
+        },
+        Err(e) => PathSize {
+            path,
+            size: 0,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Size every path, spreading the work across threads. Results keep the input order.
+pub fn sizes_of(paths: Vec<String>) -> Vec<PathSize> {
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let chunk_size = paths.len().div_ceil(workers).max(1);
+
+    let mut chunks: Vec<Vec<String>> = Vec::new();
+    let mut paths = paths.into_iter().peekable();
+    while paths.peek().is_some() {
+        chunks.push(paths.by_ref().take(chunk_size).collect());
+    }
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || chunk.into_iter().map(path_size).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each_path_collects_failures() {
+        let paths = vec!["a".to_string(), "bad".to_string(), "c".to_string()];
+        let mut visited = Vec::new();
+
+        let outcomes = for_each_path(paths, |path| {
+            visited.push(path.to_string());
+            if path == "bad" {
+                Err("Permission denied".to_string())
+            } else {
+                Ok(())
+            }
+        });
+
+        assert_eq!(visited, vec!["a", "bad", "c"]);
+        assert!(outcomes[0].result.is_ok());
+        assert_eq!(outcomes[1].result, Err("Permission denied".to_string()));
+        assert!(outcomes[2].result.is_ok());
+    }
+
+    #[test]
+    fn test_delete_outcome_serialization() {
+        let outcome = DeleteOutcome {
+            path: "/tmp/x".to_string(),
+            result: Err("nope".to_string()),
+        };
+        let json = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(json, r#"{"path":"/tmp/x","result":{"Err":"nope"}}"#);
+    }
+
+    #[test]
+    fn test_sizes_of_keeps_order_and_reports_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("a.bin");
+        std::fs::write(&file, vec![1u8; 8192]).unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        let paths = vec![
+            file.to_string_lossy().to_string(),
+            missing.to_string_lossy().to_string(),
+            temp_dir.path().to_string_lossy().to_string(),
+        ];
+        let sizes = sizes_of(paths.clone());
+
+        assert_eq!(sizes.iter().map(|s| s.path.clone()).collect::<Vec<_>>(), paths);
+        assert!(sizes[0].size >= 8192 && sizes[0].error.is_none());
+        assert_eq!(sizes[1].size, 0);
+        assert!(sizes[1].error.is_some());
+        assert!(sizes[2].size >= sizes[0].size);
+        assert!(sizes_of(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::privileges;
+use crate::scanners::trash_scanner::{self, TrashCapability};
+use std::path::Path;
+use tauri::command;
+
+/// Check whether moving any of `paths` to the trash would need an
+/// administrator password. Paths that no longer exist are ignored.
+#[command]
+pub async fn cleanup_requires_admin(paths: Vec<String>) -> Result<bool, String> {
+    Ok(paths
+        .iter()
+        .any(|path| trash_scanner::can_trash(Path::new(path)) == Ok(TrashCapability::NeedsAdmin)))
+}
+
+/// Ask for the administrator password once, so later privileged cleanups
+/// don't each prompt again. Call `end_admin_session` when done.
+#[command]
+pub async fn authorize_admin_session() -> Result<(), String> {
+    tokio::task::spawn_blocking(privileges::authorize_admin_session)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Release the authorization obtained by `authorize_admin_session`
+#[command]
+pub async fn end_admin_session() -> Result<(), String> {
+    privileges::end_admin_session();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cleanup_requires_admin() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+
+        let paths = vec![file.to_string_lossy().to_string(), "/nonexistent/path".to_string()];
+        assert!(!cleanup_requires_admin(paths).await.unwrap());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::batch::{self, DeleteOutcome};
+use crate::removal::Removal;
+use crate::scanners::browser_scanner::{self, BrowserProfile};
+use crate::scanners::size_cache;
+use tauri::command;
+
+/// Scan browser profiles and their individual caches (Cache, Code Cache, GPUCache, ...)
+#[command]
+pub async fn scan_browser_caches() -> Result<Vec<BrowserProfile>, String> {
+    let profiles = browser_scanner::scan_browser_profiles();
+    size_cache::save_global_cache();
+    Ok(profiles)
+}
+
+/// Delete a single browser cache folder, to the Trash while safe mode is on
+#[command]
+pub async fn delete_browser_cache(path: String) -> Result<(), String> {
+    browser_scanner::delete_browser_cache(&path, Removal::from_settings())
+}
+
+/// Delete several browser cache folders, reporting the outcome for each path
+#[command]
+pub async fn delete_browser_caches(paths: Vec<String>) -> Result<Vec<DeleteOutcome>, String> {
+    let removal = Removal::from_settings();
+    Ok(batch::for_each_path(paths, |path| browser_scanner::delete_browser_cache(path, removal)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scan_browser_caches() {
+        assert!(scan_browser_caches().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_browser_caches_rejects_unknown() {
+        let outcomes = delete_browser_caches(vec!["/tmp/Cookies".to_string()]).await.unwrap();
+        assert!(outcomes[0].result.is_err());
+    }
+}
@@ -1,34 +1,182 @@
-use crate::scanners::cache_scanner::{self, CacheEntry};
+use crate::batch::{self, DeleteOutcome};
+use crate::commands::config;
+use crate::scanners::cache_diff::{self, CacheDelta, CacheSnapshot};
+use crate::scanners::cache_scanner::{self, CacheContents, CacheEntry, GroupedCaches};
+use crate::removal::Removal;
+use crate::scanners::running_apps;
+use crate::scanners::scan_options::ScanOptions;
+use crate::scanners::shared_junk_scanner::{self, SharedJunkLocation};
+use crate::scanners::size_cache;
+use crate::storage;
+use std::time::SystemTime;
 use tauri::command;
 
+/// File under the app support dir holding the last saved cache scan
+const CACHE_SNAPSHOT_FILE: &str = "cache_snapshot.json";
+
+/// Build cache walk options from the optional command parameters and the
+/// exclude patterns in settings
+fn cache_scan_options(
+    max_depth: Option<usize>,
+    include_xattrs: Option<bool>,
+    check_databases: Option<bool>,
+) -> Result<ScanOptions, String> {
+    Ok(ScanOptions::with_max_depth(max_depth)
+        .with_exclude(&config::exclude_patterns_with(None))?
+        .with_include_xattrs(include_xattrs.unwrap_or(false))
+        .with_check_cache_databases(check_databases.unwrap_or(false)))
+}
+
 /// Scan user caches (~Library/Caches)
+///
+/// `max_depth` limits how deep each cache folder is walked when sizing it
+/// (1 = immediate children only). `min_size_bytes` hides caches smaller than
+/// the threshold (default 0 shows everything). `include_xattrs` adds extended
+/// attribute sizes for numbers closer to Finder's, at the cost of a slower scan.
+/// `check_databases` flags caches holding an empty `.db`/`.sqlite` file as
+/// `possibly_corrupt`, which also slows the scan.
 #[command]
-pub async fn scan_user_caches() -> Result<Vec<CacheEntry>, String> {
-    Ok(cache_scanner::scan_user_caches())
+pub async fn scan_user_caches(
+    max_depth: Option<usize>,
+    min_size_bytes: Option<u64>,
+    include_xattrs: Option<bool>,
+    check_databases: Option<bool>,
+) -> Result<Vec<CacheEntry>, String> {
+    Ok(cache_scanner::scan_user_caches(
+        &cache_scan_options(max_depth, include_xattrs, check_databases)?,
+        min_size_bytes.unwrap_or(0),
+    ))
 }
 
 /// Scan system caches (/Library/Caches)
 #[command]
-pub async fn scan_system_caches() -> Result<Vec<CacheEntry>, String> {
-    Ok(cache_scanner::scan_system_caches())
+pub async fn scan_system_caches(
+    max_depth: Option<usize>,
+    min_size_bytes: Option<u64>,
+    include_xattrs: Option<bool>,
+    check_databases: Option<bool>,
+) -> Result<Vec<CacheEntry>, String> {
+    Ok(cache_scanner::scan_system_caches(
+        &cache_scan_options(max_depth, include_xattrs, check_databases)?,
+        min_size_bytes.unwrap_or(0),
+    ))
 }
 
 /// Scan all caches
 #[command]
-pub async fn scan_all_caches() -> Result<Vec<CacheEntry>, String> {
-    Ok(cache_scanner::scan_all_caches())
+pub async fn scan_all_caches(
+    max_depth: Option<usize>,
+    min_size_bytes: Option<u64>,
+    include_xattrs: Option<bool>,
+    check_databases: Option<bool>,
+) -> Result<Vec<CacheEntry>, String> {
+    Ok(cache_scanner::scan_all_caches(
+        &cache_scan_options(max_depth, include_xattrs, check_databases)?,
+        min_size_bytes.unwrap_or(0),
+    ))
+}
+
+/// Scan user and system caches, returned as separate lists.
+/// Takes the same options as `scan_all_caches`.
+#[command]
+pub async fn scan_caches_grouped(
+    max_depth: Option<usize>,
+    min_size_bytes: Option<u64>,
+    include_xattrs: Option<bool>,
+    check_databases: Option<bool>,
+) -> Result<GroupedCaches, String> {
+    Ok(cache_scanner::scan_caches_grouped(
+        &cache_scan_options(max_depth, include_xattrs, check_databases)?,
+        min_size_bytes.unwrap_or(0),
+    ))
+}
+
+/// Report sizes of system-wide junk outside the home folder: `/Users/Shared`,
+/// `/private/var/tmp` and `/Library/Logs`, flagging which are risky to clean
+/// and which need an administrator
+#[command]
+pub async fn scan_shared_junk() -> Result<Vec<SharedJunkLocation>, String> {
+    let locations = tokio::task::spawn_blocking(shared_junk_scanner::scan_shared_junk)
+        .await
+        .map_err(|e| e.to_string())?;
+    size_cache::save_global_cache();
+    Ok(locations)
+}
+
+/// Show what a cache directory holds before it is cleared: its immediate
+/// children with on-disk sizes, largest first. At most
+/// `MAX_INSPECTED_CHILDREN` are returned; `truncated` says whether there are more.
+#[command]
+pub async fn inspect_cache(path: String) -> Result<CacheContents, String> {
+    let contents = tokio::task::spawn_blocking(move || {
+        cache_scanner::inspect_cache(&path, cache_scanner::MAX_INSPECTED_CHILDREN)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    size_cache::save_global_cache();
+    Ok(contents)
+}
+
+/// Delete a specific cache, moving it to the Trash while safe mode is on.
+/// The cache of a running app is refused with an `AppIsRunning` error unless
+/// `force` is set.
+#[command]
+pub async fn delete_cache(path: String, force: Option<bool>) -> Result<(), String> {
+    if !force.unwrap_or(false) {
+        running_apps::ensure_not_running(&path, &running_apps::running_apps())?;
+    }
+    cache_scanner::delete_cache(&path, Removal::from_settings())
+}
+
+/// Delete several caches, reporting the outcome for each path. Caches of
+/// running apps fail with `AppIsRunning` unless `force` is set.
+#[command]
+pub async fn delete_caches(paths: Vec<String>, force: Option<bool>) -> Result<Vec<DeleteOutcome>, String> {
+    let running = if force.unwrap_or(false) { Vec::new() } else { running_apps::running_apps() };
+    let removal = Removal::from_settings();
+    Ok(batch::for_each_path(paths, |path| {
+        running_apps::ensure_not_running(path, &running)?;
+        cache_scanner::delete_cache(path, removal)
+    }))
+}
+
+/// Clear the on-disk cache of computed directory sizes
+#[command]
+pub async fn clear_scan_cache() -> Result<(), String> {
+    size_cache::clear_global_cache()
 }
 
-/// Delete a specific cache
+/// Scan all caches and save the result as the baseline for `diff_caches_against_snapshot`.
+/// Returns the number of caches recorded.
 #[command]
-pub async fn delete_cache(path: String) -> Result<(), String> {
-    cache_scanner::delete_cache(&path)
+pub async fn snapshot_caches() -> Result<usize, String> {
+    let snapshot = CacheSnapshot {
+        timestamp: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        caches: cache_scanner::scan_all_caches(&ScanOptions::default(), 0),
+    };
+    storage::write_json_atomic(&storage::app_support_file(CACHE_SNAPSHOT_FILE)?, &snapshot)?;
+    size_cache::save_global_cache();
+    Ok(snapshot.caches.len())
+}
+
+/// Compare a fresh scan of all caches with the saved snapshot, listing caches
+/// that are new, grown, shrunk or removed since it was taken
+#[command]
+pub async fn diff_caches_against_snapshot() -> Result<Vec<CacheDelta>, String> {
+    let snapshot: CacheSnapshot = storage::read_json(&storage::app_support_file(CACHE_SNAPSHOT_FILE)?)
+        .ok_or("No cache snapshot has been saved yet")?;
+    let current = cache_scanner::scan_all_caches(&ScanOptions::default(), 0);
+    size_cache::save_global_cache();
+    Ok(cache_diff::diff_caches(&snapshot.caches, &current))
 }
 
 /// Get total cache size
 #[command]
 pub async fn get_total_cache_size() -> Result<u64, String> {
-    let caches = cache_scanner::scan_all_caches();
+    let caches = cache_scanner::scan_all_caches(&ScanOptions::default(), 0);
     Ok(caches.iter().map(|c| c.size).sum())
 }
 
@@ -38,17 +186,58 @@ mod tests {
 
     #[tokio::test]
     async fn test_scan_user_caches() {
-        let _ = scan_user_caches().await;
+        let _ = scan_user_caches(None, None, None, None).await;
         // Don't assert result contents as it depends on system state
     }
 
     #[tokio::test]
     async fn test_scan_system_caches() {
-        let _ = scan_system_caches().await;
+        let _ = scan_system_caches(None, None, None, None).await;
+    }
+
+    #[tokio::test]
+    async fn test_scan_shared_junk() {
+        let locations = scan_shared_junk().await.unwrap();
+        assert_eq!(locations.len(), 3);
     }
 
     #[tokio::test]
     async fn test_scan_all_caches() {
-        let _ = scan_all_caches().await;
+        let _ = scan_all_caches(None, None, None, None).await;
+    }
+
+    #[tokio::test]
+    async fn test_scan_caches_grouped() {
+        let grouped = scan_caches_grouped(Some(1), None, None, None).await.unwrap();
+        assert!(grouped.system.iter().all(|c| c.cache_type == cache_scanner::CacheType::System));
+    }
+
+    #[tokio::test]
+    async fn test_delete_caches_refuses_paths_outside_cache_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = temp_dir.path().join("com.example.App");
+        std::fs::create_dir(&cache_dir).unwrap();
+
+        let outcomes = delete_caches(vec![cache_dir.to_string_lossy().to_string()], None).await.unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes[0].result.is_err());
+        assert!(cache_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_then_diff_caches() {
+        assert!(snapshot_caches().await.is_ok());
+        assert!(diff_caches_against_snapshot().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_clear_scan_cache() {
+        assert!(clear_scan_cache().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scan_all_caches_shallow() {
+        let result = scan_all_caches(Some(1), None, None, None).await;
+        assert!(result.is_ok());
     }
 }
@@ -0,0 +1,465 @@
+use crate::commands::developer::{self, DeveloperCache};
+use crate::commands::system_info::{boot_volume_capacity, percent_of};
+use crate::path_guard::{ensure_at_or_within_roots, ensure_within_roots};
+use crate::removal::Removal;
+use crate::scanners::cache_scanner::{self, CacheEntry, CacheType};
+use crate::scanners::disk_size::get_path_size;
+use crate::scanners::file_scanner;
+use crate::scanners::app_scanner;
+use crate::scanners::hash_scanner;
+use crate::scanners::scan_options::ScanOptions;
+use crate::scanners::size_cache;
+use crate::scanners::trash_scanner;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+/// Where a cleanup item came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CleanupSource {
+    UserCache,
+    DeveloperCache,
+    Trash,
+}
+
+/// A single path the safe cleanup would remove
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupItem {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub source: CleanupSource,
+    pub description: String,
+}
+
+/// Everything considered safe to clean, for previewing before execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPlan {
+    pub items: Vec<CleanupItem>,
+    pub total_bytes: u64,
+}
+
+/// A path that could not be cleaned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupError {
+    pub path: String,
+    pub error: String,
+}
+
+/// Outcome of executing a cleanup plan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupResult {
+    pub bytes_freed: u64,
+    pub errors: Vec<CleanupError>,
+}
+
+/// Minimum size for files counted in the overview's large files estimate
+const OVERVIEW_LARGE_FILE_MB: u64 = 100;
+
+/// Minimum size for files counted in the overview's duplicates estimate
+const OVERVIEW_DUPLICATE_MB: u64 = 1;
+
+/// A cleanup category summarized on the home screen
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum OverviewCategory {
+    UserCaches,
+    DeveloperCaches,
+    Orphans,
+    Duplicates,
+    Trash,
+    LargeFiles,
+}
+
+/// How much one category could free
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryEstimate {
+    pub category: OverviewCategory,
+    pub item_count: usize,
+    pub bytes: u64,
+    pub percent_of_disk: f64, // Share of the boot volume's capacity
+}
+
+/// Freeable space per category, for the home screen summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupOverview {
+    pub categories: Vec<CategoryEstimate>,
+    pub total_bytes: u64,
+    pub percent_of_disk: f64,
+}
+
+/// Summarize a category from the sizes of its items
+fn estimate(category: OverviewCategory, sizes: impl IntoIterator<Item = u64>) -> CategoryEstimate {
+    let (item_count, bytes) = sizes
+        .into_iter()
+        .filter(|size| *size > 0)
+        .fold((0, 0), |(count, total), size| (count + 1, total + size));
+    CategoryEstimate {
+        category,
+        item_count,
+        bytes,
+        percent_of_disk: 0.0,
+    }
+}
+
+/// Combine category estimates, largest first, with each one's share of a
+/// disk of `disk_capacity` bytes
+fn build_overview(mut categories: Vec<CategoryEstimate>, disk_capacity: u64) -> CleanupOverview {
+    categories.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    for category in &mut categories {
+        category.percent_of_disk = percent_of(category.bytes, disk_capacity);
+    }
+    let total_bytes = categories.iter().map(|c| c.bytes).sum();
+    CleanupOverview {
+        categories,
+        total_bytes,
+        percent_of_disk: percent_of(total_bytes, disk_capacity),
+    }
+}
+
+/// Build a plan from scan results. System caches are never included.
+fn build_plan(user_caches: Vec<CacheEntry>, developer_caches: Vec<DeveloperCache>, trash_dirs: &[PathBuf]) -> CleanupPlan {
+    let mut items: Vec<CleanupItem> = user_caches
+        .into_iter()
+        .filter(|c| c.is_safe_to_delete && c.cache_type != CacheType::System && c.size > 0)
+        .map(|c| CleanupItem {
+            path: c.path,
+            name: c.name,
+            size: c.size,
+            source: CleanupSource::UserCache,
+            description: c.description,
+        })
+        .collect();
+
+    items.extend(
+        developer_caches
+            .into_iter()
+            .filter(|c| c.exists && c.safe_to_clean && c.size > 0)
+            .map(|c| CleanupItem {
+                path: c.path,
+                name: c.name,
+                size: c.size,
+                source: CleanupSource::DeveloperCache,
+                description: c.description,
+            }),
+    );
+
+    for dir in trash_dirs {
+        let size = trash_scanner::get_trash_size_in(std::slice::from_ref(dir));
+        if size > 0 {
+            items.push(CleanupItem {
+                path: dir.to_string_lossy().to_string(),
+                name: "Trash".to_string(),
+                size,
+                source: CleanupSource::Trash,
+                description: "Items in the trash".to_string(),
+            });
+        }
+    }
+
+    items.sort_by(|a, b| b.size.cmp(&a.size));
+    let total_bytes = items.iter().map(|i| i.size).sum();
+    CleanupPlan { items, total_bytes }
+}
+
+/// Folders a cleanup plan's items can come from
+struct CleanupRoots {
+    /// Trash folders, emptied in place
+    trash_dirs: Vec<PathBuf>,
+    /// Cache roots; items must lie inside one
+    cache_roots: Vec<PathBuf>,
+    /// Developer cache folders; items must be one of them or lie inside one
+    developer_roots: Vec<PathBuf>,
+}
+
+impl CleanupRoots {
+    fn current() -> Result<Self, String> {
+        let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+        Ok(CleanupRoots {
+            trash_dirs: trash_scanner::trash_directories(),
+            cache_roots: cache_scanner::cache_roots(),
+            developer_roots: developer::cleanable_cache_roots(&home),
+        })
+    }
+
+    /// Refuse a path the safe cleanup could never have planned
+    fn check(&self, target: &Path) -> Result<(), String> {
+        ensure_within_roots(target, &self.cache_roots)
+            .or_else(|_| ensure_at_or_within_roots(target, &self.developer_roots))
+            .map(|_| ())
+            .map_err(|_| format!("Refusing to clean {}: it is not part of the safe cleanup", target.display()))
+    }
+}
+
+/// Clean each path, collecting errors instead of stopping at the first failure.
+/// Trash directories are emptied in place; caches and developer caches are
+/// moved to the trash, and any other path is refused.
+fn execute_paths(paths: &[String], roots: &CleanupRoots) -> CleanupResult {
+    let mut result = CleanupResult::default();
+
+    for path in paths {
+        let target = Path::new(path);
+        if !target.exists() {
+            result.errors.push(CleanupError {
+                path: path.clone(),
+                error: "Path does not exist".to_string(),
+            });
+            continue;
+        }
+
+        if roots.trash_dirs.iter().any(|dir| dir == target) {
+            result.bytes_freed += trash_scanner::empty_trash_dirs(&[target.to_path_buf()]);
+            continue;
+        }
+        if let Err(error) = roots.check(target) {
+            result.errors.push(CleanupError { path: path.clone(), error });
+            continue;
+        }
+
+        let size = get_path_size(target);
+        match crate::audit::logged("move_to_trash", path, || Removal::Trash.remove(target)) {
+            Ok(()) => result.bytes_freed += size,
+            Err(error) => result.errors.push(CleanupError { path: path.clone(), error }),
+        }
+    }
+
+    result
+}
+
+/// Gather safe user caches, safe developer caches and trash contents into one plan
+#[command]
+pub async fn scan_safe_cleanup() -> Result<CleanupPlan, String> {
+    let user_caches = cache_scanner::scan_user_caches(&ScanOptions::default(), 0);
+    let developer_caches = developer::scan_developer_caches().await?;
+    let plan = build_plan(user_caches, developer_caches, &trash_scanner::trash_directories());
+    size_cache::save_global_cache();
+    Ok(plan)
+}
+
+/// Estimate how much each cleanup category could free, running the scans
+/// concurrently. Cache and developer folder sizes come from the size cache
+/// when their folders haven't changed. Caches count only what the safe
+/// cleanup would remove, and large files and duplicates use the default
+/// thresholds (100 MB and 1 MB) over the common directories. Each category
+/// also reports its share of the boot volume's capacity.
+#[command]
+pub async fn get_cleanup_overview() -> Result<CleanupOverview, String> {
+    let user_caches = tokio::task::spawn_blocking(|| {
+        let caches = cache_scanner::scan_user_caches(&ScanOptions::default(), 0);
+        estimate(
+            OverviewCategory::UserCaches,
+            caches
+                .into_iter()
+                .filter(|c| c.is_safe_to_delete && c.cache_type != CacheType::System)
+                .map(|c| c.size),
+        )
+    });
+    let orphans = tokio::task::spawn_blocking(|| {
+        estimate(OverviewCategory::Orphans, app_scanner::scan_orphan_files().into_iter().map(|o| o.size))
+    });
+    let duplicates = tokio::task::spawn_blocking(|| {
+        let groups = hash_scanner::scan_common_directories_for_duplicates(OVERVIEW_DUPLICATE_MB);
+        estimate(OverviewCategory::Duplicates, groups.into_iter().map(|g| g.total_wasted))
+    });
+    let trash = tokio::task::spawn_blocking(|| {
+        let dirs = trash_scanner::trash_directories();
+        estimate(OverviewCategory::Trash, dirs.iter().map(|d| trash_scanner::get_trash_size_in(std::slice::from_ref(d))))
+    });
+    let large_files = tokio::task::spawn_blocking(|| {
+        let files = file_scanner::scan_common_directories(OVERVIEW_LARGE_FILE_MB, None);
+        estimate(OverviewCategory::LargeFiles, files.into_iter().map(|f| f.size))
+    });
+
+    let (developer_caches, user_caches, orphans, duplicates, trash, large_files) = tokio::join!(
+        developer::scan_developer_caches(),
+        user_caches,
+        orphans,
+        duplicates,
+        trash,
+        large_files,
+    );
+    let developer_caches = estimate(
+        OverviewCategory::DeveloperCaches,
+        developer_caches?
+            .into_iter()
+            .filter(|c| c.exists && c.safe_to_clean)
+            .map(|c| c.size),
+    );
+
+    let join_error = |e: tokio::task::JoinError| e.to_string();
+    let overview = build_overview(vec![
+        user_caches.map_err(join_error)?,
+        developer_caches,
+        orphans.map_err(join_error)?,
+        duplicates.map_err(join_error)?,
+        trash.map_err(join_error)?,
+        large_files.map_err(join_error)?,
+    ], boot_volume_capacity());
+    size_cache::save_global_cache();
+    Ok(overview)
+}
+
+/// Clean the selected paths from a plan, returning bytes freed and per-item
+/// errors. Paths outside the trash, cache and developer cache folders are refused.
+#[command]
+pub async fn execute_cleanup_plan(selected_paths: Vec<String>) -> Result<CleanupResult, String> {
+    Ok(execute_paths(&selected_paths, &CleanupRoots::current()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn cache(name: &str, cache_type: CacheType, safe: bool) -> CacheEntry {
+        CacheEntry {
+            path: format!("/tmp/{}", name),
+            name: name.to_string(),
+            size: 100,
+            cache_type,
+            is_developer_related: false,
+            is_safe_to_delete: safe,
+            description: String::new(),
+            possibly_corrupt: false,
+            truncated: false,
+        }
+    }
+
+    fn developer_cache(name: &str, safe: bool) -> DeveloperCache {
+        DeveloperCache {
+            name: name.to_string(),
+            path: format!("/tmp/{}", name),
+            size: 50,
+            apparent_size: 50,
+            description: String::new(),
+            exists: true,
+            safe_to_clean: safe,
+            docker_usage: None,
+        }
+    }
+
+    #[test]
+    fn test_estimate_skips_empty_items() {
+        let estimate = estimate(OverviewCategory::Trash, vec![0, 10, 20]);
+        assert_eq!(estimate.item_count, 2);
+        assert_eq!(estimate.bytes, 30);
+    }
+
+    #[test]
+    fn test_build_overview_sorts_and_totals() {
+        let overview = build_overview(vec![
+            estimate(OverviewCategory::UserCaches, vec![10]),
+            estimate(OverviewCategory::LargeFiles, vec![500]),
+            estimate(OverviewCategory::Orphans, Vec::new()),
+        ], 1000);
+        assert_eq!(overview.total_bytes, 510);
+        assert_eq!(overview.percent_of_disk, 51.0);
+        assert_eq!(overview.categories[0].percent_of_disk, 50.0);
+        assert_eq!(overview.categories[0].category, OverviewCategory::LargeFiles);
+        assert_eq!(overview.categories[2].item_count, 0);
+    }
+
+    #[test]
+    fn test_build_plan_filters_unsafe_items() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let trash = temp_dir.path().join(".Trash");
+        fs::create_dir(&trash).unwrap();
+        fs::write(trash.join("old.txt"), "data").unwrap();
+
+        let plan = build_plan(
+            vec![
+                cache("com.example.App", CacheType::Application, true),
+                cache("com.apple.System", CacheType::System, true),
+                cache("unknown", CacheType::Unknown, false),
+            ],
+            vec![developer_cache("npm", true), developer_cache("Docker", false)],
+            &[trash],
+        );
+
+        let sources: Vec<CleanupSource> = plan.items.iter().map(|i| i.source.clone()).collect();
+        assert_eq!(plan.items.len(), 3);
+        assert!(sources.contains(&CleanupSource::UserCache));
+        assert!(sources.contains(&CleanupSource::DeveloperCache));
+        assert!(sources.contains(&CleanupSource::Trash));
+        assert_eq!(plan.total_bytes, plan.items.iter().map(|i| i.size).sum::<u64>());
+    }
+
+    #[test]
+    fn test_execute_paths_continues_after_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let trash = temp_dir.path().join(".Trash");
+        fs::create_dir(&trash).unwrap();
+        fs::write(trash.join("old.txt"), "data").unwrap();
+
+        let missing = temp_dir.path().join("missing").to_string_lossy().to_string();
+        let roots = CleanupRoots {
+            trash_dirs: vec![trash.clone()],
+            cache_roots: Vec::new(),
+            developer_roots: Vec::new(),
+        };
+        let result = execute_paths(&[missing.clone(), trash.to_string_lossy().to_string()], &roots);
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, missing);
+        assert!(result.bytes_freed > 0);
+        assert!(trash.exists());
+        assert_eq!(fs::read_dir(&trash).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_execute_paths_refuses_paths_outside_the_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let caches = temp_dir.path().join("Caches");
+        let npm = temp_dir.path().join(".npm");
+        let documents = temp_dir.path().join("Documents");
+        for dir in [caches.join("com.example.App"), npm.clone(), documents.clone()] {
+            fs::create_dir_all(&dir).unwrap();
+        }
+        let roots = CleanupRoots {
+            trash_dirs: Vec::new(),
+            cache_roots: vec![caches.clone()],
+            developer_roots: vec![npm.clone()],
+        };
+
+        assert!(roots.check(&caches.join("com.example.App")).is_ok());
+        assert!(roots.check(&npm).is_ok());
+        assert!(roots.check(&caches).is_err());
+        assert!(roots.check(&caches.join("..").join("Documents")).is_err());
+
+        let result = execute_paths(&[documents.to_string_lossy().to_string()], &roots);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.bytes_freed, 0);
+        assert!(documents.exists());
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_execute_paths_keeps_both_items_with_the_same_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let caches = temp_dir.path().canonicalize().unwrap().join("Caches");
+        let name = format!("invoice-{}.pdf", std::process::id());
+        let first = caches.join("com.example.First");
+        let second = caches.join("com.example.Second");
+        for dir in [&first, &second] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join(&name), dir.to_string_lossy().as_bytes()).unwrap();
+        }
+        let roots = CleanupRoots {
+            trash_dirs: Vec::new(),
+            cache_roots: vec![caches.clone()],
+            developer_roots: Vec::new(),
+        };
+
+        let paths = [first.join(&name), second.join(&name)].map(|p| p.to_string_lossy().to_string());
+        let result = execute_paths(&paths, &roots);
+
+        assert!(result.errors.is_empty());
+        assert!(crate::removal::take_from_trash(&first, &name));
+        assert!(crate::removal::take_from_trash(&second, &name));
+    }
+
+    #[tokio::test]
+    async fn test_scan_safe_cleanup() {
+        let plan = scan_safe_cleanup().await.unwrap();
+        assert!(plan.items.iter().all(|i| i.size > 0));
+    }
+}
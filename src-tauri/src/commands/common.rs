@@ -0,0 +1,224 @@
+use crate::batch::{self, PathSize};
+use crate::commands::developer;
+use crate::process::run_command_with_timeout;
+use crate::scanners::directory_analyzer::{self, DirEntryUsage};
+use crate::scanners::disk_size::get_path_size_with_control;
+use crate::scanners::scan_control;
+use crate::scanners::size_cache;
+use crate::scanners::storage_breakdown::{self, StorageBreakdown};
+use std::sync::Arc;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::command;
+
+/// How long to wait for Finder to be asked to reveal a path
+const REVEAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Fail with a friendly message if `path` has gone away since the scan
+fn ensure_exists(path: &str) -> Result<(), String> {
+    if Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(format!("{} no longer exists", path))
+    }
+}
+
+/// The folder to open for `path`: the path itself for a directory, otherwise its parent
+fn enclosing_dir(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+/// Run `open` with the given arguments, describing any failure with `app`
+fn run_open(args: &[&OsStr], app: &str) -> Result<(), String> {
+    let output = run_command_with_timeout("open", args, REVEAL_TIMEOUT)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to open {}: {}", app, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Reveal a file or folder in Finder, from any result view
+#[command]
+pub async fn reveal_in_finder(path: String) -> Result<(), String> {
+    ensure_exists(&path)?;
+    run_open(&[OsStr::new("-R"), OsStr::new(&path)], "Finder")
+}
+
+/// Open a folder in Finder, or the folder containing a file, without selecting anything
+#[command]
+pub async fn open_path(path: String) -> Result<(), String> {
+    ensure_exists(&path)?;
+    let dir = enclosing_dir(Path::new(&path));
+    run_open(&[dir.as_os_str()], "Finder")
+}
+
+/// Open a new Terminal window at a folder, or at the folder containing a file
+#[command]
+pub async fn open_in_terminal(path: String) -> Result<(), String> {
+    ensure_exists(&path)?;
+    let dir = enclosing_dir(Path::new(&path));
+    run_open(&[OsStr::new("-a"), OsStr::new("Terminal"), dir.as_os_str()], "Terminal")
+}
+
+/// On-disk size of each path (file or folder) in one call, computed in parallel.
+/// Paths that can't be read are returned with an error instead of failing the batch.
+#[command]
+pub async fn get_sizes(paths: Vec<String>) -> Result<Vec<PathSize>, String> {
+    let sizes = tokio::task::spawn_blocking(move || batch::sizes_of(paths))
+        .await
+        .map_err(|e| e.to_string())?;
+    size_cache::save_global_cache();
+    Ok(sizes)
+}
+
+/// On-disk size of a single file or folder tree, e.g. after drilling into a folder.
+/// An empty folder is 0. `scan_id` registers the computation so `cancel_scan`
+/// can stop it on a very large tree.
+#[command]
+pub async fn get_path_size(path: String, scan_id: Option<String>) -> Result<u64, String> {
+    ensure_exists(&path)?;
+    let registration = scan_id.as_deref().map(scan_control::register);
+    let control = registration.as_ref().map(|r| r.control()).unwrap_or_default();
+
+    let size = {
+        let control = Arc::clone(&control);
+        tokio::task::spawn_blocking(move || get_path_size_with_control(Path::new(&path), &control))
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    size_cache::save_global_cache();
+
+    match size {
+        Some(size) => Ok(size),
+        None if control.is_cancelled() => Err("Scan cancelled".to_string()),
+        None => Err("Could not read path".to_string()),
+    }
+}
+
+/// Size of each immediate child of a folder, largest first, for a drill-down
+/// "disk space analyzer" view. Children are sized in parallel. `scan_id`
+/// registers the analysis so `cancel_scan` can stop it.
+#[command]
+pub async fn analyze_directory(path: String, scan_id: Option<String>) -> Result<Vec<DirEntryUsage>, String> {
+    ensure_exists(&path)?;
+    let registration = scan_id.as_deref().map(scan_control::register);
+    let control = registration.as_ref().map(|r| r.control()).unwrap_or_default();
+
+    let entries = {
+        let control = Arc::clone(&control);
+        tokio::task::spawn_blocking(move || directory_analyzer::analyze_directory(Path::new(&path), &control))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+    size_cache::save_global_cache();
+
+    if control.is_cancelled() {
+        return Err("Scan cancelled".to_string());
+    }
+    Ok(entries)
+}
+
+/// Space used by broad buckets (Applications, Documents, Photos, Developer,
+/// System Caches and Trash) for the dashboard's at-a-glance view. Buckets are
+/// sized concurrently; `scan_id` registers the scan so `cancel_scan` can stop it.
+#[command]
+pub async fn get_storage_breakdown(scan_id: Option<String>) -> Result<StorageBreakdown, String> {
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    let registration = scan_id.as_deref().map(scan_control::register);
+    let control = registration.as_ref().map(|r| r.control()).unwrap_or_default();
+
+    let breakdown = {
+        let control = Arc::clone(&control);
+        tokio::task::spawn_blocking(move || {
+            let buckets = storage_breakdown::bucket_roots(&home, developer::cleanable_cache_roots(&home));
+            storage_breakdown::storage_breakdown(&buckets, &control)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    };
+    size_cache::save_global_cache();
+
+    if control.is_cancelled() {
+        return Err("Scan cancelled".to_string());
+    }
+    Ok(breakdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn test_reveal_in_finder() {
+        // Create a temp file
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("reveal_test.txt");
+        let mut file = std::fs::File::create(&file_path).unwrap();
+        writeln!(file, "reveal me").unwrap();
+        drop(file);
+
+        // On macOS, this should work; on CI/Linux it may fail but shouldn't panic
+        let _ = reveal_in_finder(file_path.to_string_lossy().to_string()).await;
+    }
+
+    #[tokio::test]
+    async fn test_get_sizes() {
+        let sizes = get_sizes(vec!["/nonexistent/path".to_string()]).await.unwrap();
+        assert_eq!(sizes.len(), 1);
+        assert!(sizes[0].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_path_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().to_string_lossy().to_string();
+        assert_eq!(get_path_size(dir.clone(), None).await.unwrap(), 0);
+
+        std::fs::write(temp_dir.path().join("a.bin"), vec![1u8; 10_000]).unwrap();
+        let file = temp_dir.path().join("a.bin").to_string_lossy().to_string();
+        assert!(get_path_size(file, None).await.unwrap() >= 10_000);
+
+        let missing = get_path_size("/nonexistent/path".to_string(), None).await;
+        assert!(missing.unwrap_err().contains("no longer exists"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("a.bin"), vec![1u8; 10_000]).unwrap();
+
+        let entries = analyze_directory(temp_dir.path().to_string_lossy().to_string(), None).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(analyze_directory("/nonexistent/path".to_string(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reveal_in_finder_missing_path() {
+        let result = reveal_in_finder("/nonexistent/path/file.txt".to_string()).await;
+        assert!(result.unwrap_err().contains("no longer exists"));
+    }
+
+    #[test]
+    fn test_enclosing_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("notes.txt");
+        std::fs::write(&file_path, "x").unwrap();
+
+        assert_eq!(enclosing_dir(&file_path), temp_dir.path());
+        assert_eq!(enclosing_dir(temp_dir.path()), temp_dir.path());
+    }
+
+    #[tokio::test]
+    async fn test_open_helpers_missing_path() {
+        let missing = "/nonexistent/path/file.txt".to_string();
+        assert!(open_path(missing.clone()).await.unwrap_err().contains("no longer exists"));
+        assert!(open_in_terminal(missing).await.unwrap_err().contains("no longer exists"));
+    }
+}
@@ -0,0 +1,539 @@
+use crate::commands::system_info::{UnitSystem, DEFAULT_HISTORY_RETENTION_DAYS};
+use crate::scanners::file_scanner::FileCategory;
+use crate::scanners::scan_options::{ScanOptions, DEFAULT_SCAN_CONCURRENCY};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tauri::command;
+
+/// File under the app support dir holding the user's settings
+const SETTINGS_FILE: &str = "settings.json";
+
+/// A developer cache folder the user added to the developer scan
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomDeveloperCache {
+    pub name: String,
+    /// Folder whose contents are cleaned; `~/` is expanded
+    pub path: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// User settings persisted between launches
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Default minimum size for large-file scans
+    pub large_file_min_size_mb: u64,
+    /// Default minimum size for duplicate scans
+    pub duplicate_min_size_mb: u64,
+    /// Folders scanned by the "common" large-file scan; `~/` is expanded. Each
+    /// must be inside the home folder or on a volume in `/Volumes`.
+    pub scan_roots: Vec<String>,
+    /// Folders scanned by the "common" duplicate scan, with the same rules as `scan_roots`
+    pub duplicate_scan_roots: Vec<String>,
+    /// Glob patterns excluded from every scan, on top of those a scan is given;
+    /// `~/` is expanded
+    pub exclude_patterns: Vec<String>,
+    /// Paths that must never be deleted, nor any folder containing them; `~/` is expanded
+    pub protected_paths: Vec<String>,
+    /// Unit system used when formatting sizes
+    pub unit_system: UnitSystem,
+    /// Ask for confirmation before deleting anything
+    pub confirm_before_delete: bool,
+    /// Days of disk usage history to keep
+    pub disk_history_retention_days: u64,
+    /// User category for file extensions the built-in lists don't cover, or
+    /// categorize differently. Keys are lowercase and without a leading dot.
+    pub extension_categories: BTreeMap<String, FileCategory>,
+    /// Extra developer cache folders scanned and cleaned alongside the built-in ones
+    pub custom_developer_caches: Vec<CustomDeveloperCache>,
+    /// Folders the common large-file and duplicate scans read at once. Lower
+    /// it for spinning disks, where parallel reads cause seek thrashing.
+    pub max_scan_concurrency: usize,
+    /// Cache folders the user has marked safe (true) or unsafe (false) to
+    /// delete, overriding the built-in heuristic. Keys are absolute paths.
+    pub cache_safety_overrides: BTreeMap<String, bool>,
+    /// Send deleted caches, browser caches, files, duplicates, developer cache
+    /// contents, DerivedData folders and launch items to the Trash instead of
+    /// deleting them permanently
+    pub safe_mode: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            large_file_min_size_mb: 100,
+            duplicate_min_size_mb: 1,
+            scan_roots: ["Downloads", "Desktop", "Documents", "Movies", "Music", "Pictures"]
+                .iter()
+                .map(|d| format!("~/{}", d))
+                .collect(),
+            duplicate_scan_roots: ["Downloads", "Desktop", "Documents", "Pictures"]
+                .iter()
+                .map(|d| format!("~/{}", d))
+                .collect(),
+            exclude_patterns: Vec::new(),
+            protected_paths: Vec::new(),
+            unit_system: UnitSystem::default(),
+            confirm_before_delete: true,
+            disk_history_retention_days: DEFAULT_HISTORY_RETENTION_DAYS,
+            extension_categories: BTreeMap::new(),
+            custom_developer_caches: Vec::new(),
+            max_scan_concurrency: DEFAULT_SCAN_CONCURRENCY,
+            cache_safety_overrides: BTreeMap::new(),
+            safe_mode: true,
+        }
+    }
+}
+
+/// Load settings from `path`, using defaults if the file is missing or malformed.
+/// Keys no longer known (say from a newer or older version) are ignored rather
+/// than discarding every other setting.
+fn load_from(path: &Path) -> Settings {
+    crate::storage::read_json(path).unwrap_or_default()
+}
+
+/// Merge the fields in `partial` into the settings at `path` and save atomically
+fn update_at(path: &Path, partial: Value) -> Result<Settings, String> {
+    let Value::Object(changes) = partial else {
+        return Err("Settings update must be an object".to_string());
+    };
+
+    let mut merged = serde_json::to_value(load_from(path)).map_err(|e| e.to_string())?;
+    if let Value::Object(current) = &mut merged {
+        if let Some(unknown) = changes.keys().find(|key| !current.contains_key(*key)) {
+            return Err(format!("Invalid settings: unknown setting '{}'", unknown));
+        }
+        current.extend(changes);
+    }
+    let settings: Settings = serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    for root in settings.scan_roots.iter().chain(&settings.duplicate_scan_roots) {
+        resolve_scan_root(root, &home)?;
+    }
+    for protected in &settings.protected_paths {
+        resolve_protected_path(protected, &home)?;
+    }
+    ScanOptions::default().with_exclude(&settings.exclude_patterns)?;
+
+    crate::storage::write_json_atomic(path, &settings)?;
+    Ok(settings)
+}
+
+/// Expand a leading `~` in a configured path against `home`
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    let trimmed = path.trim();
+    match trimmed.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None if trimmed == "~" => home.to_path_buf(),
+        None => PathBuf::from(trimmed),
+    }
+}
+
+/// Expand a configured scan root against `home`, checking it lies inside the
+/// home folder or on a mounted volume under `/Volumes`
+fn resolve_scan_root(root: &str, home: &Path) -> Result<PathBuf, String> {
+    let path = expand_home(root, home);
+    if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+        return Err(format!("Scan root must be an absolute path: '{}'", root));
+    }
+    let on_volume = path
+        .strip_prefix("/Volumes")
+        .is_ok_and(|rest| rest.components().next().is_some());
+    if !path.starts_with(home) && !on_volume {
+        return Err(format!(
+            "Scan root must be inside your home folder or on a volume in /Volumes: '{}'",
+            root
+        ));
+    }
+    Ok(path)
+}
+
+/// Expand configured scan roots. Invalid roots (possible only if the settings
+/// file was edited by hand) are skipped.
+fn resolve_scan_roots(roots: &[String]) -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    roots
+        .iter()
+        .filter_map(|root| match resolve_scan_root(root, &home) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                log::warn!("Ignoring {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Expand a protected path against `home`, checking it is absolute
+fn resolve_protected_path(path: &str, home: &Path) -> Result<PathBuf, String> {
+    let resolved = expand_home(path, home);
+    if !resolved.is_absolute() || resolved.components().any(|c| c == Component::ParentDir) {
+        return Err(format!("Protected path must be an absolute path: '{}'", path));
+    }
+    Ok(resolved)
+}
+
+/// Paths the user protected from deletion, from `settings.protected_paths`.
+/// Invalid entries (possible only if the settings file was edited by hand) are skipped.
+pub fn protected_paths(settings: &Settings) -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    settings
+        .protected_paths
+        .iter()
+        .filter_map(|path| resolve_protected_path(path, &home).ok())
+        .collect()
+}
+
+/// The exclude patterns from settings followed by a scan's own `extra` patterns
+pub fn exclude_patterns_with(extra: Option<Vec<String>>) -> Vec<String> {
+    let mut patterns = load_settings().exclude_patterns;
+    patterns.extend(extra.unwrap_or_default());
+    patterns
+}
+
+/// Scan options excluding the patterns from `settings`, for scans that take no
+/// patterns of their own. Invalid patterns (possible only if the settings file
+/// was edited by hand) are ignored.
+pub fn settings_scan_options(settings: &Settings) -> ScanOptions {
+    ScanOptions::default().with_exclude(&settings.exclude_patterns).unwrap_or_else(|e| {
+        log::warn!("Ignoring exclude patterns from settings: {}", e);
+        ScanOptions::default()
+    })
+}
+
+/// Folders the common large-file scan reads, from `settings.scan_roots`
+pub fn common_scan_roots(settings: &Settings) -> Vec<PathBuf> {
+    resolve_scan_roots(&settings.scan_roots)
+}
+
+/// Folders the common duplicate scan reads, from `settings.duplicate_scan_roots`
+pub fn duplicate_scan_roots(settings: &Settings) -> Vec<PathBuf> {
+    resolve_scan_roots(&settings.duplicate_scan_roots)
+}
+
+/// Add `root` to the common scan roots in the settings at `path`, or remove it
+/// if `add` is false. `duplicates` picks the duplicate scan's list instead of
+/// the large-file scan's.
+fn set_scan_root_at(path: &Path, root: &str, add: bool, duplicates: bool, home: &Path) -> Result<Settings, String> {
+    let root = root.trim();
+    let resolved = resolve_scan_root(root, home)?;
+    let mut settings = load_from(path);
+    let roots = if duplicates {
+        &mut settings.duplicate_scan_roots
+    } else {
+        &mut settings.scan_roots
+    };
+    let existing = roots
+        .iter()
+        .position(|r| resolve_scan_root(r, home).is_ok_and(|p| p == resolved));
+    match (add, existing) {
+        (true, None) => roots.push(root.to_string()),
+        (false, Some(index)) => {
+            roots.remove(index);
+        }
+        _ => {}
+    }
+    crate::storage::write_json_atomic(path, &settings)?;
+    Ok(settings)
+}
+
+/// Lowercase `extension` and strip a leading dot, rejecting empty or path-like values
+fn normalize_extension(extension: &str) -> Result<String, String> {
+    let ext = extension.trim().trim_start_matches('.').to_lowercase();
+    if ext.is_empty() || ext.contains(['/', '.']) || ext.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid file extension: '{}'", extension));
+    }
+    Ok(ext)
+}
+
+/// Map `extension` to `category` in the settings at `path`, or remove its mapping if `category` is None
+fn set_extension_category_at(path: &Path, extension: &str, category: Option<FileCategory>) -> Result<Settings, String> {
+    let ext = normalize_extension(extension)?;
+    let mut settings = load_from(path);
+    match category {
+        Some(category) => {
+            settings.extension_categories.insert(ext, category);
+        }
+        None => {
+            settings.extension_categories.remove(&ext);
+        }
+    }
+    crate::storage::write_json_atomic(path, &settings)?;
+    Ok(settings)
+}
+
+/// Mark the cache at `cache_path` safe or unsafe to delete in the settings at
+/// `path`, or drop its override if `safe` is None
+fn set_cache_safety_at(path: &Path, cache_path: &str, safe: Option<bool>) -> Result<Settings, String> {
+    let trimmed = cache_path.trim();
+    let key = if trimmed.len() > 1 { trimmed.trim_end_matches('/') } else { trimmed };
+    if !Path::new(key).is_absolute() {
+        return Err(format!("Cache path must be absolute: '{}'", cache_path));
+    }
+    let mut settings = load_from(path);
+    match safe {
+        Some(safe) => {
+            settings.cache_safety_overrides.insert(key.to_string(), safe);
+        }
+        None => {
+            settings.cache_safety_overrides.remove(key);
+        }
+    }
+    crate::storage::write_json_atomic(path, &settings)?;
+    Ok(settings)
+}
+
+/// Current settings, or defaults if none are saved
+pub fn load_settings() -> Settings {
+    crate::storage::app_support_file(SETTINGS_FILE)
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+/// Get the current settings
+#[command]
+pub async fn get_settings() -> Result<Settings, String> {
+    Ok(load_settings())
+}
+
+/// Update some settings. Fields not present in `partial` keep their current values.
+#[command]
+pub async fn update_settings(partial: Value) -> Result<Settings, String> {
+    update_at(&crate::storage::app_support_file(SETTINGS_FILE)?, partial)
+}
+
+/// Categorize files with `extension` as `category` (a category name such as
+/// "Image" or "Project") in future scans
+#[command]
+pub async fn add_extension_category(extension: String, category: String) -> Result<Settings, String> {
+    let category = FileCategory::from_name(&category).ok_or_else(|| format!("Unknown file category: '{}'", category))?;
+    set_extension_category_at(&crate::storage::app_support_file(SETTINGS_FILE)?, &extension, Some(category))
+}
+
+/// Remove the user mapping for `extension`, falling back to the built-in category
+#[command]
+pub async fn remove_extension_category(extension: String) -> Result<Settings, String> {
+    set_extension_category_at(&crate::storage::app_support_file(SETTINGS_FILE)?, &extension, None)
+}
+
+/// Include `root` (e.g. "~/Developer") in the common large-file and duplicate scans
+#[command]
+pub async fn add_scan_root(root: String, duplicates: Option<bool>) -> Result<Settings, String> {
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    let path = crate::storage::app_support_file(SETTINGS_FILE)?;
+    set_scan_root_at(&path, &root, true, duplicates.unwrap_or(false), &home)
+}
+
+/// Stop scanning `root` (e.g. "~/Documents") in the common scans
+#[command]
+pub async fn remove_scan_root(root: String, duplicates: Option<bool>) -> Result<Settings, String> {
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    let path = crate::storage::app_support_file(SETTINGS_FILE)?;
+    set_scan_root_at(&path, &root, false, duplicates.unwrap_or(false), &home)
+}
+
+/// Always mark the cache folder at `path` as safe or unsafe to delete,
+/// whatever the built-in heuristic says
+#[command]
+pub async fn set_cache_safety_override(path: String, safe: bool) -> Result<Settings, String> {
+    set_cache_safety_at(&crate::storage::app_support_file(SETTINGS_FILE)?, &path, Some(safe))
+}
+
+/// Go back to the built-in safety heuristic for the cache folder at `path`
+#[command]
+pub async fn clear_cache_safety_override(path: String) -> Result<Settings, String> {
+    set_cache_safety_at(&crate::storage::app_support_file(SETTINGS_FILE)?, &path, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_load_defaults_when_missing_or_malformed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+        assert_eq!(load_from(&path), Settings::default());
+
+        std::fs::write(&path, "{ broken").unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+    }
+
+    #[test]
+    fn test_partial_file_fills_defaults() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+        std::fs::write(&path, r#"{"large_file_min_size_mb": 500}"#).unwrap();
+
+        let settings = load_from(&path);
+        assert_eq!(settings.large_file_min_size_mb, 500);
+        assert!(settings.confirm_before_delete);
+    }
+
+    #[test]
+    fn test_load_ignores_unknown_keys() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+        std::fs::write(
+            &path,
+            r#"{"safe_mode": false, "protected_paths": ["~/Work"], "removed_setting": true}"#,
+        )
+        .unwrap();
+
+        let settings = load_from(&path);
+        assert!(!settings.safe_mode);
+        assert_eq!(settings.protected_paths, vec!["~/Work".to_string()]);
+
+        let updated = update_at(&path, json!({"confirm_before_delete": false})).unwrap();
+        assert!(!updated.safe_mode);
+        assert_eq!(load_from(&path), updated);
+    }
+
+    #[test]
+    fn test_update_merges_and_persists() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        update_at(&path, json!({"unit_system": "Decimal"})).unwrap();
+        let settings = update_at(&path, json!({"confirm_before_delete": false})).unwrap();
+
+        assert_eq!(settings.unit_system, UnitSystem::Decimal);
+        assert!(!settings.confirm_before_delete);
+        assert_eq!(load_from(&path), settings);
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_values() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        assert!(update_at(&path, json!({"large_file_min_size_mb": "big"})).is_err());
+        assert!(update_at(&path, json!({"no_such_setting": 1})).is_err());
+        assert!(update_at(&path, json!([1, 2])).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_update_validates_scan_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        assert!(update_at(&path, json!({"scan_roots": ["/etc"]})).is_err());
+        assert!(update_at(&path, json!({"duplicate_scan_roots": ["/etc"]})).is_err());
+        assert!(!path.exists());
+        let settings = update_at(&path, json!({"scan_roots": ["~/Developer", "/Volumes/Backup"]})).unwrap();
+        assert_eq!(common_scan_roots(&settings).len(), 2);
+        assert_eq!(duplicate_scan_roots(&settings).len(), 4);
+    }
+
+    #[test]
+    fn test_update_validates_protected_paths_and_exclude_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        assert!(update_at(&path, json!({"protected_paths": ["Documents"]})).is_err());
+        assert!(update_at(&path, json!({"exclude_patterns": ["**/[unclosed"]})).is_err());
+        assert!(!path.exists());
+
+        let settings = update_at(
+            &path,
+            json!({"protected_paths": ["~/Documents/Taxes", "/Volumes/Backup"], "exclude_patterns": ["**/node_modules"]}),
+        )
+        .unwrap();
+        let protected = protected_paths(&settings);
+        assert_eq!(protected.len(), 2);
+        assert!(protected[0].ends_with("Documents/Taxes"));
+        assert!(settings_scan_options(&settings).is_excluded(Path::new("/Users/me/app/node_modules")));
+    }
+
+    #[test]
+    fn test_resolve_scan_root() {
+        let home = Path::new("/Users/me");
+        assert_eq!(resolve_scan_root("~/Developer", home).unwrap(), home.join("Developer"));
+        assert_eq!(resolve_scan_root("/Users/me/Music", home).unwrap(), home.join("Music"));
+        assert_eq!(resolve_scan_root("~", home).unwrap(), home);
+        assert!(resolve_scan_root("/Volumes/Backup/Photos", home).is_ok());
+        assert!(resolve_scan_root("/Volumes", home).is_err());
+        assert!(resolve_scan_root("/Users/other", home).is_err());
+        assert!(resolve_scan_root("~/../other", home).is_err());
+        assert!(resolve_scan_root("Documents", home).is_err());
+    }
+
+    #[test]
+    fn test_set_scan_root_at() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+        let home = Path::new("/Users/me");
+
+        let settings = set_scan_root_at(&path, "~/Developer", true, false, home).unwrap();
+        assert!(settings.scan_roots.contains(&"~/Developer".to_string()));
+        assert!(!settings.duplicate_scan_roots.contains(&"~/Developer".to_string()));
+        let settings = set_scan_root_at(&path, "/Users/me/Developer", true, false, home).unwrap();
+        assert_eq!(settings.scan_roots.iter().filter(|r| r.ends_with("Developer")).count(), 1);
+
+        let settings = set_scan_root_at(&path, "/Users/me/Documents", false, false, home).unwrap();
+        assert!(!settings.scan_roots.contains(&"~/Documents".to_string()));
+        assert!(settings.duplicate_scan_roots.contains(&"~/Documents".to_string()));
+        assert_eq!(load_from(&path), settings);
+        assert!(set_scan_root_at(&path, "/tmp", true, false, home).is_err());
+
+        let settings = set_scan_root_at(&path, "~/Music", true, true, home).unwrap();
+        assert!(settings.duplicate_scan_roots.contains(&"~/Music".to_string()));
+    }
+
+    #[test]
+    fn test_set_extension_category_at() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        set_extension_category_at(&path, ".RAF", Some(FileCategory::Image)).unwrap();
+        let settings = set_extension_category_at(&path, "blend", Some(FileCategory::Project)).unwrap();
+        assert_eq!(settings.extension_categories.get("raf"), Some(&FileCategory::Image));
+        assert_eq!(load_from(&path), settings);
+
+        let settings = set_extension_category_at(&path, "raf", None).unwrap();
+        assert!(!settings.extension_categories.contains_key("raf"));
+        assert_eq!(settings.extension_categories.len(), 1);
+    }
+
+    #[test]
+    fn test_normalize_extension_rejects_invalid() {
+        assert_eq!(normalize_extension(" .DNG ").unwrap(), "dng");
+        assert!(normalize_extension("").is_err());
+        assert!(normalize_extension(".").is_err());
+        assert!(normalize_extension("tar.gz").is_err());
+        assert!(normalize_extension("a/b").is_err());
+    }
+
+    #[test]
+    fn test_set_cache_safety_at() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        let settings = set_cache_safety_at(&path, "/Library/Caches/com.example/", Some(true)).unwrap();
+        assert_eq!(settings.cache_safety_overrides.get("/Library/Caches/com.example"), Some(&true));
+        assert_eq!(load_from(&path), settings);
+
+        let settings = set_cache_safety_at(&path, "/Library/Caches/com.example", None).unwrap();
+        assert!(settings.cache_safety_overrides.is_empty());
+        assert!(set_cache_safety_at(&path, "Caches/relative", Some(false)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_add_extension_category_rejects_unknown_category() {
+        assert!(add_extension_category("blend".to_string(), "Spreadsheet".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_settings() {
+        assert!(get_settings().await.is_ok());
+    }
+}
@@ -1,242 +1,626 @@
+use crate::commands::config::{load_settings, CustomDeveloperCache};
+use crate::path_guard::{ensure_at_or_within_roots, ensure_not_protected};
+use crate::process::{run_command_with_timeout, CommandError};
+use crate::removal::Removal;
+use crate::scanners::derived_data_scanner::{self, DerivedDataProject};
+use crate::scanners::disk_size::{get_directory_size, get_directory_sizes};
+use crate::scanners::scan_control::{self, ScanControl};
+use crate::scanners::size_cache;
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::command;
-use walkdir::WalkDir;
+use tauri::ipc::Channel;
+use tokio::task::JoinSet;
 
 /// Developer cache location
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeveloperCache {
     pub name: String,
     pub path: String,
-    pub size: u64,
+    pub size: u64, // On disk
+    #[serde(default)]
+    pub apparent_size: u64, // Sum of file lengths; larger than `size` for sparse files
     pub description: String,
     pub exists: bool,
     pub safe_to_clean: bool,
+    /// Breakdown from `docker system df`, only set on the Docker entry when Docker is running
+    #[serde(default)]
+    pub docker_usage: Option<DockerUsage>,
 }
 
-/// Calculate directory size using actual disk blocks (handles sparse files correctly)
-fn get_directory_size(path: &PathBuf) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| {
-            // Use blocks * block_size for actual disk usage on Unix
-            // This correctly handles sparse files like Docker.raw
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::MetadataExt;
-                // blocks are in 512-byte units
-                m.blocks() * 512
-            }
-            #[cfg(not(unix))]
-            {
-                m.len()
-            }
-        })
-        .sum()
+/// Sent as each developer cache location finishes sizing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeveloperCacheProgress {
+    pub name: String,
+    pub size: u64,
+    pub completed: usize,
+    pub total: usize,
 }
 
-/// Calculate apparent size (for comparison/display when needed)
-#[allow(dead_code)]
-fn get_apparent_size(path: &PathBuf) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| m.len())
-        .sum()
+/// One row of `docker system df`: images, containers, local volumes or build cache
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DockerUsageCategory {
+    pub kind: String,
+    pub total_count: u64,
+    pub active: u64,
+    pub size: u64,
+    pub reclaimable: u64,
+}
+
+/// Something `brew cleanup` would remove: an old download, outdated keg or log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HomebrewCleanupItem {
+    pub path: String,
+    pub size: u64,
+    pub file_count: Option<u64>, // Only reported for folders
 }
 
+/// What `brew cleanup` would remove and roughly how much it would free
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HomebrewCleanupReport {
+    pub items: Vec<HomebrewCleanupItem>,
+    pub total_size: u64,
+}
+
+/// Docker's own view of its disk usage and how much pruning would free
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DockerUsage {
+    pub categories: Vec<DockerUsageCategory>,
+    pub total_size: u64,
+    pub total_reclaimable: u64,
+}
+
+/// Where to look for the Docker CLI; GUI apps don't inherit the shell's PATH
+const DOCKER_CLI_PATHS: &[&str] = &["docker", "/usr/local/bin/docker", "/opt/homebrew/bin/docker"];
+
+/// How long to wait for `docker system df`
+const DOCKER_DF_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often a developer cache scan checks whether it was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to let `docker system prune` run; pruning many images can be slow
+const DOCKER_PRUNE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Where to look for Homebrew; GUI apps don't inherit the shell's PATH
+const BREW_CLI_PATHS: &[&str] = &["brew", "/opt/homebrew/bin/brew", "/usr/local/bin/brew"];
+
+/// How long to wait for `brew cleanup -n`
+const BREW_DRY_RUN_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+/// How long to let `brew cleanup -s` run
+const BREW_CLEANUP_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
 /// Get home directory
 fn get_home_dir() -> Option<PathBuf> {
     dirs::home_dir()
 }
 
-/// Scan all known developer cache locations
-#[command]
-pub async fn scan_developer_caches() -> Result<Vec<DeveloperCache>, String> {
-    let mut caches = Vec::new();
+/// Read an environment variable from the app's own environment
+fn process_env(var: &str) -> Option<OsString> {
+    std::env::var_os(var)
+}
+
+/// Directory named by environment variable `var`, if it is set and not empty.
+/// For list variables like `GOPATH` only the first entry is used.
+fn env_dir(var: &str, env: &dyn Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let value = env(var)?;
+    std::env::split_paths(&value).next().filter(|p| !p.as_os_str().is_empty())
+}
+
+/// Known developer cache locations under `home`, honoring `CARGO_HOME`,
+/// `GRADLE_USER_HOME`, `npm_config_cache` and `GOPATH` as looked up with `env`
+/// (name, path, description, safe_to_clean, skip_size_calc)
+fn developer_cache_locations(home: &Path, env: &dyn Fn(&str) -> Option<OsString>) -> Vec<(&'static str, PathBuf, &'static str, bool, bool)> {
+    let npm_cache = env_dir("npm_config_cache", env).unwrap_or_else(|| home.join(".npm"));
+    let cargo_home = env_dir("CARGO_HOME", env).unwrap_or_else(|| home.join(".cargo"));
+    let gradle_home = env_dir("GRADLE_USER_HOME", env).unwrap_or_else(|| home.join(".gradle"));
+    let go_path = env_dir("GOPATH", env).unwrap_or_else(|| home.join("go"));
     
-    if let Some(home) = get_home_dir() {
-        // Define known developer cache locations
-        // (name, path, description, safe_to_clean, skip_size_calc)
-        let cache_locations: Vec<(&str, PathBuf, &str, bool, bool)> = vec![
-            // npm
-            (
-                "npm Cache",
-                home.join(".npm"),
-                "Node.js package manager cache",
-                true,
-                false,
-            ),
-            // yarn
-            (
-                "Yarn Cache",
-                home.join(".yarn").join("cache"),
-                "Yarn package manager cache",
-                true,
-                false,
-            ),
-            // pnpm
-            (
-                "pnpm Store",
-                home.join(".pnpm-store"),
-                "pnpm package manager store",
-                true,
-                false,
-            ),
-            // Cargo (Rust)
-            (
-                "Cargo Cache",
-                home.join(".cargo").join("registry").join("cache"),
-                "Rust package registry cache",
-                true,
-                false,
-            ),
-            // CocoaPods
-            (
-                "CocoaPods Cache",
-                home.join("Library").join("Caches").join("CocoaPods"),
-                "iOS dependency manager cache",
-                true,
-                false,
-            ),
-            // Xcode DerivedData
-            (
-                "Xcode DerivedData",
-                home.join("Library").join("Developer").join("Xcode").join("DerivedData"),
-                "Xcode build artifacts (safe to clean)",
-                true,
-                false,
-            ),
-            // Xcode Archives
-            (
-                "Xcode Archives",
-                home.join("Library").join("Developer").join("Xcode").join("Archives"),
-                "Xcode archived builds",
-                false,
-                false,
-            ),
-            // Gradle
-            (
-                "Gradle Cache",
-                home.join(".gradle").join("caches"),
-                "Android/Java build cache",
-                true,
-                false,
-            ),
-            // Maven
-            (
-                "Maven Repository",
-                home.join(".m2").join("repository"),
-                "Maven dependencies (partial clean recommended)",
-                false,
-                false,
-            ),
-            // Homebrew
-            (
-                "Homebrew Cache",
-                home.join("Library").join("Caches").join("Homebrew"),
-                "Homebrew package downloads",
-                true,
-                false,
-            ),
-            // pip
-            (
-                "pip Cache",
-                home.join("Library").join("Caches").join("pip"),
-                "Python package cache",
-                true,
-                false,
-            ),
-            // VS Code Extensions Cache
-            (
-                "VS Code Cache",
-                home.join("Library").join("Application Support").join("Code").join("Cache"),
-                "Visual Studio Code cache",
-                true,
-                false,
-            ),
-            // Android SDK
-            (
-                "Android SDK Cache",
-                home.join("Library").join("Android").join("sdk").join(".temp"),
-                "Android SDK temporary files",
-                true,
-                false,
-            ),
-            // Composer (PHP)
-            (
-                "Composer Cache",
-                home.join(".composer").join("cache"),
-                "PHP Composer package cache",
-                true,
-                false,
-            ),
-            // Go modules
-            (
-                "Go Modules Cache",
-                home.join("go").join("pkg").join("mod").join("cache"),
-                "Go modules cache",
-                true,
-                false,
-            ),
-        ];
-        
-        for (name, path, description, safe, _skip) in cache_locations {
-            let exists = path.exists();
-            let size = if exists { get_directory_size(&path) } else { 0 };
-            
-            caches.push(DeveloperCache {
-                name: name.to_string(),
-                path: path.to_string_lossy().to_string(),
-                size,
-                description: description.to_string(),
-                exists,
-                safe_to_clean: safe,
-            });
+    vec![
+        // npm
+        (
+            "npm Cache",
+            npm_cache,
+            "Node.js package manager cache",
+            true,
+            false,
+        ),
+        // yarn
+        (
+            "Yarn Cache",
+            home.join(".yarn").join("cache"),
+            "Yarn package manager cache",
+            true,
+            false,
+        ),
+        // pnpm
+        (
+            "pnpm Store",
+            home.join(".pnpm-store"),
+            "pnpm package manager store",
+            true,
+            false,
+        ),
+        // Cargo (Rust)
+        (
+            "Cargo Cache",
+            cargo_home.join("registry").join("cache"),
+            "Rust package registry cache",
+            true,
+            false,
+        ),
+        // CocoaPods
+        (
+            "CocoaPods Cache",
+            home.join("Library").join("Caches").join("CocoaPods"),
+            "iOS dependency manager cache",
+            true,
+            false,
+        ),
+        // Xcode DerivedData
+        (
+            "Xcode DerivedData",
+            home.join("Library").join("Developer").join("Xcode").join("DerivedData"),
+            "Xcode build artifacts (safe to clean)",
+            true,
+            false,
+        ),
+        // Xcode Archives
+        (
+            "Xcode Archives",
+            home.join("Library").join("Developer").join("Xcode").join("Archives"),
+            "Xcode archived builds",
+            false,
+            false,
+        ),
+        // Gradle
+        (
+            "Gradle Cache",
+            gradle_home.join("caches"),
+            "Android/Java build cache",
+            true,
+            false,
+        ),
+        // Maven
+        (
+            "Maven Repository",
+            home.join(".m2").join("repository"),
+            "Maven dependencies (partial clean recommended)",
+            false,
+            false,
+        ),
+        // Homebrew
+        (
+            "Homebrew Cache",
+            home.join("Library").join("Caches").join("Homebrew"),
+            "Homebrew package downloads",
+            true,
+            false,
+        ),
+        // pip
+        (
+            "pip Cache",
+            home.join("Library").join("Caches").join("pip"),
+            "Python package cache",
+            true,
+            false,
+        ),
+        // VS Code Extensions Cache
+        (
+            "VS Code Cache",
+            home.join("Library").join("Application Support").join("Code").join("Cache"),
+            "Visual Studio Code cache",
+            true,
+            false,
+        ),
+        // Android SDK
+        (
+            "Android SDK Cache",
+            home.join("Library").join("Android").join("sdk").join(".temp"),
+            "Android SDK temporary files",
+            true,
+            false,
+        ),
+        // Composer (PHP)
+        (
+            "Composer Cache",
+            home.join(".composer").join("cache"),
+            "PHP Composer package cache",
+            true,
+            false,
+        ),
+        // Go modules
+        (
+            "Go Modules Cache",
+            go_path.join("pkg").join("mod").join("cache"),
+            "Go modules cache",
+            true,
+            false,
+        ),
+    ]
+}
+
+/// User-defined cache locations from settings as (name, path, description).
+/// `~/` is expanded; relative paths, and the home folder or any folder
+/// containing it, are ignored since cleaning empties the folder.
+fn custom_cache_locations(home: &Path, custom: &[CustomDeveloperCache]) -> Vec<(String, PathBuf, String)> {
+    custom
+        .iter()
+        .filter_map(|cache| {
+            let path = match cache.path.strip_prefix("~/") {
+                Some(rest) => home.join(rest),
+                None => PathBuf::from(&cache.path),
+            };
+            if !path.is_absolute() || home.starts_with(&path) {
+                log::warn!("Ignoring custom developer cache '{}' at {}", cache.name, cache.path);
+                return None;
+            }
+            let description = if cache.description.is_empty() {
+                "Custom cache location".to_string()
+            } else {
+                cache.description.clone()
+            };
+            Some((cache.name.clone(), path, description))
+        })
+        .collect()
+}
+
+/// Every path `clean_developer_cache` may empty: the known locations plus the user's own
+pub fn cleanable_cache_roots(home: &Path) -> Vec<PathBuf> {
+    let custom = custom_cache_locations(home, &load_settings().custom_developer_caches);
+    developer_cache_locations(home, &process_env)
+        .into_iter()
+        .map(|(_, p, _, _, _)| p)
+        .chain(custom.into_iter().map(|(_, p, _)| p))
+        .collect()
+}
+
+/// Size one known developer cache location
+fn developer_cache_entry(name: &str, path: &Path, description: &str, safe: bool) -> DeveloperCache {
+    let exists = path.exists();
+    let sizes = if exists { get_directory_sizes(path) } else { Default::default() };
+    
+    DeveloperCache {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        size: sizes.on_disk,
+        apparent_size: sizes.apparent,
+        description: description.to_string(),
+        exists,
+        safe_to_clean: safe,
+        docker_usage: None,
+    }
+}
+
+/// Size Docker Desktop's data, using `docker system df` for the breakdown if available
+fn docker_cache_entry(docker_path: &Path) -> DeveloperCache {
+    // Docker.raw is sparse, so its on-disk size is far below its apparent size
+    let docker_sizes = get_directory_sizes(docker_path);
+    let docker_usage = get_docker_system_df();
+    let description = match &docker_usage {
+        Some(usage) => format!(
+            "Docker Desktop data, {} reclaimable (use 'docker system prune' to clean)",
+            format_decimal_size(usage.total_reclaimable)
+        ),
+        None => "Docker Desktop data (use 'docker system prune' to clean)".to_string(),
+    };
+    
+    DeveloperCache {
+        name: "Docker Desktop".to_string(),
+        path: docker_path.to_string_lossy().to_string(),
+        size: docker_sizes.on_disk,
+        apparent_size: docker_sizes.apparent,
+        description,
+        exists: true,
+        safe_to_clean: false,
+        docker_usage,
+    }
+}
+
+/// Size every known location on its own blocking task, calling `on_complete`
+/// with each cache and the (completed, total) counts as it finishes.
+/// Stops waiting once `control` is cancelled and returns what finished so far.
+async fn collect_developer_caches<F>(control: Option<Arc<ScanControl>>, mut on_complete: F) -> Vec<DeveloperCache>
+where
+    F: FnMut(&DeveloperCache, usize, usize),
+{
+    let Some(home) = get_home_dir() else {
+        return Vec::new();
+    };
+    
+    let mut tasks = JoinSet::new();
+    for (name, path, description, safe, _skip) in developer_cache_locations(&home, &process_env) {
+        tasks.spawn_blocking(move || developer_cache_entry(name, &path, description, safe));
+    }
+    for (name, path, description) in custom_cache_locations(&home, &load_settings().custom_developer_caches) {
+        tasks.spawn_blocking(move || developer_cache_entry(&name, &path, &description, true));
+    }
+    
+    // Handle Docker separately - use docker system df if available
+    let docker_path = home.join("Library").join("Containers").join("com.docker.docker").join("Data");
+    if docker_path.exists() {
+        tasks.spawn_blocking(move || docker_cache_entry(&docker_path));
+    }
+    
+    let total = tasks.len();
+    let mut caches = Vec::with_capacity(total);
+    loop {
+        if control.as_ref().is_some_and(|c| c.is_cancelled()) {
+            // Dropping the set aborts locations that haven't started yet
+            break;
         }
-        
-        // Handle Docker separately - use docker system df if available
-        let docker_path = home.join("Library").join("Containers").join("com.docker.docker").join("Data");
-        if docker_path.exists() {
-            // Try to get Docker disk usage via command
-            let docker_size = get_docker_disk_usage().unwrap_or_else(|| get_directory_size(&docker_path));
-            
-            caches.push(DeveloperCache {
-                name: "Docker Desktop".to_string(),
-                path: docker_path.to_string_lossy().to_string(),
-                size: docker_size,
-                description: "Docker Desktop data (use 'docker system prune' to clean)".to_string(),
-                exists: true,
-                safe_to_clean: false,
-            });
+        match tokio::time::timeout(CANCEL_POLL_INTERVAL, tasks.join_next()).await {
+            Ok(Some(Ok(cache))) => {
+                on_complete(&cache, caches.len() + 1, total);
+                caches.push(cache);
+            }
+            Ok(Some(Err(e))) => log::warn!("Developer cache sizing failed: {}", e),
+            Ok(None) => break,
+            Err(_) => continue, // Still sizing; check for cancellation again
         }
     }
     
-    // Sort by size descending, only existing caches
+    size_cache::save_global_cache();
+    
+    // Sort by size descending
     caches.sort_by(|a, b| b.size.cmp(&a.size));
-    Ok(caches)
+    caches
 }
 
-/// Get Docker disk usage using actual disk blocks
-fn get_docker_disk_usage() -> Option<u64> {
-    let home = dirs::home_dir()?;
-    let docker_data = home.join("Library").join("Containers").join("com.docker.docker").join("Data");
-    
-    if !docker_data.exists() {
+/// Scan all known developer cache locations
+#[command]
+pub async fn scan_developer_caches() -> Result<Vec<DeveloperCache>, String> {
+    Ok(collect_developer_caches(None, |_, _, _| {}).await)
+}
+
+/// Scan all known developer cache locations in parallel, sending a progress
+/// update over `on_progress` as each one finishes. `scan_id` registers the scan
+/// so `cancel_scan` can stop it; a cancelled scan returns the caches sized so far.
+#[command]
+pub async fn scan_developer_caches_with_progress(
+    on_progress: Channel<DeveloperCacheProgress>,
+    scan_id: Option<String>,
+) -> Result<Vec<DeveloperCache>, String> {
+    let registration = scan_id.as_deref().map(scan_control::register);
+    let control = registration.as_ref().map(|r| r.control());
+    Ok(collect_developer_caches(control, |cache, completed, total| {
+        let _ = on_progress.send(DeveloperCacheProgress {
+            name: cache.name.clone(),
+            size: cache.size,
+            completed,
+            total,
+        });
+    })
+    .await)
+}
+
+/// Ask the Docker CLI for its usage breakdown. Returns None when the CLI is
+/// missing or the daemon isn't running.
+fn get_docker_system_df() -> Option<DockerUsage> {
+    let output = run_docker(&["system", "df", "--format", "{{json .}}"], DOCKER_DF_TIMEOUT).ok()?;
+    if !output.status.success() {
         return None;
     }
+    parse_docker_system_df(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Run the first Docker CLI found in `DOCKER_CLI_PATHS` with `args`
+fn run_docker(args: &[&str], timeout: Duration) -> Result<Output, String> {
+    for docker in DOCKER_CLI_PATHS {
+        match run_command_with_timeout(docker, args, timeout) {
+            Ok(output) => return Ok(output),
+            Err(CommandError::Spawn(_)) => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Err("Docker CLI not found. Install Docker Desktop to clean Docker data".to_string())
+}
+
+/// Check whether Docker's stderr says the daemon isn't reachable
+fn is_docker_daemon_down(stderr: &str) -> bool {
+    stderr.contains("Cannot connect to the Docker daemon") || stderr.contains("Is the docker daemon running")
+}
+
+/// Pull the bytes from the "Total reclaimed space: 1.2GB" line of `docker system prune`
+fn parse_reclaimed_space(output: &str) -> u64 {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Total reclaimed space:"))
+        .and_then(parse_docker_size)
+        .unwrap_or(0)
+}
+
+/// Prune unused Docker data and return the bytes Docker reports reclaiming
+fn prune_docker(include_volumes: bool, all_images: bool) -> Result<u64, String> {
+    let mut args = vec!["system", "prune", "-f"];
+    if include_volumes {
+        args.push("--volumes");
+    }
+    if all_images {
+        args.push("-a");
+    }
+    
+    let output = run_docker(&args, DOCKER_PRUNE_TIMEOUT)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let result = if output.status.success() {
+        Ok(parse_reclaimed_space(&stdout))
+    } else if is_docker_daemon_down(&stderr) {
+        Err("Docker is not running. Start Docker Desktop and try again".to_string())
+    } else {
+        Err(format!("docker system prune failed: {}", stderr.trim()))
+    };
+    
+    let status = result.as_ref().map(|_| ()).map_err(String::clone);
+    crate::audit::record("docker_prune", "docker system prune", *result.as_ref().unwrap_or(&0), &status);
+    result
+}
+
+/// Parse the JSON lines printed by `docker system df --format '{{json .}}'`
+fn parse_docker_system_df(output: &str) -> Option<DockerUsage> {
+    let categories: Vec<DockerUsageCategory> = output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .map(|row| {
+            let field = |key: &str| row.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+            DockerUsageCategory {
+                kind: field("Type"),
+                total_count: field("TotalCount").parse().unwrap_or(0),
+                active: field("Active").parse().unwrap_or(0),
+                size: parse_docker_size(&field("Size")).unwrap_or(0),
+                // e.g. "1.2GB (45%)"
+                reclaimable: field("Reclaimable")
+                    .split_whitespace()
+                    .next()
+                    .and_then(parse_docker_size)
+                    .unwrap_or(0),
+            }
+        })
+        .collect();
     
-    // Calculate size using blocks (handles sparse files)
-    Some(get_directory_size(&docker_data))
+    if categories.is_empty() {
+        return None;
+    }
+    Some(DockerUsage {
+        total_size: categories.iter().map(|c| c.size).sum(),
+        total_reclaimable: categories.iter().map(|c| c.reclaimable).sum(),
+        categories,
+    })
+}
+
+/// Parse a Docker human-readable size such as "1.5GB" or "512kB" (decimal units)
+fn parse_docker_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "PB" => 1e15,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// Run the first Homebrew found in `BREW_CLI_PATHS` with `args`
+fn run_brew(args: &[&str], timeout: Duration) -> Result<Output, String> {
+    for brew in BREW_CLI_PATHS {
+        match run_command_with_timeout(brew, args, timeout) {
+            Ok(output) => return Ok(output),
+            Err(CommandError::Spawn(_)) => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Err("Homebrew not found: `brew` is not on PATH or in /opt/homebrew or /usr/local".to_string())
+}
+
+/// Parse a size as Homebrew prints it, e.g. "28.5MB" or "64B" (binary units)
+fn parse_homebrew_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "B" => 1u64,
+        "KB" => 1 << 10,
+        "MB" => 1 << 20,
+        "GB" => 1 << 30,
+        "TB" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Parse one `Would remove: <path> (<n> files, <size>)` or `Removing: ...` line
+fn parse_homebrew_cleanup_line(line: &str) -> Option<HomebrewCleanupItem> {
+    let rest = line
+        .trim()
+        .strip_prefix("Would remove: ")
+        .or_else(|| line.trim().strip_prefix("Removing: "))?;
+    let (path, details) = rest.rsplit_once(" (")?;
+    let mut parts: Vec<&str> = details.strip_suffix(')')?.split(", ").collect();
+    let size = parse_homebrew_size(parts.pop()?)?;
+    let file_count = parts
+        .first()
+        .and_then(|p| p.strip_suffix(" files").or_else(|| p.strip_suffix(" file")))
+        .and_then(|n| n.replace(',', "").parse().ok());
+    Some(HomebrewCleanupItem {
+        path: path.to_string(),
+        size,
+        file_count,
+    })
+}
+
+/// Pull the bytes from the "==> This operation would free approximately 30.1MB
+/// of disk space." (or "has freed") summary line
+fn parse_homebrew_total(output: &str) -> Option<u64> {
+    output.lines().find_map(|line| {
+        let rest = line.split_once(" approximately ")?.1;
+        parse_homebrew_size(rest.split_whitespace().next()?)
+    })
+}
+
+/// Parse the output of `brew cleanup`, dry run or not
+fn parse_homebrew_cleanup(output: &str) -> HomebrewCleanupReport {
+    let items: Vec<HomebrewCleanupItem> = output.lines().filter_map(parse_homebrew_cleanup_line).collect();
+    let total_size = parse_homebrew_total(output).unwrap_or_else(|| items.iter().map(|i| i.size).sum());
+    HomebrewCleanupReport { items, total_size }
+}
+
+/// Fail with brew's own message when it exits unsuccessfully
+fn check_brew_output(output: &Output, command: &str) -> Result<String, String> {
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("{} failed: {}", command, String::from_utf8_lossy(&output.stderr).trim()))
+    }
 }
 
-/// Clean a developer cache
+/// Ask Homebrew what `brew cleanup -s` would remove, without removing anything
+fn homebrew_dry_run() -> Result<HomebrewCleanupReport, String> {
+    let output = run_brew(&["cleanup", "-n", "-s"], BREW_DRY_RUN_TIMEOUT)?;
+    check_brew_output(&output, "brew cleanup -n").map(|stdout| parse_homebrew_cleanup(&stdout))
+}
+
+/// Run `brew cleanup -s`, returning the bytes Homebrew reports freeing
+fn homebrew_cleanup() -> Result<u64, String> {
+    let result = run_brew(&["cleanup", "-s"], BREW_CLEANUP_TIMEOUT)
+        .and_then(|output| check_brew_output(&output, "brew cleanup -s"))
+        .map(|stdout| parse_homebrew_cleanup(&stdout).total_size);
+
+    let status = result.as_ref().map(|_| ()).map_err(String::clone);
+    crate::audit::record("homebrew_cleanup", "brew cleanup -s", *result.as_ref().unwrap_or(&0), &status);
+    result
+}
+
+/// Format bytes with decimal units, matching how Docker reports sizes
+fn format_decimal_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Clean a developer cache, keeping the folder itself. Its contents go to the
+/// Trash while safe mode is on and are deleted permanently otherwise.
 #[command]
 pub async fn clean_developer_cache(path: String) -> Result<u64, String> {
     let path = PathBuf::from(&path);
@@ -250,21 +634,71 @@ pub async fn clean_developer_cache(path: String) -> Result<u64, String> {
         return Err("Please use 'docker system prune' command or Docker Desktop UI to clean Docker data".to_string());
     }
     
+    // Only clean paths that are (or lie inside) a known developer cache
+    let home = get_home_dir().ok_or("Could not determine home directory")?;
+    let path = ensure_at_or_within_roots(&path, &cleanable_cache_roots(&home))?;
+    
     let size_before = get_directory_size(&path);
     
     // Remove contents but keep the directory
-    if let Ok(entries) = fs::read_dir(&path) {
+    let result = ensure_not_protected(&path).and_then(|_| remove_contents(&path, Removal::from_settings()));
+    crate::audit::record("clean_developer_cache", &path.to_string_lossy(), size_before, &result);
+    result.map(|_| size_before)
+}
+
+/// Remove everything inside a directory per `removal`, stopping at the first failure
+fn remove_contents(path: &Path, removal: Removal) -> Result<(), String> {
+    if let Ok(entries) = fs::read_dir(path) {
         for entry in entries.filter_map(|e| e.ok()) {
-            let entry_path = entry.path();
-            if entry_path.is_dir() {
-                fs::remove_dir_all(&entry_path).map_err(|e| e.to_string())?;
-            } else {
-                fs::remove_file(&entry_path).map_err(|e| e.to_string())?;
-            }
+            removal.remove(&entry.path())?;
         }
     }
-    
-    Ok(size_before)
+    Ok(())
+}
+
+/// List each project's folder in Xcode's DerivedData with its size and last build time
+#[command]
+pub async fn scan_derived_data() -> Result<Vec<DerivedDataProject>, String> {
+    let projects = derived_data_scanner::scan_derived_data();
+    size_cache::save_global_cache();
+    Ok(projects)
+}
+
+/// Delete one project's DerivedData folder, returning the bytes freed. It goes
+/// to the Trash while safe mode is on. The shared `ModuleCache.noindex` folder
+/// is only deleted when `allow_module_cache` is set.
+#[command]
+pub async fn delete_derived_data(path: String, allow_module_cache: Option<bool>) -> Result<u64, String> {
+    derived_data_scanner::delete_derived_data(&path, allow_module_cache.unwrap_or(false), Removal::from_settings())
+}
+
+/// Run `docker system prune -f`, optionally with `--volumes` and `-a`.
+/// Returns the space Docker reports reclaiming, or an error if Docker isn't running.
+/// Runs on a blocking thread since pruning can take minutes.
+#[command]
+pub async fn docker_prune(include_volumes: bool, all_images: bool) -> Result<u64, String> {
+    tokio::task::spawn_blocking(move || prune_docker(include_volumes, all_images))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Report what `brew cleanup -s` would remove (old downloads, outdated
+/// versions, logs) and how much it would free, from Homebrew's own dry run.
+/// Fails with a clear error when Homebrew isn't installed.
+#[command]
+pub async fn scan_homebrew() -> Result<HomebrewCleanupReport, String> {
+    tokio::task::spawn_blocking(homebrew_dry_run)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Run `brew cleanup -s`, which removes outdated versions and scrubs the
+/// download cache, returning the space Homebrew reports freeing
+#[command]
+pub async fn clean_homebrew() -> Result<u64, String> {
+    tokio::task::spawn_blocking(homebrew_cleanup)
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 /// Get total developer cache size
@@ -308,6 +742,168 @@ mod tests {
         let _ = scan_developer_caches().await;
     }
 
+    #[tokio::test]
+    async fn test_scan_developer_caches_with_progress() {
+        let updates = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&updates);
+        let channel = Channel::new(move |progress: DeveloperCacheProgress| sink.lock().unwrap().push(progress));
+
+        let caches = scan_developer_caches_with_progress(channel, None).await.unwrap();
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates.len(), caches.len());
+        if let Some(last) = updates.last() {
+            assert_eq!(last.completed, last.total);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_developer_cache_scan_returns_early() {
+        let control = Arc::new(ScanControl::default());
+        control.cancel();
+        let caches = collect_developer_caches(Some(control), |_, _, _| {}).await;
+        assert!(caches.is_empty());
+    }
+
+    #[test]
+    fn test_only_known_developer_caches_pass_the_guard() {
+        let home = tempfile::tempdir().unwrap();
+        let npm = home.path().join(".npm");
+        let documents = home.path().join("Documents");
+        fs::create_dir_all(npm.join("_cacache")).unwrap();
+        fs::create_dir_all(&documents).unwrap();
+
+        let no_env = |_: &str| None;
+        let known: Vec<PathBuf> = developer_cache_locations(home.path(), &no_env).into_iter().map(|(_, p, _, _, _)| p).collect();
+        assert!(ensure_at_or_within_roots(&npm, &known).is_ok());
+        assert!(ensure_at_or_within_roots(&npm.join("_cacache"), &known).is_ok());
+        assert!(ensure_at_or_within_roots(&documents, &known).is_err());
+    }
+
+    #[test]
+    fn test_developer_cache_locations_honor_env_overrides() {
+        let home = Path::new("/Users/dev");
+        let env = |var: &str| match var {
+            "CARGO_HOME" => Some(OsString::from("/opt/cargo")),
+            "GOPATH" => Some(OsString::from("/work/go:/other/go")),
+            "GRADLE_USER_HOME" => Some(OsString::new()),
+            _ => None,
+        };
+        let paths: Vec<PathBuf> = developer_cache_locations(home, &env).into_iter().map(|(_, p, _, _, _)| p).collect();
+
+        assert!(paths.contains(&PathBuf::from("/opt/cargo/registry/cache")));
+        assert!(paths.contains(&PathBuf::from("/work/go/pkg/mod/cache")));
+        // Unset or empty variables fall back to the default location
+        assert!(paths.contains(&home.join(".gradle").join("caches")));
+        assert!(paths.contains(&home.join(".npm")));
+    }
+
+    #[test]
+    fn test_custom_cache_locations() {
+        let home = Path::new("/Users/dev");
+        let custom = |name: &str, path: &str| CustomDeveloperCache {
+            name: name.to_string(),
+            path: path.to_string(),
+            description: String::new(),
+        };
+        let locations = custom_cache_locations(
+            home,
+            &[
+                custom("Monorepo", "~/src/monorepo/.cache"),
+                custom("Bazel", "/private/var/tmp/_bazel_dev"),
+                custom("Home", "~/"),
+                custom("Users", "/Users"),
+                custom("Relative", "build/cache"),
+            ],
+        );
+
+        let paths: Vec<&PathBuf> = locations.iter().map(|(_, p, _)| p).collect();
+        assert_eq!(paths, vec![&home.join("src/monorepo/.cache"), &PathBuf::from("/private/var/tmp/_bazel_dev")]);
+        assert_eq!(locations[0].2, "Custom cache location");
+    }
+
+    #[tokio::test]
+    async fn test_clean_developer_cache_refuses_unknown_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("notes.txt");
+        fs::write(&file, "keep me").unwrap();
+
+        let result = clean_developer_cache(temp_dir.path().to_string_lossy().to_string()).await;
+        assert!(result.is_err());
+        assert!(file.exists());
+    }
+
+    #[test]
+    fn test_parse_docker_system_df() {
+        let output = r#"{"Active":"2","Reclaimable":"1.2GB (45%)","Size":"2.7GB","TotalCount":"5","Type":"Images"}
+{"Active":"1","Reclaimable":"0B (0%)","Size":"12.5kB","TotalCount":"1","Type":"Containers"}
+{"Active":"0","Reclaimable":"300MB (100%)","Size":"300MB","TotalCount":"3","Type":"Local Volumes"}
+{"Active":"0","Reclaimable":"512MB","Size":"512MB","TotalCount":"40","Type":"Build Cache"}"#;
+
+        let usage = parse_docker_system_df(output).unwrap();
+        assert_eq!(usage.categories.len(), 4);
+        assert_eq!(usage.categories[0].kind, "Images");
+        assert_eq!(usage.categories[0].total_count, 5);
+        assert_eq!(usage.categories[0].active, 2);
+        assert_eq!(usage.categories[0].size, 2_700_000_000);
+        assert_eq!(usage.categories[1].size, 12_500);
+        assert_eq!(usage.total_reclaimable, 1_200_000_000 + 300_000_000 + 512_000_000);
+        assert!(parse_docker_system_df("Cannot connect to the Docker daemon").is_none());
+    }
+
+    #[test]
+    fn test_parse_docker_size() {
+        assert_eq!(parse_docker_size("0B"), Some(0));
+        assert_eq!(parse_docker_size("1.5GB"), Some(1_500_000_000));
+        assert_eq!(parse_docker_size("512kB"), Some(512_000));
+        assert_eq!(parse_docker_size("nonsense"), None);
+        assert_eq!(format_decimal_size(1_200_000_000), "1.2 GB");
+    }
+
+    #[test]
+    fn test_parse_reclaimed_space() {
+        let output = "Deleted Images:\nuntagged: alpine:latest\n\nTotal reclaimed space: 1.5GB\n";
+        assert_eq!(parse_reclaimed_space(output), 1_500_000_000);
+        assert_eq!(parse_reclaimed_space("Total reclaimed space: 0B"), 0);
+        assert_eq!(parse_reclaimed_space(""), 0);
+    }
+
+    #[test]
+    fn test_parse_homebrew_cleanup() {
+        let output = "\
+Would remove: /Users/me/Library/Caches/Homebrew/wget--1.21.3.arm64_ventura.bottle.tar.gz (1.5MB)
+Would remove: /opt/homebrew/Cellar/openssl@3/3.1.0 (6,345 files, 28.5MB)
+Would remove: /Users/me/Library/Logs/Homebrew/wget/ (64B)
+==> This operation would free approximately 30.0MB of disk space.
+";
+        let report = parse_homebrew_cleanup(output);
+        assert_eq!(report.items.len(), 3);
+        assert_eq!(report.items[0].size, 1_572_864);
+        assert_eq!(report.items[0].file_count, None);
+        assert_eq!(report.items[1].path, "/opt/homebrew/Cellar/openssl@3/3.1.0");
+        assert_eq!(report.items[1].file_count, Some(6345));
+        assert_eq!(report.items[2].size, 64);
+        assert_eq!(report.total_size, 30 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_homebrew_cleanup_without_summary() {
+        let output = "Removing: /opt/homebrew/Cellar/git/2.40.0 (1 file, 2KB)\nWarning: Skipping node: most recent version 20.1.0 not installed\n";
+        let report = parse_homebrew_cleanup(output);
+        assert_eq!(report.items[0].file_count, Some(1));
+        assert_eq!(report.total_size, 2048);
+        assert_eq!(parse_homebrew_cleanup("").total_size, 0);
+        assert_eq!(parse_homebrew_size("1.5GB"), Some(1_610_612_736));
+        assert_eq!(parse_homebrew_size("big"), None);
+    }
+
+    #[test]
+    fn test_is_docker_daemon_down() {
+        assert!(is_docker_daemon_down(
+            "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?"
+        ));
+        assert!(!is_docker_daemon_down("permission denied"));
+    }
+
     #[tokio::test]
     async fn test_get_total_developer_cache_size() {
         let _ = get_total_developer_cache_size().await;
@@ -0,0 +1,29 @@
+use crate::dismissals;
+use tauri::command;
+
+/// Hide a result from future scans: a file path, or a group hash for duplicates
+#[command]
+pub async fn dismiss_result(key: String) -> Result<(), String> {
+    dismissals::dismiss(&key)
+}
+
+/// Show every previously dismissed result again
+#[command]
+pub async fn clear_dismissals() -> Result<(), String> {
+    dismissals::clear()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[serial_test::serial(dismissals)]
+    async fn test_dismiss_then_clear() {
+        dismiss_result("/tmp/dismissed-command-test.bin".to_string()).await.unwrap();
+        assert!(dismissals::dismissed().contains("/tmp/dismissed-command-test.bin"));
+
+        clear_dismissals().await.unwrap();
+        assert!(dismissals::dismissed().is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::batch::{self, DeleteOutcome};
+use crate::path_guard::ensure_within_roots;
+use crate::removal::Removal;
+use crate::scanners::quarantine_scanner::{self, QuarantinedDownload};
+use crate::scanners::stale_download_scanner::{self, StaleDownload};
+use std::path::Path;
+use tauri::command;
+
+/// List files in ~/Downloads that were downloaded from the internet, with their
+/// download date, downloading app and origin URL (oldest first)
+#[command]
+pub async fn scan_quarantined_downloads() -> Result<Vec<QuarantinedDownload>, String> {
+    Ok(quarantine_scanner::scan_quarantined_downloads())
+}
+
+/// List files in ~/Downloads that haven't been opened or changed in `days`
+/// days, oldest first. Files modified in the last minute are left out since
+/// they may still be downloading.
+#[command]
+pub async fn scan_stale_downloads(days: u64) -> Result<Vec<StaleDownload>, String> {
+    Ok(stale_download_scanner::scan_stale_downloads(days))
+}
+
+/// Move several stale downloads to the Trash, reporting the outcome for each
+/// path. Paths outside ~/Downloads are refused.
+#[command]
+pub async fn trash_stale_downloads(paths: Vec<String>) -> Result<Vec<DeleteOutcome>, String> {
+    let downloads = stale_download_scanner::downloads_dir().ok_or("Could not determine home directory")?;
+    Ok(batch::for_each_path(paths, |path| {
+        ensure_within_roots(Path::new(path), std::slice::from_ref(&downloads))?;
+        crate::audit::logged("move_to_trash", path, || Removal::Trash.remove(Path::new(path)))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scan_quarantined_downloads() {
+        assert!(scan_quarantined_downloads().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_trash_stale_downloads_refuses_outside_downloads() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("report.pdf");
+        std::fs::write(&file, "keep").unwrap();
+
+        let outcomes = trash_stale_downloads(vec![file.to_string_lossy().to_string()]).await.unwrap();
+        assert!(outcomes[0].result.is_err());
+        assert!(file.exists());
+    }
+}
@@ -1,22 +1,189 @@
-use crate::scanners::hash_scanner::{self, DuplicateGroup};
+use crate::batch::{self, DeleteOutcome};
+use crate::commands::config;
+use crate::dismissals;
+use crate::pagination::{paginate, sort_results, Page, SortOrder};
+use crate::removal::Removal;
+use crate::scanners::hash_scanner::{self, DuplicateGroup, GroupResolution, HashAlgorithm, KeepStrategy, KeepSuggestion, ScanStats};
+use serde::{Deserialize, Serialize};
+use crate::scanners::scan_control;
+use crate::scanners::scan_options::ScanOptions;
+use std::path::Path;
 use tauri::command;
 
-/// Scan a directory for duplicate files
+/// Kind under which directory duplicate scans are tracked, so a second scan
+/// of the same folder is refused while one is running
+const DUPLICATES_SCAN: &str = "duplicates";
+
+/// A page of duplicate groups along with how much work the scan did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateScanResult {
+    #[serde(flatten)]
+    pub page: Page<DuplicateGroup>,
+    pub stats: ScanStats,
+}
+
+/// Optional parameters of `scan_duplicates`; any field may be left out
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DuplicateScanOptions {
+    /// How deep the scan descends (1 = immediate children only)
+    pub max_depth: Option<usize>,
+    /// Glob patterns for paths to skip entirely, on top of the exclude patterns in settings
+    pub exclude: Option<Vec<String>>,
+    /// Descend into symlinked folders (default false)
+    pub follow_symlinks: Option<bool>,
+    /// Avoid descending into network or read-only mounts (default true)
+    pub skip_network_mounts: Option<bool>,
+    /// Keep only groups with a copy whose name contains this (case-insensitive)
+    pub filter: Option<String>,
+    /// Order of the groups (default most wasted space first; groups have no date)
+    pub sort_by: Option<SortOrder>,
+    /// Page of the sorted groups to return
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    /// Registers the scan so it can be paused, resumed or cancelled
+    pub scan_id: Option<String>,
+    /// Also consider files whose name starts with `.` (default false)
+    pub include_hidden: Option<bool>,
+    /// Drop groups wasting less than this; applied before filtering, sorting and paging
+    pub min_wasted_mb: Option<u64>,
+    /// Keep only this many of the most wasteful groups; applied before filtering, sorting and paging
+    pub max_groups: Option<usize>,
+    /// Keep groups the user dismissed (default false)
+    pub include_dismissed: Option<bool>,
+    /// Replaces `min_size_mb` for thresholds below 1 MB; 0 also compares
+    /// empty files, which `min_size_mb` never does
+    pub min_size_bytes: Option<u64>,
+}
+
+/// Scan a directory for duplicate files, with the optional parameters in `options`.
+/// The result's `stats` count the files considered and hashed, to help tune the
+/// threshold. Fails with a `ScanAlreadyRunning` error while another duplicate
+/// scan of the same directory is in flight.
 #[command]
-pub async fn scan_duplicates(directory: String, min_size_mb: u64) -> Result<Vec<DuplicateGroup>, String> {
-    Ok(hash_scanner::scan_duplicates(&directory, min_size_mb))
+pub async fn scan_duplicates(
+    directory: String,
+    min_size_mb: u64,
+    options: Option<DuplicateScanOptions>,
+) -> Result<DuplicateScanResult, String> {
+    let DuplicateScanOptions {
+        max_depth,
+        exclude,
+        follow_symlinks,
+        skip_network_mounts,
+        filter,
+        sort_by,
+        offset,
+        limit,
+        scan_id,
+        include_hidden,
+        min_wasted_mb,
+        max_groups,
+        include_dismissed,
+        min_size_bytes,
+    } = options.unwrap_or_default();
+    let options = ScanOptions::with_max_depth(max_depth)
+        .with_exclude(&config::exclude_patterns_with(exclude))?
+        .with_follow_symlinks(follow_symlinks.unwrap_or(false))
+        .with_skip_network_mounts(skip_network_mounts.unwrap_or(true))
+        .with_include_hidden(include_hidden.unwrap_or(false));
+    let active = scan_control::begin_scan(DUPLICATES_SCAN, &directory, scan_id.as_deref())?;
+    let options = options.with_control(active.control());
+    let min_size_bytes = min_size_bytes.unwrap_or_else(|| hash_scanner::min_size_from_mb(min_size_mb));
+    // The walk blocks while the scan is paused, so keep it off the async runtime
+    let (groups, stats, cancelled) = tokio::task::spawn_blocking(move || {
+        let (groups, stats) = hash_scanner::scan_duplicates_with_stats(&directory, min_size_bytes, &options);
+        (groups, stats, options.is_cancelled())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if cancelled {
+        return Err("Scan cancelled".to_string());
+    }
+    let groups = dismissals::without_dismissed(groups, include_dismissed.unwrap_or(false), |g| g.hash.as_str());
+    let mut groups = hash_scanner::cap_groups(groups, min_wasted_mb.unwrap_or(0) * 1024 * 1024, max_groups);
+    if let Some(query) = filter {
+        groups = hash_scanner::filter_groups_by_name(groups, &query);
+    }
+    sort_results(&mut groups, sort_by.unwrap_or_default());
+    Ok(DuplicateScanResult {
+        page: paginate(groups, offset, limit),
+        stats,
+    })
 }
 
-/// Scan common directories for duplicates
+/// Scan common directories for duplicates.
+/// `filter` keeps only groups with a copy whose name contains it (case-insensitive).
+/// Dismissed groups are left out unless `include_dismissed` is set.
 #[command]
-pub async fn scan_common_duplicates(min_size_mb: u64) -> Result<Vec<DuplicateGroup>, String> {
-    Ok(hash_scanner::scan_common_directories_for_duplicates(min_size_mb))
+pub async fn scan_common_duplicates(
+    min_size_mb: u64,
+    filter: Option<String>,
+    include_dismissed: Option<bool>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let groups = tokio::task::spawn_blocking(move || {
+        hash_scanner::scan_common_directories_for_duplicates(min_size_mb)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let groups = dismissals::without_dismissed(groups, include_dismissed.unwrap_or(false), |g| g.hash.as_str());
+    Ok(match filter {
+        Some(query) => hash_scanner::filter_groups_by_name(groups, &query),
+        None => groups,
+    })
 }
 
-/// Delete a duplicate file
+/// Suggest which copy of a duplicate group to keep, for "Smart Select"
+#[command]
+pub async fn suggest_keep(group: DuplicateGroup, strategy: KeepStrategy) -> Result<KeepSuggestion, String> {
+    Ok(hash_scanner::suggest_keep(&group, strategy))
+}
+
+/// Hex digest of a file's contents with `algorithm` (SHA-256 by default), for
+/// showing a file's hash or checking two files are identical
+#[command]
+pub async fn hash_file(path: String, algorithm: Option<HashAlgorithm>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || hash_scanner::hash_file(Path::new(&path), algorithm.unwrap_or_default()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Delete a duplicate file, moving it to the Trash while safe mode is on
 #[command]
 pub async fn delete_duplicate(path: String) -> Result<(), String> {
-    hash_scanner::delete_duplicate(&path)
+    hash_scanner::delete_duplicate(&path, Removal::from_settings())
+}
+
+/// Delete several duplicates, reporting the outcome for each path. Files go
+/// to the Trash while safe mode is on.
+#[command]
+pub async fn delete_duplicates(paths: Vec<String>) -> Result<Vec<DeleteOutcome>, String> {
+    let removal = Removal::from_settings();
+    Ok(batch::for_each_path(paths, |path| hash_scanner::delete_duplicate(path, removal)))
+}
+
+/// Move a duplicate to trash only after confirming it and `keep_path` still
+/// hash to `expected_hash`, so a file edited since the scan is never lost
+#[command]
+pub async fn delete_duplicate_verified(path: String, expected_hash: String, keep_path: String) -> Result<(), String> {
+    hash_scanner::delete_duplicate_verified(&path, &expected_hash, &keep_path)
+}
+
+/// Keep one copy of a duplicate group chosen by `strategy` and move the others
+/// to trash. Every copy is re-hashed first; if any has changed or gone, nothing
+/// in the group is touched and an error is returned.
+#[command]
+pub async fn resolve_duplicate_group(group: DuplicateGroup, strategy: KeepStrategy) -> Result<GroupResolution, String> {
+    tokio::task::spawn_blocking(move || hash_scanner::resolve_duplicate_group(&group, strategy))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Replace a duplicate with a hard link to the kept copy, reclaiming its space
+/// without removing the path. Returns the bytes reclaimed.
+#[command]
+pub async fn dedupe_via_hardlink(keep_path: String, duplicate_path: String) -> Result<u64, String> {
+    hash_scanner::dedupe_via_hardlink(&keep_path, &duplicate_path)
 }
 
 /// Move a duplicate file to trash
@@ -28,7 +195,11 @@ pub async fn move_duplicate_to_trash(path: String) -> Result<(), String> {
 /// Get total wasted space from duplicates
 #[command]
 pub async fn get_duplicates_wasted_space(min_size_mb: u64) -> Result<u64, String> {
-    let duplicates = hash_scanner::scan_common_directories_for_duplicates(min_size_mb);
+    let duplicates = tokio::task::spawn_blocking(move || {
+        hash_scanner::scan_common_directories_for_duplicates(min_size_mb)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
     Ok(duplicates.iter().map(|d| d.total_wasted).sum())
 }
 
@@ -40,12 +211,41 @@ mod tests {
     #[tokio::test]
     async fn test_scan_duplicates() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let _ = scan_duplicates(temp_dir.path().to_string_lossy().to_string(), 0).await;
+        let _ = scan_duplicates(temp_dir.path().to_string_lossy().to_string(), 0, None).await;
+    }
+
+    #[tokio::test]
+    async fn test_scan_duplicates_with_exclude() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skipped = temp_dir.path().join("backup");
+        std::fs::create_dir(&skipped).unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "same").unwrap();
+        std::fs::write(skipped.join("b.txt"), "same").unwrap();
+
+        let options = DuplicateScanOptions {
+            exclude: Some(vec!["**/backup".to_string()]),
+            ..Default::default()
+        };
+        let groups = scan_duplicates(temp_dir.path().to_string_lossy().to_string(), 0, Some(options))
+            .await
+            .unwrap();
+        assert!(groups.page.items.is_empty());
+        assert_eq!(groups.page.total_count, 0);
+        assert_eq!(groups.stats.files_considered, 1);
+    }
+
+    #[test]
+    fn test_duplicate_scan_options_deserialize_partially() {
+        let options: DuplicateScanOptions =
+            serde_json::from_str(r#"{"min_size_bytes": 0, "exclude": ["**/backup"]}"#).unwrap();
+        assert_eq!(options.min_size_bytes, Some(0));
+        assert_eq!(options.exclude, Some(vec!["**/backup".to_string()]));
+        assert!(options.scan_id.is_none());
     }
 
     #[tokio::test]
     async fn test_scan_common_duplicates() {
-        let _ = scan_common_duplicates(10).await;
+        let _ = scan_common_duplicates(10, None, None).await;
     }
 
     #[tokio::test]
@@ -58,11 +258,25 @@ mod tests {
         drop(file);
 
         // Delete it
-        let result = delete_duplicate(file_path.to_string_lossy().to_string()).await;
+        let result = hash_scanner::delete_duplicate(&file_path.to_string_lossy(), Removal::Permanent);
         assert!(result.is_ok());
         assert!(!file_path.exists());
     }
 
+    #[tokio::test]
+    async fn test_hash_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let a = temp_dir.path().join("a.txt");
+        let b = temp_dir.path().join("b.txt");
+        std::fs::write(&a, "same").unwrap();
+        std::fs::write(&b, "same").unwrap();
+
+        let hash_a = hash_file(a.to_string_lossy().to_string(), None).await.unwrap();
+        let hash_b = hash_file(b.to_string_lossy().to_string(), Some(HashAlgorithm::Sha256)).await.unwrap();
+        assert_eq!(hash_a, hash_b);
+        assert!(hash_file("/nonexistent/file".to_string(), None).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_delete_duplicate_nonexistent() {
         // Functions return Ok(()) for nonexistent files by design (idempotent delete)
@@ -70,6 +284,24 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_delete_duplicate_verified_rejects_changed_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let keep = temp_dir.path().join("keep.txt");
+        let dup = temp_dir.path().join("dup.txt");
+        std::fs::write(&keep, "original").unwrap();
+        std::fs::write(&dup, "edited").unwrap();
+
+        let result = delete_duplicate_verified(
+            dup.to_string_lossy().to_string(),
+            "0000".to_string(),
+            keep.to_string_lossy().to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(dup.exists());
+    }
+
     #[tokio::test]
     async fn test_move_duplicate_to_trash() {
         // Create a temp file
@@ -79,8 +311,13 @@ mod tests {
         writeln!(file, "trash content").unwrap();
         drop(file);
 
-        // Move to trash (this may fail on CI without trash support, so we just check it doesn't panic)
-        let _ = move_duplicate_to_trash(file_path.to_string_lossy().to_string()).await;
+        // Trash support may be missing on CI; when the move works, take the file back out
+        let result = move_duplicate_to_trash(file_path.to_string_lossy().to_string()).await;
+        if result.is_ok() {
+            assert!(!file_path.exists());
+            #[cfg(unix)]
+            assert!(crate::removal::take_from_trash(&temp_dir.path().canonicalize().unwrap(), "test_trash.txt"));
+        }
     }
 
     #[tokio::test]
@@ -0,0 +1,182 @@
+use crate::scanners::{app_scanner, cache_scanner, file_scanner, hash_scanner};
+use crate::scanners::scan_options::ScanOptions;
+use serde::Serialize;
+use std::fs::File;
+use std::path::Path;
+use tauri::command;
+
+/// Minimum sizes used when re-running scans for an export
+const EXPORT_LARGE_FILE_MIN_MB: u64 = 100;
+const EXPORT_DUPLICATE_MIN_MB: u64 = 1;
+
+/// Output format for exported reports
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "csv" => Ok(ReportFormat::Csv),
+            other => Err(format!("Unsupported export format: {}", other)),
+        }
+    }
+}
+
+/// One CSV row per file in a duplicate group
+#[derive(Debug, Serialize)]
+struct DuplicateRow {
+    hash: String,
+    file_size: u64,
+    total_wasted: u64,
+    path: String,
+    name: String,
+}
+
+fn flatten_duplicates(groups: &[hash_scanner::DuplicateGroup]) -> Vec<DuplicateRow> {
+    groups
+        .iter()
+        .flat_map(|group| {
+            group.files.iter().map(move |file| DuplicateRow {
+                hash: group.hash.clone(),
+                file_size: group.file_size,
+                total_wasted: group.total_wasted,
+                path: file.path.clone(),
+                name: file.name.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Write records as pretty JSON or as CSV with a header row, returning the record count
+fn write_records<T: Serialize>(records: &[T], format: ReportFormat, destination: &Path) -> Result<usize, String> {
+    let file = File::create(destination).map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+    match format {
+        ReportFormat::Json => serde_json::to_writer_pretty(file, records).map_err(|e| e.to_string())?,
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(file);
+            for record in records {
+                writer.serialize(record).map_err(|e| e.to_string())?;
+            }
+            writer.flush().map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(records.len())
+}
+
+/// Re-run a scan and export its results to `destination`.
+///
+/// `kind` is one of "caches", "large_files", "duplicates" or "orphans";
+/// `format` is "json" or "csv". Returns the number of records written.
+#[command]
+pub async fn export_report(kind: String, format: String, destination: String) -> Result<usize, String> {
+    let format = ReportFormat::parse(&format)?;
+    tokio::task::spawn_blocking(move || export_to(&kind, format, Path::new(&destination)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Run the scan for `kind` and write its results, on the calling thread
+fn export_to(kind: &str, format: ReportFormat, destination: &Path) -> Result<usize, String> {
+    let options = ScanOptions::default();
+
+    match kind {
+        "caches" => write_records(&cache_scanner::scan_all_caches(&options, 0), format, destination),
+        "large_files" => write_records(
+            &file_scanner::scan_common_directories(EXPORT_LARGE_FILE_MIN_MB, None),
+            format,
+            destination,
+        ),
+        "duplicates" => {
+            let groups = hash_scanner::scan_common_directories_for_duplicates(EXPORT_DUPLICATE_MIN_MB);
+            match format {
+                ReportFormat::Json => write_records(&groups, format, destination),
+                ReportFormat::Csv => write_records(&flatten_duplicates(&groups), format, destination),
+            }
+        }
+        "orphans" => write_records(&app_scanner::scan_orphan_files(), format, destination),
+        other => Err(format!("Unknown report kind: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanners::cache_scanner::{CacheEntry, CacheType};
+    use crate::scanners::hash_scanner::{DuplicateFile, DuplicateGroup};
+
+    fn sample_cache() -> CacheEntry {
+        CacheEntry {
+            path: "/tmp/Caches/com.example, \"quoted\"".to_string(),
+            name: "com.example".to_string(),
+            size: 42,
+            cache_type: CacheType::Application,
+            is_developer_related: false,
+            is_safe_to_delete: true,
+            description: "Application cache".to_string(),
+            possibly_corrupt: false,
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_report_format_parse() {
+        assert_eq!(ReportFormat::parse("JSON").unwrap(), ReportFormat::Json);
+        assert_eq!(ReportFormat::parse("csv").unwrap(), ReportFormat::Csv);
+        assert!(ReportFormat::parse("xml").is_err());
+    }
+
+    #[test]
+    fn test_write_records_csv_escapes_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join("caches.csv");
+
+        let count = write_records(&[sample_cache()], ReportFormat::Csv, &dest).unwrap();
+        assert_eq!(count, 1);
+
+        let contents = std::fs::read_to_string(&dest).unwrap();
+        let mut lines = contents.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "path,name,size,cache_type,is_developer_related,is_safe_to_delete,description,possibly_corrupt,truncated"
+        );
+        assert!(lines.next().unwrap().starts_with("\"/tmp/Caches/com.example, \"\"quoted\"\"\",com.example,42,Application"));
+    }
+
+    #[test]
+    fn test_write_records_json() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join("caches.json");
+
+        write_records(&[sample_cache()], ReportFormat::Json, &dest).unwrap();
+        let parsed: Vec<CacheEntry> = serde_json::from_str(&std::fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(parsed[0].size, 42);
+    }
+
+    #[test]
+    fn test_flatten_duplicates() {
+        let group = DuplicateGroup {
+            hash: "abc".to_string(),
+            files: vec![
+                DuplicateFile { path: "/a/x.txt".to_string(), name: "x.txt".to_string(), is_symlink: false },
+                DuplicateFile { path: "/b/x.txt".to_string(), name: "x.txt".to_string(), is_symlink: false },
+            ],
+            file_size: 10,
+            total_wasted: 10,
+        };
+        let rows = flatten_duplicates(&[group]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].path, "/b/x.txt");
+        assert_eq!(rows[1].hash, "abc");
+    }
+
+    #[tokio::test]
+    async fn test_export_report_rejects_unknown_kind() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join("out.json").to_string_lossy().to_string();
+        assert!(export_report("bogus".to_string(), "json".to_string(), dest).await.is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use crate::audit::{self, AuditEntry};
+use tauri::command;
+
+/// Recently performed destructive operations from the audit log, newest first
+#[command]
+pub async fn get_recent_operations(limit: usize) -> Result<Vec<AuditEntry>, String> {
+    Ok(audit::recent_operations(limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_recent_operations_respects_limit() {
+        let operations = get_recent_operations(3).await.unwrap();
+        assert!(operations.len() <= 3);
+    }
+}
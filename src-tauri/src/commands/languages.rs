@@ -0,0 +1,36 @@
+use crate::batch::{self, DeleteOutcome};
+use crate::scanners::language_scanner::{self, AppLanguageFiles};
+use crate::scanners::size_cache;
+use tauri::command;
+
+/// Find `.lproj` localization folders in /Applications for languages not in
+/// `languages_to_keep`. Base and English are always kept.
+#[command]
+pub async fn scan_language_files(languages_to_keep: Vec<String>) -> Result<Vec<AppLanguageFiles>, String> {
+    let apps = language_scanner::scan_language_files(&languages_to_keep);
+    size_cache::save_global_cache();
+    Ok(apps)
+}
+
+/// Move localization folders to the trash, reporting the outcome for each path.
+/// Removed languages come back when the app is updated.
+#[command]
+pub async fn remove_language_files(paths: Vec<String>) -> Result<Vec<DeleteOutcome>, String> {
+    Ok(batch::for_each_path(paths, language_scanner::remove_language_file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scan_language_files() {
+        assert!(scan_language_files(vec!["fr".to_string()]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_remove_language_files_rejects_base() {
+        let outcomes = remove_language_files(vec!["/tmp/Base.lproj".to_string()]).await.unwrap();
+        assert!(outcomes[0].result.is_err());
+    }
+}
@@ -1,41 +1,295 @@
-use crate::scanners::file_scanner::{self, FileCategory, LargeFile};
+use crate::batch::{self, DeleteOutcome};
+use crate::commands::config;
+use crate::dismissals;
+use crate::pagination::{paginate, sort_results, Page, SortOrder};
+use crate::removal::Removal;
+use crate::scanners::file_scanner::{self, FileCategory, LargeFile, LargeFileReport};
+use crate::scanners::scan_control;
+use crate::scanners::scan_options::ScanOptions;
+use serde::Deserialize;
+use std::path::PathBuf;
 use tauri::command;
+use tauri::ipc::Channel;
 
-/// Scan a directory for large files
+/// Number of files buffered before a batch is pushed to the frontend
+const STREAM_BATCH_SIZE: usize = 25;
+
+/// Kind under which directory scans for large files are tracked, so a second
+/// scan of the same folder is refused while one is running
+const LARGE_FILES_SCAN: &str = "large_files";
+
+/// Map category names from the frontend to `FileCategory` values
+fn parse_categories(categories: Option<Vec<String>>) -> Option<Vec<FileCategory>> {
+    categories.map(|cats| {
+        cats.iter().filter_map(|c| FileCategory::from_name(c)).collect()
+    })
+}
+
+/// Drop files in any of the `excluded` categories, after categorization
+fn without_categories(files: Vec<LargeFile>, excluded: Option<Vec<String>>) -> Vec<LargeFile> {
+    match parse_categories(excluded) {
+        Some(excluded) if !excluded.is_empty() => file_scanner::exclude_categories(files, &excluded),
+        _ => files,
+    }
+}
+
+/// Build walk options from the optional command parameters
+fn build_scan_options(
+    max_depth: Option<usize>,
+    exclude: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
+    skip_network_mounts: Option<bool>,
+) -> Result<ScanOptions, String> {
+    Ok(ScanOptions::with_max_depth(max_depth)
+        .with_exclude(&config::exclude_patterns_with(exclude))?
+        .with_follow_symlinks(follow_symlinks.unwrap_or(false))
+        .with_skip_network_mounts(skip_network_mounts.unwrap_or(true)))
+}
+
+/// Optional parameters of `scan_large_files`; any field may be left out
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LargeFileScanParams {
+    /// Keep only files in these categories
+    pub categories: Option<Vec<String>>,
+    /// How deep the scan descends (1 = immediate children only)
+    pub max_depth: Option<usize>,
+    /// Glob patterns for paths to skip entirely, on top of the exclude patterns in settings
+    pub exclude: Option<Vec<String>>,
+    /// Descend into symlinked folders (default false)
+    pub follow_symlinks: Option<bool>,
+    /// Avoid descending into network or read-only mounts (default true)
+    pub skip_network_mounts: Option<bool>,
+    /// Keep only files whose name contains this (case-insensitive)
+    pub filter: Option<String>,
+    /// Order of the results (default largest first)
+    pub sort_by: Option<SortOrder>,
+    /// Page of the sorted results to return
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    /// Also consider files whose name starts with `.` (default false)
+    pub include_hidden: Option<bool>,
+    /// Keep files the user dismissed (default false)
+    pub include_dismissed: Option<bool>,
+    /// Drop files in these categories; a category also in `categories` is excluded
+    pub exclude_categories: Option<Vec<String>>,
+}
+
+/// Scan a directory for large files, with the optional parameters in `params`.
+/// Fails with a `ScanAlreadyRunning` error while another large-file scan of
+/// the same directory is in flight.
 #[command]
 pub async fn scan_large_files(
+    directory: String,
+    min_size_mb: u64,
+    params: Option<LargeFileScanParams>,
+) -> Result<Page<LargeFile>, String> {
+    let LargeFileScanParams {
+        categories,
+        max_depth,
+        exclude,
+        follow_symlinks,
+        skip_network_mounts,
+        filter,
+        sort_by,
+        offset,
+        limit,
+        include_hidden,
+        include_dismissed,
+        exclude_categories,
+    } = params.unwrap_or_default();
+    let category_filter = parse_categories(categories);
+    let options = build_scan_options(max_depth, exclude, follow_symlinks, skip_network_mounts)?
+        .with_include_hidden(include_hidden.unwrap_or(false));
+    let _active = scan_control::begin_scan(LARGE_FILES_SCAN, &directory, None)?;
+    let files = tokio::task::spawn_blocking(move || {
+        file_scanner::scan_large_files(&directory, min_size_mb, category_filter, &options)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let files = without_categories(files, exclude_categories);
+    let mut files = dismissals::without_dismissed(files, include_dismissed.unwrap_or(false), |f| f.path.as_str());
+    if let Some(query) = filter {
+        files = file_scanner::filter_by_name(files, &query);
+    }
+    sort_results(&mut files, sort_by.unwrap_or_default());
+    Ok(paginate(files, offset, limit))
+}
+
+/// Scan a directory for large files, streaming batches over `on_batch` as they are found.
+///
+/// Each batch is sorted by size, but batches arrive in discovery order. Use
+/// `scan_large_files` when a fully sorted list is needed. Returns the total
+/// number of files streamed. `scan_id` registers the scan so it can be
+/// paused, resumed or cancelled. Dismissed files are left out unless
+/// `include_dismissed` is set. Fails with a `ScanAlreadyRunning` error while
+/// another large-file scan of the same directory is in flight.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn scan_large_files_streaming(
     directory: String,
     min_size_mb: u64,
     categories: Option<Vec<String>>,
+    max_depth: Option<usize>,
+    exclude: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
+    skip_network_mounts: Option<bool>,
+    on_batch: Channel<Vec<LargeFile>>,
+    scan_id: Option<String>,
+    include_dismissed: Option<bool>,
+) -> Result<usize, String> {
+    let category_filter = parse_categories(categories);
+    let include_dismissed = include_dismissed.unwrap_or(false);
+    let options = build_scan_options(max_depth, exclude, follow_symlinks, skip_network_mounts)?;
+    let active = scan_control::begin_scan(LARGE_FILES_SCAN, &directory, scan_id.as_deref())?;
+    let options = options.with_control(active.control());
+    // The walk blocks while the scan is paused, so keep it off the async runtime
+    let (streamed, cancelled) = tokio::task::spawn_blocking(move || {
+        // Counted after the dismissal filter, so only files actually sent
+        let mut streamed = 0;
+        file_scanner::scan_large_files_streaming(
+            &directory,
+            min_size_mb,
+            category_filter,
+            &options,
+            STREAM_BATCH_SIZE,
+            |batch| {
+                let batch = dismissals::without_dismissed(batch, include_dismissed, |f| f.path.as_str());
+                if !batch.is_empty() {
+                    streamed += batch.len();
+                    let _ = on_batch.send(batch);
+                }
+            },
+        );
+        (streamed, options.is_cancelled())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if cancelled {
+        return Err("Scan cancelled".to_string());
+    }
+    Ok(streamed)
+}
+
+/// Scan a directory for large files, including per-category totals and any
+/// mount points that were skipped. Fails with a `ScanAlreadyRunning` error
+/// while another large-file scan of the same directory is in flight.
+#[command]
+pub async fn scan_large_files_with_report(
+    directory: String,
+    min_size_mb: u64,
+    categories: Option<Vec<String>>,
+    max_depth: Option<usize>,
+    exclude: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
+    skip_network_mounts: Option<bool>,
+) -> Result<LargeFileReport, String> {
+    let category_filter = parse_categories(categories);
+    let options = build_scan_options(max_depth, exclude, follow_symlinks, skip_network_mounts)?;
+    let _active = scan_control::begin_scan(LARGE_FILES_SCAN, &directory, None)?;
+    tokio::task::spawn_blocking(move || {
+        file_scanner::scan_large_files_with_report(&directory, min_size_mb, category_filter, &options)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Scan common directories for large files.
+/// `filter` keeps only files whose name contains it (case-insensitive), and
+/// `categories` keeps only files in those categories and `exclude_categories`
+/// drops files in those, as for `scan_large_files`; exclusion wins.
+/// Dismissed files are left out unless `include_dismissed` is set.
+#[command]
+pub async fn scan_common_large_files(
+    min_size_mb: u64,
+    filter: Option<String>,
+    categories: Option<Vec<String>>,
+    include_dismissed: Option<bool>,
+    exclude_categories: Option<Vec<String>>,
 ) -> Result<Vec<LargeFile>, String> {
-    let category_filter = categories.map(|cats| {
-        cats.iter()
-            .filter_map(|c| match c.as_str() {
-                "Video" => Some(FileCategory::Video),
-                "Image" => Some(FileCategory::Image),
-                "Audio" => Some(FileCategory::Audio),
-                "Archive" => Some(FileCategory::Archive),
-                "Document" => Some(FileCategory::Document),
-                "Application" => Some(FileCategory::Application),
-                "DiskImage" => Some(FileCategory::DiskImage),
-                _ => None,
-            })
-            .collect()
-    });
-    
-    Ok(file_scanner::scan_large_files(&directory, min_size_mb, category_filter))
-}
-
-/// Scan common directories for large files
+    let category_filter = parse_categories(categories);
+    let files = tokio::task::spawn_blocking(move || file_scanner::scan_common_directories(min_size_mb, category_filter))
+        .await
+        .map_err(|e| e.to_string())?;
+    let files = without_categories(files, exclude_categories);
+    let files = dismissals::without_dismissed(files, include_dismissed.unwrap_or(false), |f| f.path.as_str());
+    Ok(match filter {
+        Some(query) => file_scanner::filter_by_name(files, &query),
+        None => files,
+    })
+}
+
+/// The `n` largest files under `roots` (the home folder when empty), largest
+/// first. Only the current top `n` are kept while walking, so memory stays
+/// small however many files there are. Network and System volumes are skipped.
+/// `scan_id` registers the scan so it can be paused, resumed or cancelled.
+#[command]
+pub async fn top_n_largest_files(n: usize, roots: Vec<String>, scan_id: Option<String>) -> Result<Vec<LargeFile>, String> {
+    let roots: Vec<PathBuf> = if roots.is_empty() {
+        vec![dirs::home_dir().ok_or("Could not determine home directory")?]
+    } else {
+        roots.iter().map(PathBuf::from).collect()
+    };
+    let registration = scan_id.as_deref().map(scan_control::register);
+    let options = ScanOptions::default().with_control(registration.as_ref().map(|r| r.control()));
+
+    let (files, cancelled) = tokio::task::spawn_blocking(move || {
+        let files = file_scanner::top_n_largest_files(&roots, n, &options);
+        (files, options.is_cancelled())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if cancelled {
+        return Err("Scan cancelled".to_string());
+    }
+    Ok(files)
+}
+
+/// Find large files in the home directory via Spotlight, falling back to a
+/// directory walk when Spotlight is disabled or returns nothing
 #[command]
-pub async fn scan_common_large_files(min_size_mb: u64) -> Result<Vec<LargeFile>, String> {
-    Ok(file_scanner::scan_common_directories(min_size_mb))
+pub async fn scan_large_files_spotlight(min_size_mb: u64) -> Result<Vec<LargeFile>, String> {
+    Ok(file_scanner::scan_large_files_spotlight(min_size_mb))
 }
 
-/// Delete a file
+/// Scan common directories for large files modified within the last `within_hours`,
+/// newest first
+#[command]
+pub async fn scan_recent_large_files(min_size_mb: u64, within_hours: u64) -> Result<Vec<LargeFile>, String> {
+    tokio::task::spawn_blocking(move || file_scanner::scan_recent_large_files(min_size_mb, within_hours))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Scan a directory for zero-byte and tiny files (at or below `max_size_bytes`),
+/// sorted by directory
+#[command]
+pub async fn scan_tiny_files(root: String, max_size_bytes: u64) -> Result<Vec<LargeFile>, String> {
+    Ok(file_scanner::scan_tiny_files(&root, max_size_bytes, &ScanOptions::default()))
+}
+
+/// Delete a file, moving it to the Trash while safe mode is on
 #[command]
 pub async fn delete_file(path: String) -> Result<(), String> {
-    file_scanner::delete_file(&path)
+    file_scanner::delete_file(&path, Removal::from_settings())
+}
+
+/// Overwrite a file with random data `passes` times (1 to 3) and then delete it.
+/// Opt-in for sensitive files; only best-effort on SSDs because of wear leveling.
+#[command]
+pub async fn secure_delete(path: String, passes: u8) -> Result<(), String> {
+    file_scanner::secure_delete(&path, passes)
+}
+
+/// Delete several files, reporting the outcome for each path. Files go to the
+/// Trash while safe mode is on.
+#[command]
+pub async fn delete_files(paths: Vec<String>) -> Result<Vec<DeleteOutcome>, String> {
+    Ok(delete_files_with(paths, Removal::from_settings()))
+}
+
+fn delete_files_with(paths: Vec<String>, removal: Removal) -> Vec<DeleteOutcome> {
+    batch::for_each_path(paths, |path| file_scanner::delete_file(path, removal))
 }
 
 /// Move a file to trash
@@ -44,6 +298,12 @@ pub async fn move_file_to_trash(path: String) -> Result<(), String> {
     file_scanner::move_to_trash(&path)
 }
 
+/// Move several files to trash, reporting the outcome for each path
+#[command]
+pub async fn move_files_to_trash(paths: Vec<String>) -> Result<Vec<DeleteOutcome>, String> {
+    Ok(batch::for_each_path(paths, file_scanner::move_to_trash))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,30 +315,42 @@ mod tests {
         let _ = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, None).await;
     }
 
+    #[test]
+    fn test_large_file_scan_params_deserialize_partially() {
+        let params: LargeFileScanParams =
+            serde_json::from_str(r#"{"sort_by": "SizeAsc", "exclude_categories": ["Video"]}"#).unwrap();
+        assert_eq!(params.sort_by, Some(SortOrder::SizeAsc));
+        assert_eq!(params.exclude_categories, Some(vec!["Video".to_string()]));
+        assert!(params.categories.is_none());
+    }
+
     #[tokio::test]
     async fn test_scan_common_large_files() {
-        let _ = scan_common_large_files(10).await;
+        let _ = scan_common_large_files(10, None, None, None, None).await;
+    }
+
+    #[tokio::test]
+    async fn test_scan_common_large_files_video_only() {
+        let files = scan_common_large_files(10, None, Some(vec!["Video".to_string()]), None, None).await.unwrap();
+        assert!(files.iter().all(|f| f.category == FileCategory::Video));
     }
 
     #[tokio::test]
     async fn test_scan_large_files_with_video_category() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let result = scan_large_files(
-            temp_dir.path().to_string_lossy().to_string(),
-            0,
-            Some(vec!["Video".to_string()]),
-        )
-        .await;
+        let params = LargeFileScanParams {
+            categories: Some(vec!["Video".to_string()]),
+            ..Default::default()
+        };
+        let result = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 0, Some(params)).await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn test_scan_large_files_with_multiple_categories() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let result = scan_large_files(
-            temp_dir.path().to_string_lossy().to_string(),
-            0,
-            Some(vec![
+        let params = LargeFileScanParams {
+            categories: Some(vec![
                 "Video".to_string(),
                 "Image".to_string(),
                 "Audio".to_string(),
@@ -86,26 +358,194 @@ mod tests {
                 "Document".to_string(),
                 "Application".to_string(),
                 "DiskImage".to_string(),
+                "Font".to_string(),
+                "Ebook".to_string(),
+                "VirtualMachine".to_string(),
             ]),
-        )
-        .await;
+            ..Default::default()
+        };
+        let result = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 0, Some(params)).await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn test_scan_large_files_with_unknown_category() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let result = scan_large_files(
+        let params = LargeFileScanParams {
+            categories: Some(vec!["UnknownCategory".to_string()]),
+            ..Default::default()
+        };
+        let result = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 0, Some(params)).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scan_large_files_with_report() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let f = std::fs::File::create(temp_dir.path().join("movie.mkv")).unwrap();
+        f.set_len(1024 * 1024 * 2).unwrap();
+
+        let report = scan_large_files_with_report(
             temp_dir.path().to_string_lossy().to_string(),
-            0,
-            Some(vec!["UnknownCategory".to_string()]),
+            1,
+            None,
+            None,
+            None,
+            None,
+            None,
         )
-        .await;
-        assert!(result.is_ok());
+        .await
+        .unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.totals_by_category["Video"].count, 1);
+        assert!(report.skipped_mounts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scan_large_files_with_exclude() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skipped = temp_dir.path().join("Projects");
+        std::fs::create_dir(&skipped).unwrap();
+        let f = std::fs::File::create(skipped.join("build.zip")).unwrap();
+        f.set_len(1024 * 1024 * 2).unwrap();
+        let f = std::fs::File::create(temp_dir.path().join("keep.zip")).unwrap();
+        f.set_len(1024 * 1024 * 2).unwrap();
+
+        let params = LargeFileScanParams {
+            exclude: Some(vec!["**/Projects".to_string()]),
+            ..Default::default()
+        };
+        let files = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, Some(params))
+            .await
+            .unwrap();
+        assert_eq!(files.total_count, 1);
+        assert_eq!(files.items[0].name, "keep.zip");
+    }
+
+    #[tokio::test]
+    async fn test_scan_large_files_paginated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for (name, mb) in [("a.zip", 4), ("b.zip", 3), ("c.zip", 2)] {
+            let f = std::fs::File::create(temp_dir.path().join(name)).unwrap();
+            f.set_len(1024 * 1024 * mb).unwrap();
+        }
+
+        let params = LargeFileScanParams {
+            offset: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let page = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, Some(params))
+            .await
+            .unwrap();
+        assert_eq!(page.total_count, 3);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].name, "b.zip");
     }
 
     #[tokio::test]
-    async fn test_delete_file() {
+    async fn test_scan_large_files_with_name_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in ["Trip.mov", "trip-backup.zip", "other.zip"] {
+            let f = std::fs::File::create(temp_dir.path().join(name)).unwrap();
+            f.set_len(1024 * 1024 * 2).unwrap();
+        }
+
+        let params = LargeFileScanParams {
+            filter: Some("TRIP".to_string()),
+            ..Default::default()
+        };
+        let page = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, Some(params))
+            .await
+            .unwrap();
+        assert_eq!(page.total_count, 2);
+        assert!(page.items.iter().all(|f| f.name.to_lowercase().contains("trip")));
+    }
+
+    #[tokio::test]
+    async fn test_scan_large_files_sorted_smallest_first() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for (name, mb) in [("a.zip", 4), ("b.zip", 2), ("c.zip", 3)] {
+            let f = std::fs::File::create(temp_dir.path().join(name)).unwrap();
+            f.set_len(1024 * 1024 * mb).unwrap();
+        }
+
+        let params = LargeFileScanParams {
+            sort_by: Some(SortOrder::SizeAsc),
+            ..Default::default()
+        };
+        let page = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, Some(params))
+            .await
+            .unwrap();
+        let names: Vec<&str> = page.items.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["b.zip", "c.zip", "a.zip"]);
+    }
+
+    #[tokio::test]
+    async fn test_scan_large_files_exclude_categories_wins() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in ["Tool.dmg", "movie.mp4"] {
+            let f = std::fs::File::create(temp_dir.path().join(name)).unwrap();
+            f.set_len(2 * 1024 * 1024).unwrap();
+        }
+
+        let params = LargeFileScanParams {
+            categories: Some(vec!["DiskImage".to_string(), "Video".to_string()]),
+            exclude_categories: Some(vec!["DiskImage".to_string()]),
+            ..Default::default()
+        };
+        let page = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, Some(params))
+            .await
+            .unwrap();
+        let names: Vec<&str> = page.items.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["movie.mp4"]);
+    }
+
+    #[tokio::test]
+    async fn test_scan_large_files_invalid_exclude() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let params = LargeFileScanParams {
+            exclude: Some(vec!["[bad".to_string()]),
+            ..Default::default()
+        };
+        let result = scan_large_files(temp_dir.path().to_string_lossy().to_string(), 1, Some(params)).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_recent_large_files() {
+        let files = scan_recent_large_files(100, 24).await.unwrap();
+        assert!(files.windows(2).all(|w| w[0].last_modified >= w[1].last_modified));
+    }
+
+    #[tokio::test]
+    async fn test_top_n_largest_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("small.bin"), vec![1u8; 10]).unwrap();
+        std::fs::write(temp_dir.path().join("big.bin"), vec![1u8; 1000]).unwrap();
+
+        let files = top_n_largest_files(1, vec![temp_dir.path().to_string_lossy().to_string()], None).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "big.bin");
+    }
+
+    #[tokio::test]
+    async fn test_scan_tiny_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("empty.txt"), "").unwrap();
+
+        let files = scan_tiny_files(temp_dir.path().to_string_lossy().to_string(), 0).await.unwrap();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_move_files_to_trash_missing_paths() {
+        let outcomes = move_files_to_trash(vec!["/nonexistent/a".to_string()]).await.unwrap();
+        assert_eq!(outcomes.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_file() {
         // Create a temp file
         let temp_dir = tempfile::tempdir().unwrap();
         let file_path = temp_dir.path().join("test_delete.txt");
@@ -113,11 +553,31 @@ mod tests {
         writeln!(file, "delete me").unwrap();
         drop(file);
 
-        let result = delete_file(file_path.to_string_lossy().to_string()).await;
+        let result = file_scanner::delete_file(&file_path.to_string_lossy(), Removal::Permanent);
         assert!(result.is_ok());
         assert!(!file_path.exists());
     }
 
+    #[test]
+    fn test_delete_files_reports_each_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("a.txt");
+        std::fs::write(&file_path, "delete me").unwrap();
+        // delete_file leaves directories alone
+        let dir_path = temp_dir.path().join("folder");
+        std::fs::create_dir(&dir_path).unwrap();
+
+        let outcomes = delete_files_with(
+            vec![file_path.to_string_lossy().to_string(), dir_path.to_string_lossy().to_string()],
+            Removal::Permanent,
+        );
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|o| o.result.is_ok()));
+        assert!(!file_path.exists());
+        assert!(dir_path.exists());
+    }
+
     #[tokio::test]
     async fn test_delete_file_nonexistent() {
         // Functions return Ok(()) for nonexistent files by design (idempotent delete)
@@ -134,8 +594,12 @@ mod tests {
         writeln!(file, "trash me").unwrap();
         drop(file);
 
-        // Move to trash (may fail on CI without trash support)
-        let _ = move_file_to_trash(file_path.to_string_lossy().to_string()).await;
+        let result = move_file_to_trash(file_path.to_string_lossy().to_string()).await;
+        if result.is_ok() {
+            assert!(!file_path.exists());
+            #[cfg(unix)]
+            assert!(crate::removal::take_from_trash(&temp_dir.path().canonicalize().unwrap(), "test_trash_file.txt"));
+        }
     }
 }
 
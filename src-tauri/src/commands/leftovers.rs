@@ -1,7 +1,15 @@
-use crate::scanners::app_scanner::{self, InstalledApp, OrphanFile};
+use crate::batch::{self, DeleteOutcome};
+use crate::known_apps;
+use crate::pagination::{paginate, sort_results, Page, SortOrder};
+use crate::removal::Removal;
+use crate::scanners::app_scanner::{self, AppFootprint, InstalledApp, OrphanFile, OrphanType};
 use crate::scanners::app_data_scanner::{self, LargeAppData};
+use crate::scanners::file_scanner::CategoryTotal;
+use crate::scanners::launch_item_scanner::{self, OrphanLaunchItem};
+use crate::scanners::running_apps;
+use crate::scanners::size_cache;
+use std::collections::HashMap;
 use tauri::command;
-use std::process::Command;
 
 /// Scan for installed applications
 #[command]
@@ -9,33 +17,85 @@ pub async fn scan_installed_apps() -> Result<Vec<InstalledApp>, String> {
     Ok(app_scanner::scan_installed_apps())
 }
 
-/// Scan for orphan files from uninstalled apps
+/// Total disk usage of each installed app: its bundle plus its caches,
+/// containers, preferences, logs and application support data, largest first.
+/// Apps that are currently running are flagged.
 #[command]
-pub async fn scan_orphan_files() -> Result<Vec<OrphanFile>, String> {
-    Ok(app_scanner::scan_orphan_files())
+pub async fn scan_app_footprints() -> Result<Vec<AppFootprint>, String> {
+    let footprints = tokio::task::spawn_blocking(app_scanner::scan_app_footprints)
+        .await
+        .map_err(|e| e.to_string())?;
+    size_cache::save_global_cache();
+    Ok(footprints)
 }
 
-/// Scan for large application data folders (sorted by size)
+/// Scan for orphan files from uninstalled apps.
+/// `sort_by` orders the results (default largest first; orphans have no date),
+/// and `offset` and `limit` then select a page of them.
 #[command]
-pub async fn scan_large_app_data() -> Result<Vec<LargeAppData>, String> {
-    Ok(app_data_scanner::scan_large_app_data())
+pub async fn scan_orphan_files(
+    sort_by: Option<SortOrder>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Page<OrphanFile>, String> {
+    let mut orphans = app_scanner::scan_orphan_files();
+    sort_results(&mut orphans, sort_by.unwrap_or_default());
+    Ok(paginate(orphans, offset, limit))
 }
 
-/// Delete an orphan file or directory
+/// Scan for large application data folders (sorted by size).
+/// Returns at most `limit` folders (default 50).
 #[command]
-pub async fn delete_orphan(path: String) -> Result<(), String> {
+pub async fn scan_large_app_data(limit: Option<usize>) -> Result<Vec<LargeAppData>, String> {
+    let folders = app_data_scanner::scan_large_app_data(limit.unwrap_or(app_data_scanner::DEFAULT_LARGE_APP_DATA_LIMIT));
+    size_cache::save_global_cache();
+    Ok(folders)
+}
+
+/// Whether the app with `bundle_id` is currently running
+#[command]
+pub async fn is_app_running(bundle_id: String) -> Result<bool, String> {
+    Ok(running_apps::is_app_running(&bundle_id))
+}
+
+/// Delete an orphan file or directory. Data belonging to a running app is
+/// refused with an `AppIsRunning` error unless `force` is set.
+#[command]
+pub async fn delete_orphan(path: String, force: Option<bool>) -> Result<(), String> {
+    if !force.unwrap_or(false) {
+        running_apps::ensure_not_running(&path, &running_apps::running_apps())?;
+    }
     app_scanner::delete_orphan(&path)
 }
 
-/// Open a file or folder in Finder
+/// Delete several orphan files or directories, reporting the outcome for each path.
+/// Items that need administrator privileges share a single password prompt.
+/// Items belonging to a running app fail with `AppIsRunning` unless `force` is set.
 #[command]
-pub async fn reveal_in_finder(path: String) -> Result<(), String> {
-    Command::new("open")
-        .arg("-R")
-        .arg(&path)
-        .spawn()
-        .map_err(|e| format!("Failed to open Finder: {}", e))?;
-    Ok(())
+pub async fn delete_orphans(paths: Vec<String>, force: Option<bool>) -> Result<Vec<DeleteOutcome>, String> {
+    let running = if force.unwrap_or(false) { Vec::new() } else { running_apps::running_apps() };
+    tokio::task::spawn_blocking(move || {
+        crate::privileges::with_admin_session(|| {
+            batch::for_each_path(paths, |path| {
+                running_apps::ensure_not_running(path, &running)?;
+                app_scanner::delete_orphan(path)
+            })
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Scan LaunchAgents/LaunchDaemons for items whose program no longer exists
+#[command]
+pub async fn scan_orphan_launch_items() -> Result<Vec<OrphanLaunchItem>, String> {
+    Ok(launch_item_scanner::scan_orphan_launch_items())
+}
+
+/// Unload an orphaned launch item and remove its plist, to the Trash while safe mode is on
+#[command]
+pub async fn remove_orphan_launch_item(path: String) -> Result<(), String> {
+    launch_item_scanner::remove_orphan_launch_item(&path, Removal::from_settings())
 }
 
 /// Get total size of orphan files
@@ -45,6 +105,31 @@ pub async fn get_orphan_total_size() -> Result<u64, String> {
     Ok(orphans.iter().map(|o| o.size).sum())
 }
 
+/// Get orphan counts and sizes broken down by orphan type
+#[command]
+pub async fn get_orphan_totals_by_type() -> Result<HashMap<OrphanType, CategoryTotal>, String> {
+    let orphans = app_scanner::scan_orphan_files();
+    Ok(app_scanner::orphan_totals_by_type(&orphans))
+}
+
+/// List the apps and tools the user has marked as known, which are never flagged as orphans
+#[command]
+pub async fn list_known_apps() -> Result<Vec<String>, String> {
+    Ok(known_apps::load())
+}
+
+/// Mark an app or tool as known so its data is never flagged as orphaned
+#[command]
+pub async fn add_known_app(name: String) -> Result<Vec<String>, String> {
+    known_apps::add(&name)
+}
+
+/// Remove an app or tool from the known list
+#[command]
+pub async fn remove_known_app(name: String) -> Result<Vec<String>, String> {
+    known_apps::remove(&name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,9 +138,12 @@ mod tests {
     #[tokio::test]
     async fn test_scan_wrappers() {
         let _ = scan_installed_apps().await;
-        let _ = scan_orphan_files().await;
-        let _ = scan_large_app_data().await;
+        let _ = scan_orphan_files(None, None, None).await;
+        let _ = scan_large_app_data(None).await;
         let _ = get_orphan_total_size().await;
+        let _ = get_orphan_totals_by_type().await;
+        let _ = list_known_apps().await;
+        let _ = scan_orphan_launch_items().await;
     }
 
     #[tokio::test]
@@ -67,7 +155,7 @@ mod tests {
         writeln!(file, "orphan content").unwrap();
         drop(file);
 
-        let result = delete_orphan(file_path.to_string_lossy().to_string()).await;
+        let result = delete_orphan(file_path.to_string_lossy().to_string(), None).await;
         assert!(result.is_ok());
         assert!(!file_path.exists());
     }
@@ -83,7 +171,7 @@ mod tests {
         writeln!(file, "file in orphan dir").unwrap();
         drop(file);
 
-        let result = delete_orphan(sub_dir.to_string_lossy().to_string()).await;
+        let result = delete_orphan(sub_dir.to_string_lossy().to_string(), None).await;
         assert!(result.is_ok());
         assert!(!sub_dir.exists());
     }
@@ -91,21 +179,8 @@ mod tests {
     #[tokio::test]
     async fn test_delete_orphan_nonexistent() {
         // Functions return Ok(()) for nonexistent files by design (idempotent delete)
-        let result = delete_orphan("/nonexistent/path/orphan".to_string()).await;
+        let result = delete_orphan("/nonexistent/path/orphan".to_string(), None).await;
         assert!(result.is_ok());
     }
-
-    #[tokio::test]
-    async fn test_reveal_in_finder() {
-        // Create a temp file
-        let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("reveal_test.txt");
-        let mut file = std::fs::File::create(&file_path).unwrap();
-        writeln!(file, "reveal me").unwrap();
-        drop(file);
-
-        // On macOS, this should work; on CI/Linux it may fail but shouldn't panic
-        let _ = reveal_in_finder(file_path.to_string_lossy().to_string()).await;
-    }
 }
 
@@ -0,0 +1,223 @@
+use crate::privileges;
+use crate::process::run_command_with_timeout;
+use crate::scanners::disk_size::get_path_size;
+use crate::scanners::mail_scanner::{self, MailIndexReport};
+use crate::scanners::trash_scanner::remove_dir_contents;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::command;
+
+/// Launch Services registration tool, which isn't on the default PATH
+const LSREGISTER: &str = "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister";
+
+/// Rebuilding the Launch Services database rescans every app and can be slow
+const LSREGISTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// `qlmanage` and `getconf` return almost immediately
+const QUICKLOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// QuickLook's thumbnail cache folder inside the per-user cache dir
+const QUICKLOOK_CACHE_FOLDER: &str = "com.apple.QuickLook.thumbnailcache";
+
+/// Outcome of rebuilding the font caches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontCacheCleanResult {
+    pub bytes_freed: u64,
+    pub restart_recommended: bool,
+}
+
+/// Clear the per-user ATS font cache folder, returning bytes freed
+fn clear_user_font_cache(ats_cache: &Path) -> u64 {
+    remove_dir_contents(ats_cache)
+}
+
+/// Advanced: rebuild the font caches.
+///
+/// Runs `atsutil databases -remove` with administrator privileges (prompting the
+/// user), then clears `~/Library/Caches/com.apple.ATS`. macOS rebuilds the caches
+/// on demand; a logout or restart is recommended so running apps pick them up.
+#[command]
+pub async fn clean_font_caches() -> Result<FontCacheCleanResult, String> {
+    privileges::run_shell_with_admin("/usr/bin/atsutil databases -remove")?;
+
+    let bytes_freed = dirs::home_dir()
+        .map(|home| clear_user_font_cache(&home.join("Library").join("Caches").join("com.apple.ATS")))
+        .unwrap_or(0);
+
+    Ok(FontCacheCleanResult {
+        bytes_freed,
+        restart_recommended: true,
+    })
+}
+
+/// Flush the DNS cache so stale or poisoned lookups are resolved afresh.
+///
+/// Runs `dscacheutil -flushcache` and signals `mDNSResponder` with
+/// administrator privileges (prompting the user).
+#[command]
+pub async fn flush_dns_cache() -> Result<(), String> {
+    privileges::run_commands_with_admin(&[
+        &["/usr/bin/dscacheutil", "-flushcache"],
+        &["/usr/bin/killall", "-HUP", "mDNSResponder"],
+    ])
+}
+
+/// Outcome of resetting the QuickLook thumbnail cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickLookCacheResult {
+    /// Location of the thumbnail cache, if it could be found
+    pub cache_path: Option<String>,
+    /// Space released by the reset; `None` when the cache couldn't be located
+    pub bytes_freed: Option<u64>,
+}
+
+/// Parse the folder printed by `getconf DARWIN_USER_CACHE_DIR`
+fn parse_user_cache_dir(output: &str) -> Option<PathBuf> {
+    let trimmed = output.trim();
+    trimmed.starts_with('/').then(|| PathBuf::from(trimmed))
+}
+
+/// The per-user cache folder under `/private/var/folders`, via `getconf`
+fn darwin_user_cache_dir() -> Option<PathBuf> {
+    let output = run_command_with_timeout("/usr/bin/getconf", ["DARWIN_USER_CACHE_DIR"], QUICKLOOK_TIMEOUT).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_user_cache_dir(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Fix broken or stale Finder previews by resetting the QuickLook thumbnail
+/// cache with `qlmanage -r cache`. Runs as the current user; thumbnails are
+/// regenerated as files are previewed again. The cache's size is reported
+/// when its folder can be located.
+#[command]
+pub async fn clean_quicklook_cache() -> Result<QuickLookCacheResult, String> {
+    let cache_dir = darwin_user_cache_dir()
+        .map(|dir| dir.join(QUICKLOOK_CACHE_FOLDER))
+        .filter(|dir| dir.is_dir());
+    let size_before = cache_dir.as_deref().map(get_path_size);
+
+    let output = run_command_with_timeout("/usr/bin/qlmanage", ["-r", "cache"], QUICKLOOK_TIMEOUT)?;
+    if !output.status.success() {
+        return Err(format!(
+            "qlmanage failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let bytes_freed = cache_dir
+        .as_deref()
+        .zip(size_before)
+        .map(|(dir, before)| before.saturating_sub(get_path_size(dir)));
+    Ok(QuickLookCacheResult {
+        cache_path: cache_dir.map(|dir| dir.to_string_lossy().to_string()),
+        bytes_freed,
+    })
+}
+
+/// Check that `volume` is the boot volume or a mounted volume's root
+fn validate_spotlight_volume(volume: &Path) -> Result<(), String> {
+    let is_volume_root = volume == Path::new("/")
+        || (volume.parent() == Some(Path::new("/Volumes")) && volume.file_name().is_some());
+    if !is_volume_root {
+        return Err(format!("{} is not a volume", volume.display()));
+    }
+    if !volume.is_dir() {
+        return Err(format!("Volume {} is not mounted", volume.display()));
+    }
+    Ok(())
+}
+
+/// Erase and rebuild the Spotlight index of `volume` ("/" or "/Volumes/<name>")
+/// with `mdutil -E`. Requires administrator privileges. Indexing then runs in
+/// the background and can take a while.
+#[command]
+pub async fn rebuild_spotlight_index(volume: String) -> Result<(), String> {
+    validate_spotlight_volume(Path::new(&volume))?;
+    privileges::run_commands_with_admin(&[&["/usr/bin/mdutil", "-E", &volume]])
+}
+
+/// Rebuild the Launch Services database, which fixes duplicate "Open With"
+/// entries and wrong default apps. Runs as the current user.
+#[command]
+pub async fn rebuild_launch_services() -> Result<(), String> {
+    let output = run_command_with_timeout(
+        LSREGISTER,
+        ["-kill", "-r", "-domain", "local", "-domain", "user"],
+        LSREGISTER_TIMEOUT,
+    )?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "lsregister failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Sizes of Apple Mail's `Envelope Index` databases, which can bloat to
+/// several gigabytes or become corrupted, and whether Mail is running
+#[command]
+pub async fn scan_mail_indexes() -> Result<MailIndexReport, String> {
+    let mail_dir = mail_scanner::mail_dir().ok_or("Could not determine home directory")?;
+    let indexes = mail_scanner::scan_mail_indexes_in(&mail_dir)?;
+    Ok(MailIndexReport {
+        total_size: indexes.iter().map(|i| i.size).sum(),
+        indexes,
+        mail_running: mail_scanner::is_mail_running(),
+    })
+}
+
+/// Delete Mail's envelope indexes so Mail rebuilds them on its next launch,
+/// returning the bytes freed. Mail must be quit first; this fails while it is
+/// running. Rebuilding re-reads every mailbox and can take a while on large accounts.
+#[command]
+pub async fn rebuild_mail_index() -> Result<u64, String> {
+    let mail_dir = mail_scanner::mail_dir().ok_or("Could not determine home directory")?;
+    mail_scanner::rebuild_mail_index_in(&mail_dir, mail_scanner::is_mail_running())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_clear_user_font_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ats = temp_dir.path().join("com.apple.ATS");
+        fs::create_dir_all(ats.join("annex")).unwrap();
+        fs::write(ats.join("annex").join("fonts.db"), vec![0u8; 8192]).unwrap();
+
+        assert!(clear_user_font_cache(&ats) >= 8192);
+        assert!(ats.exists());
+        assert_eq!(fs::read_dir(&ats).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_parse_user_cache_dir() {
+        assert_eq!(
+            parse_user_cache_dir("/var/folders/ab/xyz123/C/\n"),
+            Some(PathBuf::from("/var/folders/ab/xyz123/C/"))
+        );
+        assert_eq!(parse_user_cache_dir(""), None);
+        assert_eq!(parse_user_cache_dir("undefined"), None);
+    }
+
+    #[test]
+    fn test_validate_spotlight_volume() {
+        assert!(validate_spotlight_volume(Path::new("/")).is_ok());
+        assert!(validate_spotlight_volume(Path::new("/Users")).is_err());
+        assert!(validate_spotlight_volume(Path::new("/Volumes")).is_err());
+        assert!(validate_spotlight_volume(Path::new("/Volumes/Backup/folder")).is_err());
+        assert!(validate_spotlight_volume(Path::new("/Volumes/NotMounted-quick-cleaner")).is_err());
+    }
+
+    #[test]
+    fn test_clear_user_font_cache_missing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(clear_user_font_cache(&temp_dir.path().join("missing")), 0);
+    }
+}
@@ -1,7 +1,23 @@
 // Command handlers
+pub mod admin;
+pub mod browser;
 pub mod cache;
+pub mod cleanup;
+pub mod common;
+pub mod config;
 pub mod developer;
+pub mod dismissed;
+pub mod downloads;
+pub mod languages;
 pub mod leftovers;
+pub mod maintenance;
+pub mod preview;
+pub mod quarantined;
+pub mod scans;
 pub mod large_files;
 pub mod duplicates;
+pub mod export;
+pub mod history;
+pub mod startup;
 pub mod system_info;
+pub mod trash;
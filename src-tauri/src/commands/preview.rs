@@ -0,0 +1,134 @@
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::command;
+
+use crate::process::run_command_with_timeout;
+
+/// How long to wait for Quick Look to render a thumbnail
+const THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Bounds for the requested thumbnail edge length
+const MIN_THUMBNAIL_PX: u32 = 16;
+const MAX_THUMBNAIL_PX: u32 = 1024;
+
+/// Folder under the system temp dir where rendered thumbnails are kept
+fn thumbnail_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("macos-quick-cleaner-thumbnails")
+}
+
+/// Cache file for `path` at `max_px`, keyed by path and modification time so
+/// an edited file gets a fresh thumbnail
+fn thumbnail_cache_path(cache_dir: &Path, path: &Path, max_px: u32) -> Result<PathBuf, String> {
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(modified.as_nanos().to_le_bytes());
+    hasher.update(max_px.to_le_bytes());
+    Ok(cache_dir.join(format!("{}.png", hex::encode(hasher.finalize()))))
+}
+
+/// Render a PNG thumbnail of `path` into `dest` with Quick Look
+fn render_thumbnail(path: &Path, max_px: u32, dest: &Path) -> Result<(), String> {
+    let work_dir = dest.with_extension("work");
+    fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+
+    let size = max_px.to_string();
+    let result = run_command_with_timeout(
+        "qlmanage",
+        [
+            "-t".as_ref(),
+            "-s".as_ref(),
+            size.as_ref(),
+            "-o".as_ref(),
+            work_dir.as_os_str(),
+            path.as_os_str(),
+        ],
+        THUMBNAIL_TIMEOUT,
+    )
+    .map_err(String::from)
+    .and_then(|_| {
+        // qlmanage names its output after the source file and exits 0 even
+        // when it can't preview the type, so look for the file it wrote
+        let file_name = path.file_name().ok_or("Invalid file name")?;
+        let mut rendered = file_name.to_os_string();
+        rendered.push(".png");
+        fs::rename(work_dir.join(rendered), dest)
+            .map_err(|_| format!("No preview available for {}", path.display()))
+    });
+
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+/// Thumbnail for `path` as a PNG data URL, rendered on first use and then cached
+fn thumbnail_data_url(cache_dir: &Path, path: &Path, max_px: u32) -> Result<String, String> {
+    if !path.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+
+    let max_px = max_px.clamp(MIN_THUMBNAIL_PX, MAX_THUMBNAIL_PX);
+    let cached = thumbnail_cache_path(cache_dir, path, max_px)?;
+    if !cached.exists() {
+        render_thumbnail(path, max_px, &cached)?;
+    }
+
+    let png = fs::read(&cached).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+    ))
+}
+
+/// Generate a small preview of an image, video or other Quick Look-able file,
+/// returned as a PNG data URL. Unsupported types return an error the UI can
+/// ignore and fall back to an icon.
+#[command]
+pub async fn generate_thumbnail(path: String, max_px: u32) -> Result<String, String> {
+    thumbnail_data_url(&thumbnail_cache_dir(), Path::new(&path), max_px)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_cache_path_depends_on_size_and_mtime() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("photo.jpg");
+        fs::write(&file, "jpeg").unwrap();
+
+        let small = thumbnail_cache_path(temp_dir.path(), &file, 64).unwrap();
+        assert_eq!(small, thumbnail_cache_path(temp_dir.path(), &file, 64).unwrap());
+        assert_ne!(small, thumbnail_cache_path(temp_dir.path(), &file, 128).unwrap());
+
+        let f = fs::File::options().write(true).open(&file).unwrap();
+        f.set_modified(UNIX_EPOCH + Duration::from_secs(1)).unwrap();
+        assert_ne!(small, thumbnail_cache_path(temp_dir.path(), &file, 64).unwrap());
+    }
+
+    #[test]
+    fn test_cached_thumbnail_is_reused() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = temp_dir.path().join("cache");
+        fs::create_dir(&cache_dir).unwrap();
+        let file = temp_dir.path().join("movie.mov");
+        fs::write(&file, "video").unwrap();
+        fs::write(thumbnail_cache_path(&cache_dir, &file, 64).unwrap(), b"png").unwrap();
+
+        let url = thumbnail_data_url(&cache_dir, &file, 64).unwrap();
+        assert_eq!(url, "data:image/png;base64,cG5n");
+    }
+
+    #[tokio::test]
+    async fn test_generate_thumbnail_missing_file() {
+        assert!(generate_thumbnail("/nonexistent/photo.jpg".to_string(), 128).await.is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use crate::quarantine::{self, QuarantinedItem};
+use tauri::command;
+
+/// Move an item to the quarantine folder instead of deleting it, so it can be
+/// restored until it is purged. Works across volumes by copying and then deleting.
+#[command]
+pub async fn quarantine_path(path: String) -> Result<QuarantinedItem, String> {
+    tokio::task::spawn_blocking(move || quarantine::quarantine(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Everything currently in quarantine, newest first
+#[command]
+pub async fn list_quarantine() -> Result<Vec<QuarantinedItem>, String> {
+    quarantine::list()
+}
+
+/// Move the quarantined item with `id` back to its original path. Fails if
+/// something has since been created there.
+#[command]
+pub async fn restore_quarantine(id: String) -> Result<QuarantinedItem, String> {
+    tokio::task::spawn_blocking(move || quarantine::restore(&id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Permanently delete items quarantined more than `older_than_days` days ago
+/// (0 empties the quarantine), returning the bytes freed
+#[command]
+pub async fn purge_quarantine(older_than_days: u64) -> Result<u64, String> {
+    tokio::task::spawn_blocking(move || quarantine::purge(older_than_days))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quarantine_then_restore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("cache.db");
+        std::fs::write(&file, "cached").unwrap();
+
+        let item = quarantine_path(file.to_string_lossy().to_string()).await.unwrap();
+        assert!(!file.exists());
+        assert!(list_quarantine().await.unwrap().iter().any(|i| i.id == item.id));
+
+        restore_quarantine(item.id.clone()).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "cached");
+        assert!(!list_quarantine().await.unwrap().iter().any(|i| i.id == item.id));
+    }
+}
@@ -0,0 +1,32 @@
+use crate::scanners::scan_control;
+use tauri::command;
+
+/// Pause a running scan. It stops reading the disk until resumed or cancelled.
+#[command]
+pub async fn pause_scan(scan_id: String) -> Result<(), String> {
+    scan_control::pause_scan(&scan_id)
+}
+
+/// Resume a paused scan where it left off
+#[command]
+pub async fn resume_scan(scan_id: String) -> Result<(), String> {
+    scan_control::resume_scan(&scan_id)
+}
+
+/// Cancel a running or paused scan
+#[command]
+pub async fn cancel_scan(scan_id: String) -> Result<(), String> {
+    scan_control::cancel_scan(&scan_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unknown_scan_id() {
+        assert!(pause_scan("no-such-scan".to_string()).await.is_err());
+        assert!(resume_scan("no-such-scan".to_string()).await.is_err());
+        assert!(cancel_scan("no-such-scan".to_string()).await.is_err());
+    }
+}
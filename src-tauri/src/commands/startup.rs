@@ -0,0 +1,24 @@
+use crate::scanners::login_item_scanner::{self, LoginItem};
+use tauri::command;
+
+/// List everything that starts at login: launch agents and "Open at Login" apps
+#[command]
+pub async fn scan_login_items() -> Result<Vec<LoginItem>, String> {
+    Ok(login_item_scanner::scan_login_items())
+}
+
+/// Enable or disable a launch agent at login
+#[command]
+pub async fn set_login_item_enabled(path: String, enabled: bool) -> Result<(), String> {
+    login_item_scanner::set_login_item_enabled(&path, enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scan_login_items() {
+        assert!(scan_login_items().await.is_ok());
+    }
+}
@@ -1,14 +1,65 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
+use crate::process::run_command_with_timeout;
 use tauri::command;
 
+/// Placeholder used when a system query fails
+const UNKNOWN: &str = "Unknown";
+
+/// How long to wait for system query tools such as `sysctl` and `vm_stat`
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for `purge`, which can take a while on busy systems
+const PURGE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// File under the app support dir holding disk usage snapshots
+const DISK_HISTORY_FILE: &str = "disk_usage_history.json";
+
+/// Default number of days of disk usage history to keep
+pub const DEFAULT_HISTORY_RETENTION_DAYS: u64 = 365;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// JXA script printing the boot volume's "available for important usage" capacity,
+/// the figure Finder and About This Mac show as available
+const FINDER_FREE_SPACE_SCRIPT: &str = "ObjC.import('Foundation'); \
+var value = Ref(); \
+$.NSURL.fileURLWithPath('/').getResourceValueForKeyError(value, $.NSURLVolumeAvailableCapacityForImportantUsageKey, null); \
+String(ObjC.unwrap(value[0]));";
+
+/// Why the Finder figure differs from statvfs, returned alongside both numbers
+const FINDER_FREE_SPACE_NOTE: &str = "Finder's available space includes purgeable data \
+(local Time Machine snapshots, iCloud files that can be downloaded again, caches) \
+that macOS frees automatically when space is needed. The statvfs figure only counts \
+blocks that are free right now, so on APFS it is usually lower.";
+
 /// Disk usage information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskUsage {
     pub total_bytes: u64,
-    pub free_bytes: u64,
+    pub free_bytes: u64, // Raw statvfs free space
     pub used_bytes: u64,
     pub used_percentage: f64,
+    #[serde(default)]
+    pub finder_available_bytes: Option<u64>, // What Finder shows as available, if known
+}
+
+/// Free space as Finder reports it, next to the raw statvfs figure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinderFreeSpace {
+    pub available_bytes: u64,
+    pub statvfs_free_bytes: u64,
+    pub note: String,
+}
+
+/// Disk usage recorded at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageSnapshot {
+    pub timestamp: u64, // Unix timestamp
+    pub disk_usage: DiskUsage,
 }
 
 /// System information
@@ -19,11 +70,138 @@ pub struct SystemInfo {
     pub username: String,
     pub home_directory: String,
     pub disk_usage: DiskUsage,
+    pub model_identifier: String,
+    pub cpu_model: String,
+    pub cpu_cores: u32,
+    pub total_memory_bytes: u64,
+    pub used_memory_bytes: u64,
 }
 
-/// Get disk usage for the root volume
-fn get_disk_usage() -> DiskUsage {
-    // Use statvfs to get disk info
+/// System memory pressure as reported by the kernel
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum MemoryPressureLevel {
+    Normal,
+    Warning,
+    Critical,
+    Unknown,
+}
+
+/// Memory page breakdown and pressure level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryPressure {
+    pub page_size: u64,
+    pub wired_pages: u64,
+    pub active_pages: u64,
+    pub inactive_pages: u64,
+    pub free_pages: u64,
+    pub free_bytes: u64,
+    pub level: MemoryPressureLevel,
+}
+
+/// Free memory before and after running `purge`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeResult {
+    pub free_bytes_before: u64,
+    pub free_bytes_after: u64,
+}
+
+/// Page counts reported by `vm_stat`, keyed by the label before the colon
+#[derive(Debug, Clone, Default)]
+struct VmStat {
+    page_size: u64,
+    pages: HashMap<String, u64>,
+}
+
+impl VmStat {
+    /// Get a page count by label, converted to bytes
+    fn bytes(&self, label: &str) -> u64 {
+        self.pages.get(label).copied().unwrap_or(0) * self.page_size
+    }
+}
+
+/// Run a command and return its trimmed stdout, or None if it failed or printed nothing
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = run_command_with_timeout(program, args, QUERY_TIMEOUT).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stdout.is_empty() {
+        None
+    } else {
+        Some(stdout)
+    }
+}
+
+/// Read a value with `sysctl -n`
+fn sysctl(name: &str) -> Option<String> {
+    command_output("sysctl", &["-n", name])
+}
+
+/// Parse `vm_stat` output into page counts
+fn parse_vm_stat(output: &str) -> VmStat {
+    let mut stat = VmStat::default();
+    
+    for line in output.lines() {
+        // Header: "Mach Virtual Memory Statistics: (page size of 16384 bytes)"
+        if let Some(rest) = line.split("page size of ").nth(1) {
+            stat.page_size = rest
+                .split_whitespace()
+                .next()
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0);
+            continue;
+        }
+        
+        if let Some((label, value)) = line.split_once(':') {
+            if let Ok(count) = value.trim().trim_end_matches('.').parse::<u64>() {
+                stat.pages.insert(label.trim().to_string(), count);
+            }
+        }
+    }
+    
+    stat
+}
+
+/// Read memory statistics via `vm_stat`
+fn get_vm_stat() -> Option<VmStat> {
+    command_output("vm_stat", &[]).map(|output| parse_vm_stat(&output))
+}
+
+/// Map `kern.memorystatus_vm_pressure_level` (1 = normal, 2 = warn, 4 = critical)
+fn pressure_level_from_sysctl(value: &str) -> MemoryPressureLevel {
+    match value.trim() {
+        "1" => MemoryPressureLevel::Normal,
+        "2" => MemoryPressureLevel::Warning,
+        "4" => MemoryPressureLevel::Critical,
+        _ => MemoryPressureLevel::Unknown,
+    }
+}
+
+/// Build a memory pressure snapshot from vm_stat counts and the kernel level
+fn memory_pressure_from(stat: &VmStat, level: MemoryPressureLevel) -> MemoryPressure {
+    let page = |label: &str| stat.pages.get(label).copied().unwrap_or(0);
+    
+    MemoryPressure {
+        page_size: stat.page_size,
+        wired_pages: page("Pages wired down"),
+        active_pages: page("Pages active"),
+        inactive_pages: page("Pages inactive"),
+        free_pages: page("Pages free"),
+        free_bytes: stat.bytes("Pages free"),
+        level,
+    }
+}
+
+/// Memory in use: active + wired + compressed pages
+fn used_memory_from_vm_stat(stat: &VmStat) -> u64 {
+    stat.bytes("Pages active")
+        + stat.bytes("Pages wired down")
+        + stat.bytes("Pages occupied by compressor")
+}
+
+/// Total and available bytes of the root volume from statvfs
+fn root_volume_space() -> Option<(u64, u64)> {
     #[cfg(target_os = "macos")]
     {
         use std::ffi::CString;
@@ -38,18 +216,25 @@ fn get_disk_usage() -> DiskUsage {
                 let block_size = stat.f_frsize;
                 let total = stat.f_blocks as u64 * block_size;
                 let free = stat.f_bavail as u64 * block_size;
-                let used = total - free;
-                let percentage = (used as f64 / total as f64) * 100.0;
-                
-                return DiskUsage {
-                    total_bytes: total,
-                    free_bytes: free,
-                    used_bytes: used,
-                    used_percentage: percentage,
-                };
+                return Some((total, free));
             }
         }
     }
+    None
+}
+
+/// Get disk usage for the root volume
+fn get_disk_usage() -> DiskUsage {
+    if let Some((total, free)) = root_volume_space() {
+        let used = total - free;
+        return DiskUsage {
+            total_bytes: total,
+            free_bytes: free,
+            used_bytes: used,
+            used_percentage: percent_of(used, total),
+            finder_available_bytes: get_finder_available_bytes(),
+        };
+    }
     
     // Fallback
     DiskUsage {
@@ -57,7 +242,36 @@ fn get_disk_usage() -> DiskUsage {
         free_bytes: 0,
         used_bytes: 0,
         used_percentage: 0.0,
+        finder_available_bytes: None,
+    }
+}
+
+/// `size` as a percentage of `total`, or 0 when the total is unknown (0)
+pub fn percent_of(size: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
     }
+    (size as f64 / total as f64) * 100.0
+}
+
+/// Total capacity of the boot volume, or 0 if it can't be read
+pub fn boot_volume_capacity() -> u64 {
+    root_volume_space().map(|(total, _)| total).unwrap_or(0)
+}
+
+/// Parse the number printed by the JXA capacity script
+fn parse_capacity_output(output: &str) -> Option<u64> {
+    let trimmed = output.trim();
+    trimmed
+        .parse::<u64>()
+        .ok()
+        .or_else(|| trimmed.parse::<f64>().ok().filter(|v| v.is_finite() && *v >= 0.0).map(|v| v as u64))
+}
+
+/// Boot volume space available for important usage, via NSURL resource values
+fn get_finder_available_bytes() -> Option<u64> {
+    command_output("osascript", &["-l", "JavaScript", "-e", FINDER_FREE_SPACE_SCRIPT])
+        .and_then(|output| parse_capacity_output(&output))
 }
 
 /// Get system information
@@ -73,42 +287,231 @@ pub async fn get_system_info() -> Result<SystemInfo, String> {
         .map(|h| h.to_string_lossy().to_string())
         .unwrap_or_else(|_| "Unknown".to_string());
     
+    let os_version = command_output("sw_vers", &["-productVersion"])
+        .map(|v| format!("macOS {}", v))
+        .unwrap_or_else(|| UNKNOWN.to_string());
+    
+    let model_identifier = sysctl("hw.model").unwrap_or_else(|| UNKNOWN.to_string());
+    let cpu_model = sysctl("machdep.cpu.brand_string").unwrap_or_else(|| UNKNOWN.to_string());
+    
+    let cpu_cores = sysctl("hw.ncpu")
+        .and_then(|n| n.parse().ok())
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get() as u32))
+        .unwrap_or(0);
+    
+    let total_memory_bytes = sysctl("hw.memsize")
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0);
+    
+    let used_memory_bytes = get_vm_stat()
+        .map(|stat| used_memory_from_vm_stat(&stat))
+        .unwrap_or(0);
+    
     Ok(SystemInfo {
-        os_version: "macOS".to_string(),
+        os_version,
         hostname,
         username,
         home_directory: home_dir,
         disk_usage: get_disk_usage(),
+        model_identifier,
+        cpu_model,
+        cpu_cores,
+        total_memory_bytes,
+        used_memory_bytes,
     })
 }
 
+/// Get the current memory page breakdown and pressure level
+#[command]
+pub async fn get_memory_pressure() -> Result<MemoryPressure, String> {
+    let stat = get_vm_stat().ok_or("Failed to read memory statistics")?;
+    let level = sysctl("kern.memorystatus_vm_pressure_level")
+        .map(|v| pressure_level_from_sysctl(&v))
+        .unwrap_or(MemoryPressureLevel::Unknown);
+    Ok(memory_pressure_from(&stat, level))
+}
+
+/// Free inactive memory by running the `purge` tool.
+///
+/// Escalates to an administrator prompt if `purge` cannot run as the current
+/// user. Note that macOS manages inactive memory well on its own, so this has
+/// limited real benefit; it is offered because users coming from other
+/// cleaners expect it.
+#[command]
+pub async fn purge_inactive_memory() -> Result<PurgeResult, String> {
+    let free_bytes = || get_vm_stat().map(|s| s.bytes("Pages free")).unwrap_or(0);
+    let free_bytes_before = free_bytes();
+    
+    let purged = run_command_with_timeout("purge", Vec::<String>::new(), PURGE_TIMEOUT)
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !purged {
+        crate::privileges::run_shell_with_admin("/usr/sbin/purge")?;
+    }
+    
+    Ok(PurgeResult {
+        free_bytes_before,
+        free_bytes_after: free_bytes(),
+    })
+}
+
+/// Current time as a Unix timestamp
+fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Append a snapshot to the history file, dropping entries older than the retention window
+fn append_snapshot(path: &Path, snapshot: DiskUsageSnapshot, retention_days: u64) -> Result<(), String> {
+    let cutoff = snapshot.timestamp.saturating_sub(retention_days.saturating_mul(SECONDS_PER_DAY));
+    let mut history: Vec<DiskUsageSnapshot> = crate::storage::read_json(path).unwrap_or_default();
+    history.retain(|s| s.timestamp >= cutoff);
+    history.push(snapshot);
+    crate::storage::write_json_atomic(path, &history)
+}
+
+/// Load snapshots recorded at or after `since`
+fn load_history_since(path: &Path, since: u64) -> Vec<DiskUsageSnapshot> {
+    let history: Vec<DiskUsageSnapshot> = crate::storage::read_json(path).unwrap_or_default();
+    history.into_iter().filter(|s| s.timestamp >= since).collect()
+}
+
+/// Record the current disk usage to the history log.
+///
+/// Entries older than `retention_days` (default from settings, 365 days) are pruned on each write.
+#[command]
+pub async fn record_disk_usage_snapshot(retention_days: Option<u64>) -> Result<DiskUsageSnapshot, String> {
+    let snapshot = DiskUsageSnapshot {
+        timestamp: now_timestamp(),
+        disk_usage: get_disk_usage(),
+    };
+    let path = crate::storage::app_support_file(DISK_HISTORY_FILE)?;
+    append_snapshot(
+        &path,
+        snapshot.clone(),
+        retention_days.unwrap_or_else(|| crate::commands::config::load_settings().disk_history_retention_days),
+    )?;
+    Ok(snapshot)
+}
+
+/// Get disk usage snapshots from the last `days` days, oldest first
+#[command]
+pub async fn get_disk_usage_history(days: u64) -> Result<Vec<DiskUsageSnapshot>, String> {
+    let path = crate::storage::app_support_file(DISK_HISTORY_FILE)?;
+    let since = now_timestamp().saturating_sub(days.saturating_mul(SECONDS_PER_DAY));
+    Ok(load_history_since(&path, since))
+}
+
 /// Get disk usage
 #[command]
 pub async fn get_disk_usage_info() -> Result<DiskUsage, String> {
     Ok(get_disk_usage())
 }
 
-/// Format bytes to human-readable string
+/// Get the free space Finder and About This Mac show for the boot volume.
+///
+/// This includes purgeable space, so it is usually larger than the raw
+/// statvfs free space in `DiskUsage::free_bytes`; `note` explains why.
 #[command]
-pub async fn format_bytes(bytes: u64) -> Result<String, String> {
-    let kb = 1024_u64;
-    let mb = kb * 1024;
-    let gb = mb * 1024;
-    let tb = gb * 1024;
-    
-    let result = if bytes >= tb {
-        format!("{:.2} TB", bytes as f64 / tb as f64)
-    } else if bytes >= gb {
-        format!("{:.2} GB", bytes as f64 / gb as f64)
-    } else if bytes >= mb {
-        format!("{:.2} MB", bytes as f64 / mb as f64)
-    } else if bytes >= kb {
-        format!("{:.2} KB", bytes as f64 / kb as f64)
-    } else {
-        format!("{} B", bytes)
+pub async fn get_finder_free_space() -> Result<FinderFreeSpace, String> {
+    let available_bytes = get_finder_available_bytes().ok_or("Failed to read available capacity from Finder")?;
+    Ok(FinderFreeSpace {
+        available_bytes,
+        statvfs_free_bytes: get_disk_usage().free_bytes,
+        note: FINDER_FREE_SPACE_NOTE.to_string(),
+    })
+}
+
+/// Unit system used when formatting sizes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum UnitSystem {
+    /// Powers of 1024, labelled KiB/MiB/GiB/TiB
+    #[default]
+    Binary,
+    /// Powers of 1000, labelled KB/MB/GB/TB
+    Decimal,
+}
+
+/// Default number of decimal places for formatted sizes
+const DEFAULT_DECIMALS: usize = 2;
+
+/// Format a byte count using the given unit system and precision.
+/// When `trim` is set, trailing zeros (and a dangling decimal point) are removed.
+fn format_size(bytes: u64, unit_system: UnitSystem, decimals: usize, trim: bool) -> String {
+    let (base, labels) = match unit_system {
+        UnitSystem::Binary => (1024_u64, ["KiB", "MiB", "GiB", "TiB"]),
+        UnitSystem::Decimal => (1000_u64, ["KB", "MB", "GB", "TB"]),
     };
     
-    Ok(result)
+    let mut unit = base.pow(labels.len() as u32);
+    for label in labels.iter().rev() {
+        if bytes >= unit {
+            let mut value = format!("{:.*}", decimals, bytes as f64 / unit as f64);
+            if trim && value.contains('.') {
+                value = value.trim_end_matches('0').trim_end_matches('.').to_string();
+            }
+            return format!("{} {}", value, label);
+        }
+        unit /= base;
+    }
+    
+    format!("{} B", bytes)
+}
+
+/// Format bytes to human-readable string.
+///
+/// Defaults to binary units with 2 decimal places; `trim` drops trailing zeros
+/// (e.g. "2.00 GiB" becomes "2 GiB").
+#[command]
+pub async fn format_bytes(
+    bytes: u64,
+    unit_system: Option<UnitSystem>,
+    decimals: Option<usize>,
+    trim: Option<bool>,
+) -> Result<String, String> {
+    Ok(format_size(
+        bytes,
+        unit_system.unwrap_or_default(),
+        decimals.unwrap_or(DEFAULT_DECIMALS),
+        trim.unwrap_or(false),
+    ))
+}
+
+/// Parse a human-readable size such as "500MB" or "2.5 gb" into bytes.
+///
+/// Accepts an optional decimal value, optional whitespace and a
+/// case-insensitive B/KB/MB/GB/TB suffix. Both KB and KiB style suffixes are
+/// treated as 1024-based, matching the default `format_bytes` output.
+/// A bare number is treated as bytes.
+#[command]
+pub async fn parse_bytes(input: String) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let split_at = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split_at);
+    
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size: '{}'", input))?;
+    
+    let multiplier: u64 = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" | "KIB" => 1024,
+        "MB" | "MIB" => 1024 * 1024,
+        "GB" | "GIB" => 1024 * 1024 * 1024,
+        "TB" | "TIB" => 1024 * 1024 * 1024 * 1024,
+        _ => return Err(format!("Unknown size unit in '{}'", input)),
+    };
+    
+    let bytes = value * multiplier as f64;
+    if !bytes.is_finite() || bytes > u64::MAX as f64 {
+        return Err(format!("Size out of range: '{}'", input));
+    }
+    
+    Ok(bytes.round() as u64)
 }
 
 #[cfg(test)]
@@ -117,10 +520,67 @@ mod tests {
 
     #[tokio::test]
     async fn test_format_bytes() {
-        assert_eq!(format_bytes(100).await.unwrap(), "100 B");
-        assert_eq!(format_bytes(1024).await.unwrap(), "1.00 KB");
-        assert_eq!(format_bytes(1024 * 1024).await.unwrap(), "1.00 MB");
-        assert_eq!(format_bytes(1024 * 1024 * 1024).await.unwrap(), "1.00 GB");
+        assert_eq!(format_bytes(100, None, None, None).await.unwrap(), "100 B");
+        assert_eq!(format_bytes(1024, None, None, None).await.unwrap(), "1.00 KiB");
+        assert_eq!(format_bytes(1024 * 1024, None, None, None).await.unwrap(), "1.00 MiB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024, None, None, None).await.unwrap(), "1.00 GiB");
+        assert_eq!(format_bytes(1024_u64.pow(4), None, None, None).await.unwrap(), "1.00 TiB");
+    }
+
+    #[tokio::test]
+    async fn test_format_bytes_unit_systems() {
+        assert_eq!(
+            format_bytes(1_000_000, Some(UnitSystem::Binary), None, None).await.unwrap(),
+            "976.56 KiB"
+        );
+        assert_eq!(
+            format_bytes(1_000_000, Some(UnitSystem::Decimal), None, None).await.unwrap(),
+            "1.00 MB"
+        );
+        assert_eq!(format_bytes(999, Some(UnitSystem::Decimal), None, None).await.unwrap(), "999 B");
+        assert_eq!(
+            format_bytes(1_500_000_000_000, Some(UnitSystem::Decimal), None, None).await.unwrap(),
+            "1.50 TB"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_bytes_precision_and_trim() {
+        let gib = 1024 * 1024 * 1024;
+        assert_eq!(format_bytes(2 * gib, None, None, Some(true)).await.unwrap(), "2 GiB");
+        assert_eq!(format_bytes(2 * gib, None, Some(0), None).await.unwrap(), "2 GiB");
+        assert_eq!(format_bytes(gib + gib / 2, None, None, Some(true)).await.unwrap(), "1.5 GiB");
+        assert_eq!(format_bytes(gib + gib / 2, None, Some(3), None).await.unwrap(), "1.500 GiB");
+        assert_eq!(format_bytes(1_234_567, Some(UnitSystem::Decimal), Some(1), None).await.unwrap(), "1.2 MB");
+        assert_eq!(format_bytes(100, None, Some(3), Some(true)).await.unwrap(), "100 B");
+    }
+
+    #[tokio::test]
+    async fn test_parse_bytes() {
+        assert_eq!(parse_bytes("1024".to_string()).await.unwrap(), 1024);
+        assert_eq!(parse_bytes("1 KB".to_string()).await.unwrap(), 1024);
+        assert_eq!(parse_bytes("2.5GB".to_string()).await.unwrap(), 2_684_354_560);
+        assert_eq!(parse_bytes(" 500mb ".to_string()).await.unwrap(), 500 * 1024 * 1024);
+        assert_eq!(parse_bytes("2.5 gb".to_string()).await.unwrap(), 2_684_354_560);
+        assert_eq!(parse_bytes("1TB".to_string()).await.unwrap(), 1024_u64.pow(4));
+        assert_eq!(parse_bytes("12 b".to_string()).await.unwrap(), 12);
+        assert_eq!(parse_bytes("3 MiB".to_string()).await.unwrap(), 3 * 1024 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_parse_bytes_rejects_malformed() {
+        assert!(parse_bytes("abc".to_string()).await.is_err());
+        assert!(parse_bytes("".to_string()).await.is_err());
+        assert!(parse_bytes("10 XB".to_string()).await.is_err());
+        assert!(parse_bytes("1.2.3 MB".to_string()).await.is_err());
+        assert!(parse_bytes("-5 MB".to_string()).await.is_err());
+    }
+
+    #[test]
+    fn test_percent_of() {
+        assert_eq!(percent_of(25, 100), 25.0);
+        assert_eq!(percent_of(0, 100), 0.0);
+        assert_eq!(percent_of(10, 0), 0.0);
     }
 
     #[tokio::test]
@@ -128,7 +588,117 @@ mod tests {
         let info = get_system_info().await.unwrap();
         assert!(!info.hostname.is_empty());
         assert!(!info.username.is_empty());
-        assert_eq!(info.os_version, "macOS");
+        // Real values on macOS, "Unknown" fallbacks elsewhere
+        assert!(!info.os_version.is_empty());
+        assert!(!info.cpu_model.is_empty());
+        assert!(!info.model_identifier.is_empty());
+    }
+
+    #[test]
+    fn test_parse_vm_stat() {
+        let output = "Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                               12000.
+Pages active:                            100000.
+Pages inactive:                           90000.
+Pages wired down:                         50000.
+Pages occupied by compressor:             10000.
+Pageins:                                2000000.";
+        let stat = parse_vm_stat(output);
+        assert_eq!(stat.page_size, 16384);
+        assert_eq!(stat.pages["Pages free"], 12000);
+        assert_eq!(stat.bytes("Pages inactive"), 90000 * 16384);
+        assert_eq!(stat.bytes("Missing label"), 0);
+        assert_eq!(used_memory_from_vm_stat(&stat), 160000 * 16384);
+    }
+
+    #[test]
+    fn test_memory_pressure_from_vm_stat() {
+        let stat = parse_vm_stat(
+            "Mach Virtual Memory Statistics: (page size of 4096 bytes)
+Pages free:                                 100.
+Pages active:                               200.
+Pages inactive:                             300.
+Pages wired down:                           400.",
+        );
+        let pressure = memory_pressure_from(&stat, MemoryPressureLevel::Normal);
+        assert_eq!(pressure.page_size, 4096);
+        assert_eq!(pressure.free_pages, 100);
+        assert_eq!(pressure.active_pages, 200);
+        assert_eq!(pressure.inactive_pages, 300);
+        assert_eq!(pressure.wired_pages, 400);
+        assert_eq!(pressure.free_bytes, 100 * 4096);
+    }
+
+    #[test]
+    fn test_pressure_level_from_sysctl() {
+        assert_eq!(pressure_level_from_sysctl("1"), MemoryPressureLevel::Normal);
+        assert_eq!(pressure_level_from_sysctl("2\n"), MemoryPressureLevel::Warning);
+        assert_eq!(pressure_level_from_sysctl("4"), MemoryPressureLevel::Critical);
+        assert_eq!(pressure_level_from_sysctl("x"), MemoryPressureLevel::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_get_memory_pressure() {
+        // vm_stat is macOS-only; elsewhere this returns an error rather than panicking
+        let _ = get_memory_pressure().await;
+    }
+
+    fn snapshot_at(timestamp: u64) -> DiskUsageSnapshot {
+        DiskUsageSnapshot {
+            timestamp,
+            disk_usage: DiskUsage {
+                total_bytes: 100,
+                free_bytes: 40,
+                used_bytes: 60,
+                used_percentage: 60.0,
+                finder_available_bytes: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_disk_usage_history_append_and_prune() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(DISK_HISTORY_FILE);
+        let day = SECONDS_PER_DAY;
+
+        append_snapshot(&path, snapshot_at(day), 30).unwrap();
+        append_snapshot(&path, snapshot_at(20 * day), 30).unwrap();
+        assert_eq!(load_history_since(&path, 0).len(), 2);
+
+        // Day 40 with 30-day retention drops the day-1 entry
+        append_snapshot(&path, snapshot_at(40 * day), 30).unwrap();
+        let history = load_history_since(&path, 0);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].timestamp, 20 * day);
+
+        assert_eq!(load_history_since(&path, 30 * day).len(), 1);
+
+        // A retention too long to represent keeps everything
+        append_snapshot(&path, snapshot_at(50 * day), u64::MAX).unwrap();
+        assert_eq!(load_history_since(&path, 0).len(), 3);
+    }
+
+    #[test]
+    fn test_parse_capacity_output() {
+        assert_eq!(parse_capacity_output("123456789012\n"), Some(123_456_789_012));
+        assert_eq!(parse_capacity_output("1.5e+12"), Some(1_500_000_000_000));
+        assert_eq!(parse_capacity_output("undefined"), None);
+        assert_eq!(parse_capacity_output("-1"), None);
+    }
+
+    #[test]
+    fn test_disk_usage_without_finder_field_deserializes() {
+        let usage: DiskUsage = serde_json::from_str(
+            r#"{"total_bytes":100,"free_bytes":40,"used_bytes":60,"used_percentage":60.0}"#,
+        )
+        .unwrap();
+        assert_eq!(usage.finder_available_bytes, None);
+    }
+
+    #[test]
+    fn test_command_output_missing_program() {
+        assert!(command_output("definitely-not-a-real-command", &[]).is_none());
     }
 
     #[tokio::test]
@@ -0,0 +1,78 @@
+use crate::scanners::trash_scanner::{TrashCapability, TrashLocation};
+use crate::scanners::{size_cache, trash_scanner};
+use std::path::Path;
+use tauri::command;
+
+/// Get the total on-disk size of the user's trash across all volumes
+#[command]
+pub async fn get_trash_size() -> Result<u64, String> {
+    let size = trash_scanner::get_trash_size_in(&trash_scanner::trash_directories());
+    size_cache::save_global_cache();
+    Ok(size)
+}
+
+/// Size the user's trash on each volume separately: the home trash ("/") plus
+/// `.Trashes/<uid>` on every mounted external volume. Emptying the home trash
+/// frees nothing on an external disk, so each is reported on its own.
+#[command]
+pub async fn scan_all_trashes() -> Result<Vec<TrashLocation>, String> {
+    let locations = tokio::task::spawn_blocking(trash_scanner::scan_all_trashes)
+        .await
+        .map_err(|e| e.to_string())?;
+    size_cache::save_global_cache();
+    Ok(locations)
+}
+
+/// Permanently empty the trash on the volume mounted at `mount` ("/" for the
+/// home trash). Requires `confirm` to be true. Returns the number of bytes freed.
+#[command]
+pub async fn empty_trash_for_volume(mount: String, confirm: bool) -> Result<u64, String> {
+    if !confirm {
+        return Err("Emptying the trash permanently deletes its contents and must be confirmed".to_string());
+    }
+    trash_scanner::empty_trash_for_volume(&mount)
+}
+
+/// Permanently empty the trash on all volumes. Requires `confirm` to be true.
+/// Returns the number of bytes freed.
+#[command]
+pub async fn empty_trash(confirm: bool) -> Result<u64, String> {
+    if !confirm {
+        return Err("Emptying the trash permanently deletes its contents and must be confirmed".to_string());
+    }
+    Ok(trash_scanner::empty_trash_dirs(&trash_scanner::trash_directories()))
+}
+
+/// Check whether an item can be moved to the trash normally, only with an
+/// administrator password, or not at all because its volume is read-only
+#[command]
+pub async fn can_trash(path: String) -> Result<TrashCapability, String> {
+    trash_scanner::can_trash(Path::new(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_trash_size() {
+        assert!(get_trash_size().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_can_trash_missing_path() {
+        assert!(can_trash("/nonexistent/path".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_trash_requires_confirmation() {
+        assert!(empty_trash(false).await.is_err());
+        assert!(empty_trash_for_volume("/".to_string(), false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_trash_for_unknown_volume() {
+        let result = empty_trash_for_volume("/Volumes/NotMounted-quick-cleaner".to_string(), true).await;
+        assert!(result.unwrap_err().contains("No trash found"));
+    }
+}
@@ -0,0 +1,89 @@
+// Results the user has chosen to ignore, hidden from later scans
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// File under the app support dir holding dismissed result keys
+const DISMISSALS_FILE: &str = "dismissed_results.json";
+
+/// Read the dismissed keys stored at `path`
+fn load_from(path: &Path) -> BTreeSet<String> {
+    crate::storage::read_json(path).unwrap_or_default()
+}
+
+/// Add `key` to the dismissals stored at `path`
+fn dismiss_in(path: &Path, key: &str) -> Result<(), String> {
+    let mut keys = load_from(path);
+    if keys.insert(key.to_string()) {
+        crate::storage::write_json_atomic(path, &keys)?;
+    }
+    Ok(())
+}
+
+/// Dismissed keys: file paths, and group hashes for duplicates
+pub fn dismissed() -> BTreeSet<String> {
+    crate::storage::app_support_file(DISMISSALS_FILE)
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+/// Hide a result from future scans. `key` is a file path, or a group hash for duplicates.
+pub fn dismiss(key: &str) -> Result<(), String> {
+    dismiss_in(&crate::storage::app_support_file(DISMISSALS_FILE)?, key)
+}
+
+/// Forget every dismissal
+pub fn clear() -> Result<(), String> {
+    let path = crate::storage::app_support_file(DISMISSALS_FILE)?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Drop items whose key has been dismissed, unless `include_dismissed` is set
+pub fn without_dismissed<T, F>(items: Vec<T>, include_dismissed: bool, key: F) -> Vec<T>
+where
+    F: Fn(&T) -> &str,
+{
+    if include_dismissed {
+        return items;
+    }
+    let dismissed = dismissed();
+    if dismissed.is_empty() {
+        return items;
+    }
+    items.into_iter().filter(|item| !dismissed.contains(key(item))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dismiss_in_persists_keys_once() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(DISMISSALS_FILE);
+
+        assert!(load_from(&path).is_empty());
+        dismiss_in(&path, "/Users/me/Movies/keep.mov").unwrap();
+        dismiss_in(&path, "abc123").unwrap();
+        dismiss_in(&path, "abc123").unwrap();
+
+        let keys = load_from(&path);
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains("abc123"));
+    }
+
+    #[test]
+    #[serial_test::serial(dismissals)]
+    fn test_without_dismissed() {
+        let key = "/tmp/dismissals-test/keep.mov";
+        dismiss(key).unwrap();
+
+        let items = vec![key.to_string(), "/tmp/dismissals-test/other.mov".to_string()];
+        let kept = without_dismissed(items.clone(), false, |s| s.as_str());
+        assert_eq!(kept, vec!["/tmp/dismissals-test/other.mov".to_string()]);
+        assert_eq!(without_dismissed(items, true, |s| s.as_str()).len(), 2);
+    }
+}
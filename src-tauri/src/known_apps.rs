@@ -0,0 +1,82 @@
+// User-maintained list of apps and tools that must never be flagged as orphans
+
+use std::path::Path;
+
+/// File under the app support dir holding the known-apps list
+const KNOWN_APPS_FILE: &str = "known_apps.json";
+
+/// Load the list from `path`, empty if missing
+fn load_from(path: &Path) -> Vec<String> {
+    crate::storage::read_json(path).unwrap_or_default()
+}
+
+/// Add `name` to the list at `path` (case-insensitive, no duplicates)
+fn add_to(path: &Path, name: &str) -> Result<Vec<String>, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("App name cannot be empty".to_string());
+    }
+
+    let mut apps = load_from(path);
+    if !apps.iter().any(|a| a.eq_ignore_ascii_case(name)) {
+        apps.push(name.to_string());
+        apps.sort_by_key(|a| a.to_lowercase());
+        crate::storage::write_json_atomic(path, &apps)?;
+    }
+    Ok(apps)
+}
+
+/// Remove `name` from the list at `path` (case-insensitive)
+fn remove_from(path: &Path, name: &str) -> Result<Vec<String>, String> {
+    let mut apps = load_from(path);
+    let before = apps.len();
+    apps.retain(|a| !a.eq_ignore_ascii_case(name.trim()));
+    if apps.len() != before {
+        crate::storage::write_json_atomic(path, &apps)?;
+    }
+    Ok(apps)
+}
+
+/// The user's known apps, or an empty list if none are saved
+pub fn load() -> Vec<String> {
+    crate::storage::app_support_file(KNOWN_APPS_FILE)
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+/// Add an app to the user's known apps, returning the updated list
+pub fn add(name: &str) -> Result<Vec<String>, String> {
+    add_to(&crate::storage::app_support_file(KNOWN_APPS_FILE)?, name)
+}
+
+/// Remove an app from the user's known apps, returning the updated list
+pub fn remove(name: &str) -> Result<Vec<String>, String> {
+    remove_from(&crate::storage::app_support_file(KNOWN_APPS_FILE)?, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_remove_known_apps() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(KNOWN_APPS_FILE);
+
+        assert!(load_from(&path).is_empty());
+        add_to(&path, "ollama").unwrap();
+        add_to(&path, "Deno").unwrap();
+        let apps = add_to(&path, "OLLAMA").unwrap();
+        assert_eq!(apps, vec!["Deno", "ollama"]);
+
+        let apps = remove_from(&path, "deno").unwrap();
+        assert_eq!(apps, vec!["ollama"]);
+        assert_eq!(load_from(&path), vec!["ollama"]);
+    }
+
+    #[test]
+    fn test_add_rejects_empty_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(add_to(&temp_dir.path().join(KNOWN_APPS_FILE), "  ").is_err());
+    }
+}
@@ -1,10 +1,21 @@
 // macOS Quick Cleaner - Rust Backend
 // A powerful disk cleanup and optimization utility for macOS
 
+mod audit;
+mod batch;
 mod commands;
+mod dismissals;
+mod known_apps;
+mod pagination;
+mod path_guard;
+mod privileges;
+mod process;
+mod quarantine;
+mod removal;
 mod scanners;
+mod storage;
 
-use commands::{cache, developer, duplicates, large_files, leftovers, system_info};
+use commands::{admin, browser, cache, cleanup, common, config, developer, dismissed, downloads, duplicates, export, history, languages, large_files, leftovers, maintenance, preview, quarantined, scans, startup, system_info, trash};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -18,35 +29,148 @@ pub fn run() {
             cache::scan_user_caches,
             cache::scan_system_caches,
             cache::scan_all_caches,
+            cache::scan_caches_grouped,
+            cache::scan_shared_junk,
+            cache::inspect_cache,
             cache::delete_cache,
+            cache::delete_caches,
             cache::get_total_cache_size,
+            cache::clear_scan_cache,
+            cache::snapshot_caches,
+            cache::diff_caches_against_snapshot,
+            // Browser commands
+            browser::scan_browser_caches,
+            browser::delete_browser_cache,
+            browser::delete_browser_caches,
+            // Common commands
+            common::reveal_in_finder,
+            common::open_path,
+            common::open_in_terminal,
+            common::get_sizes,
+            common::get_path_size,
+            common::analyze_directory,
+            common::get_storage_breakdown,
+            // Preview commands
+            preview::generate_thumbnail,
+            // Scan control commands
+            scans::pause_scan,
+            scans::resume_scan,
+            scans::cancel_scan,
+            // Dismissal commands
+            dismissed::dismiss_result,
+            dismissed::clear_dismissals,
+            // Config commands
+            config::get_settings,
+            config::update_settings,
+            config::add_extension_category,
+            config::remove_extension_category,
+            config::add_scan_root,
+            config::remove_scan_root,
+            config::set_cache_safety_override,
+            config::clear_cache_safety_override,
+            // History commands
+            history::get_recent_operations,
             // Developer commands
             developer::scan_developer_caches,
+            developer::scan_developer_caches_with_progress,
             developer::clean_developer_cache,
+            developer::scan_derived_data,
+            developer::delete_derived_data,
+            developer::docker_prune,
+            developer::scan_homebrew,
+            developer::clean_homebrew,
             developer::get_total_developer_cache_size,
             developer::is_developer_user,
             // Leftover commands
             leftovers::scan_installed_apps,
+            leftovers::scan_app_footprints,
             leftovers::scan_orphan_files,
             leftovers::scan_large_app_data,
+            leftovers::is_app_running,
             leftovers::delete_orphan,
-            leftovers::reveal_in_finder,
+            leftovers::delete_orphans,
+            leftovers::scan_orphan_launch_items,
+            leftovers::remove_orphan_launch_item,
             leftovers::get_orphan_total_size,
+            leftovers::get_orphan_totals_by_type,
+            leftovers::list_known_apps,
+            leftovers::add_known_app,
+            leftovers::remove_known_app,
             // Large files commands
             large_files::scan_large_files,
+            large_files::scan_large_files_with_report,
+            large_files::scan_large_files_streaming,
             large_files::scan_common_large_files,
+            large_files::scan_recent_large_files,
+            large_files::top_n_largest_files,
+            large_files::scan_large_files_spotlight,
             large_files::delete_file,
+            large_files::secure_delete,
+            large_files::delete_files,
             large_files::move_file_to_trash,
+            large_files::move_files_to_trash,
+            large_files::scan_tiny_files,
+            // Download commands
+            downloads::scan_quarantined_downloads,
+            downloads::scan_stale_downloads,
+            downloads::trash_stale_downloads,
             // Duplicate commands
             duplicates::scan_duplicates,
             duplicates::scan_common_duplicates,
             duplicates::delete_duplicate,
+            duplicates::delete_duplicates,
             duplicates::move_duplicate_to_trash,
+            duplicates::delete_duplicate_verified,
+            duplicates::resolve_duplicate_group,
+            duplicates::suggest_keep,
+            duplicates::hash_file,
+            duplicates::dedupe_via_hardlink,
             duplicates::get_duplicates_wasted_space,
             // System info commands
             system_info::get_system_info,
             system_info::get_disk_usage_info,
+            system_info::get_finder_free_space,
+            system_info::record_disk_usage_snapshot,
+            system_info::get_disk_usage_history,
+            system_info::get_memory_pressure,
+            system_info::purge_inactive_memory,
             system_info::format_bytes,
+            system_info::parse_bytes,
+            // Language file commands
+            languages::scan_language_files,
+            languages::remove_language_files,
+            // Startup commands
+            startup::scan_login_items,
+            startup::set_login_item_enabled,
+            // Maintenance commands
+            maintenance::clean_font_caches,
+            maintenance::flush_dns_cache,
+            maintenance::rebuild_spotlight_index,
+            maintenance::rebuild_launch_services,
+            maintenance::scan_mail_indexes,
+            maintenance::rebuild_mail_index,
+            maintenance::clean_quicklook_cache,
+            // Admin session commands
+            admin::cleanup_requires_admin,
+            admin::authorize_admin_session,
+            admin::end_admin_session,
+            // Trash commands
+            trash::get_trash_size,
+            trash::empty_trash,
+            trash::scan_all_trashes,
+            trash::empty_trash_for_volume,
+            trash::can_trash,
+            // Quarantine commands
+            quarantined::quarantine_path,
+            quarantined::list_quarantine,
+            quarantined::restore_quarantine,
+            quarantined::purge_quarantine,
+            // Cleanup commands
+            cleanup::scan_safe_cleanup,
+            cleanup::get_cleanup_overview,
+            cleanup::execute_cleanup_plan,
+            // Export commands
+            export::export_report,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
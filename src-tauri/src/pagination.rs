@@ -0,0 +1,102 @@
+// Sorting scan results and slicing them into pages for the frontend
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Order in which scan results are returned
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SortOrder {
+    #[default]
+    SizeDesc,
+    SizeAsc,
+    NameAsc,
+    DateNewest,
+    DateOldest,
+}
+
+/// A scan result that can be ordered by `SortOrder`
+pub trait Sortable {
+    fn sort_size(&self) -> u64;
+    fn sort_name(&self) -> &str;
+    /// Last modified time as a Unix timestamp, if the result has one
+    fn sort_date(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Sort results in place. Results without a date sort after dated ones.
+pub fn sort_results<T: Sortable>(items: &mut [T], order: SortOrder) {
+    match order {
+        SortOrder::SizeDesc => items.sort_by_key(|i| Reverse(i.sort_size())),
+        SortOrder::SizeAsc => items.sort_by_key(|i| i.sort_size()),
+        SortOrder::NameAsc => items.sort_by_key(|i| i.sort_name().to_lowercase()),
+        SortOrder::DateNewest => items.sort_by_key(|i| (i.sort_date().is_none(), Reverse(i.sort_date()))),
+        SortOrder::DateOldest => items.sort_by_key(|i| (i.sort_date().is_none(), i.sort_date())),
+    }
+}
+
+/// One page of a sorted result list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of results before paging, so the UI can show page counts
+    pub total_count: usize,
+}
+
+/// Skip `offset` items and keep at most `limit`. `None` means from the start
+/// and no limit respectively.
+pub fn paginate<T>(items: Vec<T>, offset: Option<usize>, limit: Option<usize>) -> Page<T> {
+    let total_count = items.len();
+    let items = items
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    Page { items, total_count }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item(&'static str, u64, Option<u64>);
+
+    impl Sortable for Item {
+        fn sort_size(&self) -> u64 {
+            self.1
+        }
+        fn sort_name(&self) -> &str {
+            self.0
+        }
+        fn sort_date(&self) -> Option<u64> {
+            self.2
+        }
+    }
+
+    fn sorted(order: SortOrder) -> Vec<&'static str> {
+        let mut items = vec![Item("b", 10, Some(200)), Item("A", 30, None), Item("c", 20, Some(100))];
+        sort_results(&mut items, order);
+        items.into_iter().map(|i| i.0).collect()
+    }
+
+    #[test]
+    fn test_sort_results() {
+        assert_eq!(sorted(SortOrder::SizeDesc), vec!["A", "c", "b"]);
+        assert_eq!(sorted(SortOrder::SizeAsc), vec!["b", "c", "A"]);
+        assert_eq!(sorted(SortOrder::NameAsc), vec!["A", "b", "c"]);
+        assert_eq!(sorted(SortOrder::DateNewest), vec!["b", "c", "A"]);
+        assert_eq!(sorted(SortOrder::DateOldest), vec!["c", "b", "A"]);
+    }
+
+    #[test]
+    fn test_paginate() {
+        let items: Vec<u32> = (0..10).collect();
+
+        let page = paginate(items.clone(), Some(4), Some(3));
+        assert_eq!(page.items, vec![4, 5, 6]);
+        assert_eq!(page.total_count, 10);
+
+        assert_eq!(paginate(items.clone(), None, None).items.len(), 10);
+        assert!(paginate(items, Some(20), Some(5)).items.is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+// Checks that destructive operations stay inside the folders they are meant for
+
+use std::path::{Path, PathBuf};
+
+/// Canonicalize `path` and confirm it lies strictly inside one of `roots`.
+/// Resolving symlinks and `..` first means a crafted path can't escape the roots.
+/// Returns the canonical path on success.
+pub fn ensure_within_roots(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    resolve_against_roots(path, roots, false)
+}
+
+/// Like `ensure_within_roots`, but also accepts a path equal to one of the roots
+pub fn ensure_at_or_within_roots(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    resolve_against_roots(path, roots, true)
+}
+
+fn resolve_against_roots(path: &Path, roots: &[PathBuf], allow_root: bool) -> Result<PathBuf, String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("Cannot resolve {}: {}", path.display(), e))?;
+
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| canonical.starts_with(&root) && (allow_root || canonical != root));
+
+    if allowed {
+        Ok(canonical)
+    } else {
+        Err(format!("Refusing to delete {}: it is outside the allowed folders", path.display()))
+    }
+}
+
+/// Refuse to delete `path` if it is, lies inside, or contains one of the
+/// paths the user protected in settings
+pub fn ensure_not_protected(path: &Path) -> Result<(), String> {
+    let settings = crate::commands::config::load_settings();
+    if settings.protected_paths.is_empty() {
+        return Ok(());
+    }
+    ensure_not_protected_by(path, &crate::commands::config::protected_paths(&settings))
+}
+
+fn ensure_not_protected_by(path: &Path, protected: &[PathBuf]) -> Result<(), String> {
+    // Paths that don't exist yet are compared as written
+    let resolve = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let target = resolve(path);
+    match protected.iter().map(|p| resolve(p)).find(|p| target.starts_with(p) || p.starts_with(&target)) {
+        Some(p) => Err(format!("Refusing to delete {}: {} is protected in settings", path.display(), p.display())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_path_inside_root_is_allowed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("Caches");
+        let inside = root.join("com.example.App");
+        fs::create_dir_all(&inside).unwrap();
+
+        let canonical = ensure_within_roots(&inside, &[root]).unwrap();
+        assert_eq!(canonical, inside.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_root_itself_and_outside_paths_are_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("Caches");
+        let documents = temp_dir.path().join("Documents");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&documents).unwrap();
+
+        let roots = [root.clone()];
+        assert!(ensure_within_roots(&root, &roots).is_err());
+        assert!(ensure_within_roots(&documents, &roots).is_err());
+        assert!(ensure_at_or_within_roots(&root, &roots).is_ok());
+        assert!(ensure_at_or_within_roots(&documents, &roots).is_err());
+    }
+
+    #[test]
+    fn test_protected_paths_and_their_folders_are_refused() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let documents = temp_dir.path().join("Documents");
+        let taxes = documents.join("Taxes");
+        let notes = documents.join("notes.txt");
+        fs::create_dir_all(&taxes).unwrap();
+        fs::write(&notes, "x").unwrap();
+        let protected = [taxes.clone(), temp_dir.path().join("missing")];
+
+        assert!(ensure_not_protected_by(&taxes, &protected).is_err());
+        assert!(ensure_not_protected_by(&taxes.join("2024.pdf"), &protected).is_err());
+        assert!(ensure_not_protected_by(&documents, &protected).is_err());
+        assert!(ensure_not_protected_by(&documents.join(".").join("Taxes"), &protected).is_err());
+        assert!(ensure_not_protected_by(&notes, &protected).is_ok());
+        assert!(ensure_not_protected_by(&notes, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_dot_dot_traversal_is_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("Caches");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(temp_dir.path().join("Documents")).unwrap();
+
+        let escaped = root.join("..").join("Documents");
+        assert!(ensure_within_roots(&escaped, &[root]).is_err());
+    }
+}
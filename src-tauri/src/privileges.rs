@@ -0,0 +1,435 @@
+// Helpers for running commands with administrator privileges
+
+use serde::Deserialize;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{mpsc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::process::run_command_with_timeout;
+
+/// How long to wait for the password prompt and the privileged command
+const ADMIN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// JXA helper behind an admin session. It reads one JSON string per line on
+/// stdin, runs it as a shell script with administrator privileges and answers
+/// with one JSON line each. The script is quoted on the Rust side by
+/// `session_request`, so the helper never builds shell source itself. macOS
+/// caches the authorization inside this process, so only the first command
+/// prompts, and it is gone once the process exits.
+const SESSION_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+function run() {
+    const app = Application.currentApplication();
+    app.includeStandardAdditions = true;
+    const input = $.NSFileHandle.fileHandleWithStandardInput;
+    const output = $.NSFileHandle.fileHandleWithStandardOutput;
+    const reply = (value) => output.writeData($(JSON.stringify(value) + '\n').dataUsingEncoding($.NSUTF8StringEncoding));
+    let buffer = '';
+    for (;;) {
+        const data = input.availableData;
+        if (data.length === 0) return;
+        buffer += $.NSString.alloc.initWithDataEncoding(data, $.NSUTF8StringEncoding).js;
+        let newline;
+        while ((newline = buffer.indexOf('\n')) >= 0) {
+            const shell = JSON.parse(buffer.slice(0, newline));
+            buffer = buffer.slice(newline + 1);
+            try {
+                app.doShellScript(shell, { administratorPrivileges: true });
+                reply({ ok: true });
+            } catch (e) {
+                reply({ ok: false, error: String(e), cancelled: e.errorNumber === -128 });
+            }
+        }
+    }
+}
+"#;
+
+/// Run a fixed shell command as root via AppleScript's `with administrator privileges`.
+///
+/// This prompts the user for their password. Returns an error if the user
+/// cancels the prompt or the command fails. The command is embedded in the
+/// script, so use `run_commands_with_admin` for anything containing paths.
+pub fn run_shell_with_admin(shell_command: &str) -> Result<(), String> {
+    if let Some(result) = run_in_session(&[&["/bin/sh", "-c", shell_command]]) {
+        return result;
+    }
+    
+    let script = format!(
+        r#"do shell script "{}" with administrator privileges"#,
+        shell_command.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+    
+    let output = run_command_with_timeout("osascript", ["-e", &script], ADMIN_TIMEOUT)?;
+    
+    admin_result(output)
+}
+
+/// Run a sequence of commands as root, one after another, each given as a
+/// program followed by its arguments.
+///
+/// The words are passed to osascript as arguments and quoted by AppleScript's
+/// `quoted form of`, so paths never appear in the script or shell source and
+/// can't inject commands whatever characters they contain. A command only runs
+/// if the one before it succeeded, and the first failure is the result.
+pub fn run_commands_with_admin(commands: &[&[&str]]) -> Result<(), String> {
+    if let Some(result) = run_in_session(commands) {
+        return result;
+    }
+    
+    let (script_lines, args) = admin_script(commands);
+    
+    let mut osascript_args = Vec::new();
+    for line in script_lines {
+        osascript_args.push("-e".to_string());
+        osascript_args.push(line);
+    }
+    osascript_args.push("--".to_string());
+    osascript_args.extend(args);
+    let output = run_command_with_timeout("osascript", &osascript_args, ADMIN_TIMEOUT)?;
+    
+    admin_result(output)
+}
+
+/// Build the AppleScript lines and argv for `run_commands_with_admin`
+fn admin_script(commands: &[&[&str]]) -> (Vec<String>, Vec<String>) {
+    let mut args = Vec::new();
+    let mut shell_parts = Vec::new();
+    
+    for command in commands {
+        let words: Vec<String> = command
+            .iter()
+            .map(|word| {
+                args.push(word.to_string());
+                format!("quoted form of item {} of argv", args.len())
+            })
+            .collect();
+        shell_parts.push(words.join(r#" & " " & "#));
+    }
+    
+    let script_lines = vec![
+        "on run argv".to_string(),
+        format!(
+            "do shell script {} with administrator privileges",
+            shell_parts.join(r#" & " && " & "#)
+        ),
+        "end run".to_string(),
+    ];
+    (script_lines, args)
+}
+
+/// Turn osascript's output into the admin command's result
+fn admin_result(output: std::process::Output) -> Result<(), String> {
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_user_cancelled(&stderr) {
+            Err("Operation cancelled by user".to_string())
+        } else {
+            Err(format!("Admin command failed: {}", stderr.trim()))
+        }
+    }
+}
+
+/// Check whether osascript's stderr indicates the password prompt was cancelled
+pub fn is_user_cancelled(stderr: &str) -> bool {
+    stderr.contains("User canceled") || stderr.contains("-128")
+}
+
+/// A running session helper process
+struct AdminHelper {
+    child: Child,
+    stdin: ChildStdin,
+    replies: mpsc::Receiver<String>,
+}
+
+impl AdminHelper {
+    fn spawn() -> Result<Self, String> {
+        let mut child = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", SESSION_SCRIPT])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start admin session: {}", e))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err("Failed to start admin session".to_string());
+        };
+
+        // Forward replies so waiting for one can time out
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(AdminHelper { child, stdin, replies })
+    }
+
+    /// Send one request and wait for its reply. Returns None if the helper
+    /// exited, so the caller can fall back to prompting on its own.
+    fn run(&mut self, commands: &[&[&str]]) -> Option<Result<(), String>> {
+        writeln!(self.stdin, "{}", session_request(commands))
+            .and_then(|_| self.stdin.flush())
+            .ok()?;
+        match self.replies.recv_timeout(ADMIN_TIMEOUT) {
+            Ok(reply) => Some(parse_session_reply(&reply)),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                let _ = self.child.kill();
+                Some(Err("Admin session stopped responding".to_string()))
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for AdminHelper {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// The helper's answer to one request
+#[derive(Debug, Deserialize)]
+struct SessionReply {
+    ok: bool,
+    #[serde(default)]
+    error: String,
+    #[serde(default)]
+    cancelled: bool,
+}
+
+/// Quote `word` as a single /bin/sh word
+fn shell_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// One request line for the helper: the commands joined with `&&` into one
+/// shell script, every word quoted, sent as a JSON string
+fn session_request(commands: &[&[&str]]) -> String {
+    let script = commands
+        .iter()
+        .map(|words| words.iter().map(|word| shell_quote(word)).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join(" && ");
+    serde_json::Value::String(script).to_string()
+}
+
+/// Turn a helper reply into the admin command's result
+fn parse_session_reply(line: &str) -> Result<(), String> {
+    let reply: SessionReply =
+        serde_json::from_str(line).map_err(|e| format!("Unexpected reply from admin session: {}", e))?;
+    if reply.ok {
+        Ok(())
+    } else if reply.cancelled || is_user_cancelled(&reply.error) {
+        Err("Operation cancelled by user".to_string())
+    } else {
+        Err(format!("Admin command failed: {}", reply.error.trim()))
+    }
+}
+
+/// Admin session shared by every privileged command. The lock is only held
+/// briefly; the helper is taken out while it runs a command.
+struct SessionState {
+    helper: Option<AdminHelper>,
+    /// Set by `authorize_admin_session` until `end_admin_session`
+    authorized: bool,
+    /// Batches currently running inside `with_admin_session`
+    batches: usize,
+    /// The password prompt was cancelled; fail the rest of the batch without asking again
+    declined: bool,
+}
+
+impl SessionState {
+    fn in_use(&self) -> bool {
+        self.authorized || self.batches > 0
+    }
+
+    /// Stop the helper once nothing needs the session any more
+    fn release_if_unused(&mut self) {
+        if !self.in_use() {
+            self.helper = None;
+            self.declined = false;
+        }
+    }
+}
+
+static SESSION: Mutex<SessionState> = Mutex::new(SessionState {
+    helper: None,
+    authorized: false,
+    batches: 0,
+    declined: false,
+});
+
+/// Held while a command runs in the helper, so commands take turns without
+/// keeping `SESSION` locked through the password prompt
+static HELPER_TURN: Mutex<()> = Mutex::new(());
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn session() -> MutexGuard<'static, SessionState> {
+    lock(&SESSION)
+}
+
+/// Run `commands` in the admin session if one is active or a batch wants one.
+/// Returns None when commands should prompt on their own: no session is
+/// active, or the helper couldn't be started or exited before answering.
+fn run_in_session(commands: &[&[&str]]) -> Option<Result<(), String>> {
+    let _turn = lock(&HELPER_TURN);
+    let mut helper = {
+        let mut state = session();
+        if !state.in_use() {
+            return None;
+        }
+        if state.declined {
+            return Some(Err("Operation cancelled by user".to_string()));
+        }
+        state.helper.take()
+    };
+    if !helper.as_mut().is_some_and(AdminHelper::is_running) {
+        helper = Some(AdminHelper::spawn().ok()?);
+    }
+    let mut helper = helper?;
+
+    let result = helper.run(commands)?;
+    let mut state = session();
+    if matches!(&result, Err(e) if e == "Operation cancelled by user") {
+        state.declined = true;
+    } else if state.in_use() && helper.is_running() {
+        state.helper = Some(helper);
+    }
+    Some(result)
+}
+
+/// Ask for the administrator password once up front. Privileged commands reuse
+/// the authorization until `end_admin_session`, or until macOS expires it.
+pub fn authorize_admin_session() -> Result<(), String> {
+    session().declined = false;
+    with_admin_session(|| {
+        let result = run_in_session(&[&["/usr/bin/true"]])
+            .unwrap_or_else(|| Err("Failed to start admin session".to_string()));
+        session().authorized = result.is_ok();
+        result
+    })
+}
+
+/// Drop the admin session's authorization
+pub fn end_admin_session() {
+    let mut state = session();
+    state.authorized = false;
+    state.release_if_unused();
+}
+
+/// Run a batch so that any privileged commands in it share one password prompt.
+/// The prompt only appears if something in the batch needs it, and the
+/// authorization is released afterwards unless a session was authorized explicitly.
+pub fn with_admin_session<T>(batch: impl FnOnce() -> T) -> T {
+    {
+        let mut state = session();
+        if state.batches == 0 {
+            state.declined = false;
+        }
+        state.batches += 1;
+    }
+    let result = batch();
+    let mut state = session();
+    state.batches -= 1;
+    state.release_if_unused();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_user_cancelled() {
+        assert!(is_user_cancelled("execution error: User canceled. (-128)"));
+        assert!(is_user_cancelled("error -128"));
+        assert!(!is_user_cancelled("execution error: Operation not permitted"));
+    }
+
+    #[test]
+    fn test_admin_script_keeps_paths_out_of_the_script() {
+        let paths = [
+            "/Library/Application Support/Old App",
+            "/tmp/it's \"quoted\"",
+            "/tmp/$(rm -rf ~)",
+            "/tmp/new\nline`id`",
+        ];
+        for path in paths {
+            let (lines, args) = admin_script(&[&["mkdir", "-p", "/tmp/trash"], &["mv", path, "/tmp/trash/x"]]);
+
+            assert_eq!(args, vec!["mkdir", "-p", "/tmp/trash", "mv", path, "/tmp/trash/x"]);
+            assert!(lines.iter().all(|line| !line.contains(path)));
+            assert!(lines[1].contains("quoted form of item 5 of argv"));
+            assert!(lines[1].contains(r#" & " && " & "#));
+        }
+    }
+
+    #[test]
+    fn test_session_request_and_reply() {
+        let request = session_request(&[&["mkdir", "-p", "/tmp/trash"], &["mv", "/tmp/it's \"x\"\nnew", "/tmp/trash"]]);
+        assert!(!request.contains('\n'));
+        let decoded: String = serde_json::from_str(&request).unwrap();
+        assert_eq!(
+            decoded,
+            "'mkdir' '-p' '/tmp/trash' && 'mv' '/tmp/it'\\''s \"x\"\nnew' '/tmp/trash'"
+        );
+
+        assert!(parse_session_reply(r#"{"ok":true}"#).is_ok());
+        assert_eq!(
+            parse_session_reply(r#"{"ok":false,"error":"Error: User canceled.","cancelled":true}"#).unwrap_err(),
+            "Operation cancelled by user"
+        );
+        assert!(parse_session_reply(r#"{"ok":false,"error":"mv: Permission denied"}"#)
+            .unwrap_err()
+            .contains("Permission denied"));
+        assert!(parse_session_reply("garbage").is_err());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/tmp/plain"), "'/tmp/plain'");
+        assert_eq!(shell_quote("/tmp/it's"), r"'/tmp/it'\''s'");
+        assert_eq!(shell_quote("'; rm -rf ~; '"), r"''\''; rm -rf ~; '\'''");
+    }
+
+    #[test]
+    fn test_session_script_has_no_raw_newlines_in_strings() {
+        assert!(SESSION_SCRIPT.contains(r"indexOf('\n')"));
+        assert!(!SESSION_SCRIPT.contains("'\n'"));
+    }
+
+    #[test]
+    fn test_admin_session_without_osascript() {
+        // osascript only exists on macOS; elsewhere the session must fail cleanly
+        if cfg!(not(target_os = "macos")) {
+            assert!(authorize_admin_session().is_err());
+            let result = with_admin_session(|| run_commands_with_admin(&[&["true"]]));
+            assert!(result.is_err());
+            end_admin_session();
+            assert!(session().helper.is_none());
+        }
+    }
+
+    #[test]
+    fn test_run_shell_with_admin_without_osascript() {
+        // osascript only exists on macOS; elsewhere this must fail cleanly
+        if cfg!(not(target_os = "macos")) {
+            assert!(run_shell_with_admin("true").is_err());
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Running external tools with a deadline and bounded output
+
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, Read};
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Maximum bytes kept from each of stdout and stderr; the rest is discarded
+const MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+
+/// How often to check whether the child has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Why a command could not produce output
+#[derive(Debug, PartialEq)]
+pub enum CommandError {
+    /// The program could not be started
+    Spawn(String),
+    /// The program was killed after running past its deadline
+    TimedOut { program: String, timeout: Duration },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Spawn(e) => write!(f, "{}", e),
+            CommandError::TimedOut { program, timeout } => {
+                write!(f, "{} timed out after {}s", program, timeout.as_secs_f32())
+            }
+        }
+    }
+}
+
+impl From<CommandError> for String {
+    fn from(e: CommandError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Read up to `MAX_OUTPUT_BYTES` on a background thread, draining anything
+/// beyond that so the child never blocks on a full pipe
+fn read_capped<R: Read + Send + 'static>(reader: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let mut limited = reader.take(MAX_OUTPUT_BYTES as u64);
+        let _ = limited.read_to_end(&mut buf);
+        let _ = io::copy(&mut limited.into_inner(), &mut io::sink());
+        buf
+    })
+}
+
+/// Run `program` with `args`, killing it if it hasn't exited within `timeout`.
+/// Captured stdout and stderr are each capped at `MAX_OUTPUT_BYTES`.
+pub fn run_command_with_timeout<I, S>(program: &str, args: I, timeout: Duration) -> Result<Output, CommandError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| CommandError::Spawn(format!("Failed to run {}: {}", program, e)))?;
+
+    let stdout = child.stdout.take().map(read_capped);
+    let stderr = child.stderr.take().map(read_capped);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                log::warn!("{} timed out after {:?}", program, timeout);
+                return Err(CommandError::TimedOut {
+                    program: program.to_string(),
+                    timeout,
+                });
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(CommandError::Spawn(format!("Failed to wait for {}: {}", program, e))),
+        }
+    };
+
+    let collect = |handle: Option<thread::JoinHandle<Vec<u8>>>| handle.and_then(|h| h.join().ok()).unwrap_or_default();
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_captures_output() {
+        let output = run_command_with_timeout("echo", ["hello"], Duration::from_secs(5)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kills_command_past_deadline() {
+        let started = Instant::now();
+        let result = run_command_with_timeout("sleep", ["5"], Duration::from_millis(100));
+        assert!(matches!(result, Err(CommandError::TimedOut { .. })));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_caps_output_size() {
+        let output = run_command_with_timeout(
+            "head",
+            ["-c", &(MAX_OUTPUT_BYTES + 1024).to_string(), "/dev/zero"],
+            Duration::from_secs(30),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), MAX_OUTPUT_BYTES);
+    }
+
+    #[test]
+    fn test_missing_program() {
+        let result = run_command_with_timeout("definitely-not-a-real-tool", Vec::<String>::new(), Duration::from_secs(1));
+        assert!(matches!(result, Err(CommandError::Spawn(_))));
+    }
+}
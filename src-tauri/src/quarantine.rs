@@ -0,0 +1,327 @@
+// Holding area for cleaned items, kept until purged so a bad cleanup can be reversed
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use crate::scanners::disk_size::get_path_size;
+
+/// Folder under the app support dir holding quarantined items
+const QUARANTINE_FOLDER: &str = "quarantine";
+
+/// Manifest of quarantined items, inside the quarantine folder
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Serializes manifest updates so concurrent commands don't lose entries
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// An item moved into quarantine
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuarantinedItem {
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    pub size: u64,
+    pub quarantined_at: u64, // Unix timestamp
+}
+
+fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Get (and create if needed) the quarantine folder
+fn quarantine_dir() -> Result<PathBuf, String> {
+    let dir = crate::storage::app_support_file(QUARANTINE_FOLDER)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+fn load_manifest(dir: &Path) -> Vec<QuarantinedItem> {
+    crate::storage::read_json(&dir.join(MANIFEST_FILE)).unwrap_or_default()
+}
+
+fn save_manifest(dir: &Path, items: &[QuarantinedItem]) -> Result<(), String> {
+    crate::storage::write_json_atomic(&dir.join(MANIFEST_FILE), &items)
+}
+
+/// Copy `from` to `to` recursively, recreating symlinks rather than following them
+fn copy_recursive(from: &Path, to: &Path) -> Result<(), String> {
+    for entry in WalkDir::new(from) {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let relative = entry.path().strip_prefix(from).map_err(|e| e.to_string())?;
+        let target = if relative.as_os_str().is_empty() {
+            to.to_path_buf()
+        } else {
+            to.join(relative)
+        };
+        let file_type = entry.file_type();
+        let result = if file_type.is_dir() {
+            fs::create_dir_all(&target)
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            {
+                fs::read_link(entry.path()).and_then(|link| std::os::unix::fs::symlink(link, &target))
+            }
+            #[cfg(not(unix))]
+            {
+                Ok(())
+            }
+        } else {
+            fs::copy(entry.path(), &target).map(|_| ())
+        };
+        result.map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+    }
+    Ok(())
+}
+
+/// Move `from` to `to`, copying and then deleting when they are on different volumes
+fn move_path(from: &Path, to: &Path) -> Result<(), String> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            if let Err(copy_error) = copy_recursive(from, to) {
+                // Leave the original untouched and drop the partial copy
+                let _ = remove_path(to);
+                return Err(copy_error);
+            }
+            remove_path(from)
+        }
+        Err(e) => Err(format!("Failed to move {}: {}", from.display(), e)),
+    }
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = match fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    };
+    result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+/// Where an item's contents are kept inside the quarantine folder
+fn stored_path(dir: &Path, item: &QuarantinedItem) -> PathBuf {
+    dir.join(&item.id).join(&item.name)
+}
+
+/// Move `path` into the quarantine folder `dir`
+fn quarantine_in(dir: &Path, path: &Path) -> Result<QuarantinedItem, String> {
+    if !path.is_absolute() {
+        return Err(format!("Path must be absolute: {}", path.display()));
+    }
+    if fs::symlink_metadata(path).is_err() {
+        return Err(format!("{} no longer exists", path.display()));
+    }
+    if dir.starts_with(path) || path.starts_with(dir) {
+        return Err(format!("Refusing to quarantine {}", path.display()));
+    }
+    if dirs::home_dir().is_some_and(|home| home.starts_with(path)) {
+        return Err(format!("Refusing to quarantine {}", path.display()));
+    }
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("Refusing to quarantine {}", path.display()))?
+        .to_string_lossy()
+        .to_string();
+
+    let _guard = MANIFEST_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut items = load_manifest(dir);
+    let quarantined_at = now_timestamp();
+    let mut id = quarantined_at.to_string();
+    let mut suffix = 1;
+    while dir.join(&id).exists() {
+        id = format!("{}-{}", quarantined_at, suffix);
+        suffix += 1;
+    }
+
+    let item = QuarantinedItem {
+        id,
+        name,
+        original_path: path.to_string_lossy().to_string(),
+        size: get_path_size(path),
+        quarantined_at,
+    };
+    let holder = dir.join(&item.id);
+    fs::create_dir_all(&holder).map_err(|e| format!("Failed to create {}: {}", holder.display(), e))?;
+    let moved = crate::path_guard::ensure_not_protected(path).and_then(|_| move_path(path, &stored_path(dir, &item)));
+    crate::audit::record("quarantine", &item.original_path, item.size, &moved);
+    if let Err(e) = moved {
+        let _ = fs::remove_dir_all(&holder);
+        return Err(e);
+    }
+
+    items.push(item.clone());
+    save_manifest(dir, &items)?;
+    Ok(item)
+}
+
+/// Items in the quarantine folder `dir`, newest first
+fn list_in(dir: &Path) -> Vec<QuarantinedItem> {
+    let mut items = load_manifest(dir);
+    items.sort_by(|a, b| b.quarantined_at.cmp(&a.quarantined_at));
+    items
+}
+
+/// Move item `id` back to where it came from. Fails if something now exists there.
+fn restore_in(dir: &Path, id: &str) -> Result<QuarantinedItem, String> {
+    let _guard = MANIFEST_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut items = load_manifest(dir);
+    let index = items
+        .iter()
+        .position(|item| item.id == id)
+        .ok_or_else(|| format!("No quarantined item with id {}", id))?;
+    let item = items[index].clone();
+    let original = PathBuf::from(&item.original_path);
+    if fs::symlink_metadata(&original).is_ok() {
+        return Err(format!("{} already exists", original.display()));
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    move_path(&stored_path(dir, &item), &original)?;
+    let _ = fs::remove_dir(dir.join(&item.id));
+    items.remove(index);
+    save_manifest(dir, &items)?;
+    Ok(item)
+}
+
+/// Permanently delete items quarantined more than `older_than_days` days before
+/// `now`, returning the bytes freed
+fn purge_in(dir: &Path, older_than_days: u64, now: u64) -> Result<u64, String> {
+    let _guard = MANIFEST_LOCK.lock().map_err(|e| e.to_string())?;
+    let cutoff = now.saturating_sub(older_than_days.saturating_mul(24 * 60 * 60));
+    let (expired, mut kept): (Vec<_>, Vec<_>) = load_manifest(dir)
+        .into_iter()
+        .partition(|item| item.quarantined_at <= cutoff);
+
+    let mut freed = 0;
+    for item in expired {
+        let result = remove_path(&dir.join(&item.id));
+        crate::audit::record("purge_quarantine", &item.original_path, item.size, &result);
+        match result {
+            Ok(()) => freed += item.size,
+            Err(e) => {
+                log::warn!("{}", e);
+                kept.push(item);
+            }
+        }
+    }
+    save_manifest(dir, &kept)?;
+    Ok(freed)
+}
+
+/// Move `path` into quarantine instead of deleting it
+pub fn quarantine(path: &str) -> Result<QuarantinedItem, String> {
+    quarantine_in(&quarantine_dir()?, Path::new(path))
+}
+
+/// Everything currently in quarantine, newest first
+pub fn list() -> Result<Vec<QuarantinedItem>, String> {
+    Ok(list_in(&quarantine_dir()?))
+}
+
+/// Put a quarantined item back where it came from
+pub fn restore(id: &str) -> Result<QuarantinedItem, String> {
+    restore_in(&quarantine_dir()?, id)
+}
+
+/// Permanently delete items quarantined more than `older_than_days` days ago
+pub fn purge(older_than_days: u64) -> Result<u64, String> {
+    purge_in(&quarantine_dir()?, older_than_days, now_timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let quarantine = temp_dir.path().join("quarantine");
+        let cache = temp_dir.path().join("Caches").join("com.example.app");
+        fs::create_dir_all(&quarantine).unwrap();
+        fs::create_dir_all(cache.join("data")).unwrap();
+        fs::write(cache.join("data").join("blob"), vec![1u8; 4096]).unwrap();
+        (temp_dir, quarantine, cache)
+    }
+
+    #[test]
+    fn test_quarantine_and_restore() {
+        let (_temp_dir, quarantine, cache) = setup();
+
+        let item = quarantine_in(&quarantine, &cache).unwrap();
+        assert!(!cache.exists());
+        assert!(stored_path(&quarantine, &item).join("data").join("blob").exists());
+        assert_eq!(list_in(&quarantine), vec![item.clone()]);
+
+        let restored = restore_in(&quarantine, &item.id).unwrap();
+        assert_eq!(restored.id, item.id);
+        assert!(cache.join("data").join("blob").exists());
+        assert!(list_in(&quarantine).is_empty());
+        assert!(!quarantine.join(&item.id).exists());
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite() {
+        let (_temp_dir, quarantine, cache) = setup();
+        let item = quarantine_in(&quarantine, &cache).unwrap();
+        fs::create_dir_all(&cache).unwrap();
+
+        assert!(restore_in(&quarantine, &item.id).unwrap_err().contains("already exists"));
+        assert_eq!(list_in(&quarantine).len(), 1);
+        assert!(restore_in(&quarantine, "missing").is_err());
+    }
+
+    #[test]
+    fn test_quarantine_rejects_invalid_paths() {
+        let (temp_dir, quarantine, _cache) = setup();
+        assert!(quarantine_in(&quarantine, Path::new("relative/path")).is_err());
+        assert!(quarantine_in(&quarantine, &temp_dir.path().join("missing")).is_err());
+        assert!(quarantine_in(&quarantine, temp_dir.path()).is_err());
+        assert!(quarantine_in(&quarantine, Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn test_purge_removes_only_old_items() {
+        let (temp_dir, quarantine, cache) = setup();
+        let old = quarantine_in(&quarantine, &cache).unwrap();
+        let other = temp_dir.path().join("other.log");
+        fs::write(&other, "log").unwrap();
+        let recent = quarantine_in(&quarantine, &other).unwrap();
+
+        // Pretend the first item was quarantined ten days ago
+        let mut items = load_manifest(&quarantine);
+        items.iter_mut().find(|i| i.id == old.id).unwrap().quarantined_at -= 10 * 24 * 60 * 60;
+        save_manifest(&quarantine, &items).unwrap();
+
+        let freed = purge_in(&quarantine, 7, now_timestamp()).unwrap();
+        assert_eq!(freed, old.size);
+        assert!(!quarantine.join(&old.id).exists());
+        assert_eq!(list_in(&quarantine), vec![recent]);
+    }
+
+    #[test]
+    fn test_purge_with_huge_age_keeps_everything() {
+        let (_temp_dir, quarantine, cache) = setup();
+        let item = quarantine_in(&quarantine, &cache).unwrap();
+
+        assert_eq!(purge_in(&quarantine, u64::MAX, now_timestamp()).unwrap(), 0);
+        assert_eq!(list_in(&quarantine), vec![item]);
+    }
+
+    #[test]
+    fn test_copy_recursive() {
+        let (temp_dir, _quarantine, cache) = setup();
+        let copy = temp_dir.path().join("copy");
+        copy_recursive(&cache, &copy).unwrap();
+        assert_eq!(fs::read(copy.join("data").join("blob")).unwrap().len(), 4096);
+        assert!(cache.exists());
+    }
+}
@@ -0,0 +1,123 @@
+// Getting rid of paths either through the Trash or permanently, per the safe-mode setting
+
+use std::fs;
+use std::path::Path;
+
+use crate::commands::config::Settings;
+
+/// How a cleanup command disposes of a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Removal {
+    /// Move to the Trash so the user can still recover it
+    Trash,
+    /// Unlink immediately, with no way back
+    Permanent,
+}
+
+impl Removal {
+    /// `Trash` while safe mode is on (the default), `Permanent` once the user turns it off
+    pub fn for_settings(settings: &Settings) -> Self {
+        if settings.safe_mode {
+            Removal::Trash
+        } else {
+            Removal::Permanent
+        }
+    }
+
+    /// The removal the saved settings ask for
+    pub fn from_settings() -> Self {
+        Self::for_settings(&crate::commands::config::load_settings())
+    }
+
+    /// Remove the file, folder or symlink at `path`. Symlinks are removed
+    /// themselves, never followed.
+    pub fn remove(self, path: &Path) -> Result<(), String> {
+        match self {
+            Removal::Trash => trash::delete(path)
+                .map_err(|e| format!("Failed to move {} to the Trash: {}", path.display(), e)),
+            Removal::Permanent => {
+                let result = match fs::symlink_metadata(path) {
+                    Ok(m) if m.is_dir() => fs::remove_dir_all(path),
+                    _ => fs::remove_file(path),
+                };
+                result.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+            }
+        }
+    }
+}
+
+/// Whether an item called `name` that lived in `parent` is in the Trash, taking
+/// it back out if so. Lets tests that trash real files clean up after themselves.
+#[cfg(all(test, unix, not(target_os = "macos")))]
+pub(crate) fn take_from_trash(parent: &Path, name: &str) -> bool {
+    let items: Vec<_> = trash::os_limited::list()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|item| item.name == name && item.original_parent == parent)
+        .collect();
+    let found = !items.is_empty();
+    let _ = trash::os_limited::purge_all(items);
+    found
+}
+
+#[cfg(all(test, target_os = "macos"))]
+pub(crate) fn take_from_trash(_parent: &Path, name: &str) -> bool {
+    let trashed = dirs::home_dir().map(|home| home.join(".Trash").join(name));
+    trashed.is_some_and(|path| fs::remove_file(path).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permanent_removal() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        let folder = temp_dir.path().join("folder");
+        fs::write(&file, "x").unwrap();
+        fs::create_dir_all(folder.join("nested")).unwrap();
+        fs::write(folder.join("nested").join("inner.txt"), "x").unwrap();
+
+        Removal::Permanent.remove(&file).unwrap();
+        Removal::Permanent.remove(&folder).unwrap();
+        assert!(!file.exists());
+        assert!(!folder.exists());
+        assert!(Removal::Permanent.remove(&file).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_safe_mode_sends_items_to_the_trash() {
+        let mut settings = Settings::default();
+        assert_eq!(Removal::for_settings(&settings), Removal::Trash);
+        settings.safe_mode = false;
+        assert_eq!(Removal::for_settings(&settings), Removal::Permanent);
+        settings.safe_mode = true;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let parent = temp_dir.path().canonicalize().unwrap();
+        let name = format!("safe-mode-{}.txt", std::process::id());
+        let file = parent.join(&name);
+        fs::write(&file, "x").unwrap();
+
+        Removal::for_settings(&settings).remove(&file).unwrap();
+        assert!(!file.exists());
+        assert!(take_from_trash(&parent, &name));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permanent_removal_keeps_symlink_target() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("target");
+        let link = temp_dir.path().join("link");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("keep.txt"), "x").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        Removal::Permanent.remove(&link).unwrap();
+        assert!(!link.exists());
+        assert!(target.join("keep.txt").exists());
+    }
+}
@@ -1,46 +1,28 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use walkdir::WalkDir;
+
+use super::disk_size::get_directory_sizes;
 
 /// Represents a large application data folder
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LargeAppData {
     pub path: String,
     pub name: String,
-    pub size: u64,
+    pub size: u64, // On disk
+    #[serde(default)]
+    pub apparent_size: u64, // Sum of file lengths
     pub location: String, // "ApplicationSupport" or "Containers"
 }
 
+/// Number of folders `scan_large_app_data` returns unless told otherwise
+pub const DEFAULT_LARGE_APP_DATA_LIMIT: usize = 50;
+
 /// Get the user's home directory
 fn get_home_dir() -> Option<PathBuf> {
     dirs::home_dir()
 }
 
-/// Calculate directory size using actual disk usage (blocks)
-fn get_directory_size(path: &PathBuf) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| {
-            // Use blocks * block_size for actual disk usage on Unix
-            // This correctly handles sparse files like Docker.raw
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::MetadataExt;
-                // blocks are in 512-byte units
-                m.blocks() * 512
-            }
-            #[cfg(not(unix))]
-            {
-                m.len()
-            }
-        })
-        .sum()
-}
-
 /// Scan a directory and return its immediate subdirectories with sizes
 fn scan_directory_for_large_folders(base_path: PathBuf, location: &str) -> Vec<LargeAppData> {
     let mut folders = Vec::new();
@@ -65,8 +47,9 @@ fn scan_directory_for_large_folders(base_path: PathBuf, location: &str) -> Vec<L
                 continue;
             }
             
-            // Calculate size
-            let size = get_directory_size(&path);
+            // Calculate on-disk and apparent size in one walk
+            let sizes = get_directory_sizes(&path);
+            let size = sizes.on_disk;
             
             // Only include folders > 1MB
             if size > 1_000_000 {
@@ -74,6 +57,7 @@ fn scan_directory_for_large_folders(base_path: PathBuf, location: &str) -> Vec<L
                     path: path.to_string_lossy().to_string(),
                     name,
                     size,
+                    apparent_size: sizes.apparent,
                     location: location.to_string(),
                 });
             }
@@ -83,8 +67,8 @@ fn scan_directory_for_large_folders(base_path: PathBuf, location: &str) -> Vec<L
     folders
 }
 
-/// Scan for large application data folders
-pub fn scan_large_app_data() -> Vec<LargeAppData> {
+/// Scan for large application data folders, returning the `limit` largest
+pub fn scan_large_app_data(limit: usize) -> Vec<LargeAppData> {
     let mut all_folders = Vec::new();
     
     if let Some(home) = get_home_dir() {
@@ -106,7 +90,6 @@ pub fn scan_large_app_data() -> Vec<LargeAppData> {
     // Sort by size (largest first)
     all_folders.sort_by(|a, b| b.size.cmp(&a.size));
     
-    // Return top 50
-    all_folders.truncate(50);
+    all_folders.truncate(limit);
     all_folders
 }
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use walkdir::WalkDir;
 
+use super::disk_size::get_path_size;
+use super::file_scanner::CategoryTotal;
+use super::running_apps::{self, RunningApp};
+use super::trash_scanner;
+use crate::pagination::Sortable;
+
 /// Represents an installed application
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledApp {
@@ -13,7 +19,7 @@ pub struct InstalledApp {
 }
 
 /// Types of orphan files
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum OrphanType {
     ApplicationSupport,
     Preferences,
@@ -33,6 +39,15 @@ pub struct OrphanFile {
     pub possible_app_name: String,
 }
 
+impl Sortable for OrphanFile {
+    fn sort_size(&self) -> u64 {
+        self.size
+    }
+    fn sort_name(&self) -> &str {
+        &self.name
+    }
+}
+
 /// Get the user's home directory
 fn get_home_dir() -> Option<PathBuf> {
     dirs::home_dir()
@@ -105,30 +120,36 @@ pub fn scan_apps_in_directories(app_dirs: Vec<PathBuf>) -> Vec<InstalledApp> {
     apps
 }
 
-/// Get a set of known bundle ID prefixes from installed apps
-fn get_known_bundle_prefixes(apps: &[InstalledApp]) -> HashSet<String> {
+/// Add the names a single app's data folders may go by to `prefixes`
+fn add_app_prefixes(app: &InstalledApp, prefixes: &mut HashSet<String>) {
+    if !app.bundle_id.is_empty() {
+        // Add full bundle ID
+        prefixes.insert(app.bundle_id.clone());
+        prefixes.insert(app.bundle_id.to_lowercase());
+        
+        // Add each component of the bundle ID
+        // e.g., com.adobe.lightroomCC -> ["com", "adobe", "lightroomcc"]
+        for part in app.bundle_id.split('.') {
+            if !part.is_empty() && part.len() > 2 {
+                prefixes.insert(part.to_lowercase());
+            }
+        }
+    }
+    
+    // Add normalized app name
+    let normalized_name = app.name.to_lowercase().replace(" ", "").replace("-", "").replace("_", "");
+    prefixes.insert(normalized_name);
+    prefixes.insert(app.name.to_lowercase());
+}
+
+/// Get a set of known bundle ID prefixes from installed apps and the user's
+/// own list of apps/tools to keep
+fn get_known_bundle_prefixes(apps: &[InstalledApp], user_known_apps: &[String]) -> HashSet<String> {
     let mut prefixes = HashSet::new();
     
     // Add all installed .app bundles
     for app in apps {
-        if !app.bundle_id.is_empty() {
-            // Add full bundle ID
-            prefixes.insert(app.bundle_id.clone());
-            prefixes.insert(app.bundle_id.to_lowercase());
-            
-            // Add each component of the bundle ID
-            // e.g., com.adobe.lightroomCC -> ["com", "adobe", "lightroomcc"]
-            for part in app.bundle_id.split('.') {
-                if !part.is_empty() && part.len() > 2 {
-                    prefixes.insert(part.to_lowercase());
-                }
-            }
-        }
-        
-        // Add normalized app name
-        let normalized_name = app.name.to_lowercase().replace(" ", "").replace("-", "").replace("_", "");
-        prefixes.insert(normalized_name);
-        prefixes.insert(app.name.to_lowercase());
+        add_app_prefixes(app, &mut prefixes);
     }
     
     // Add common CLI tools and system packages that don't have .app bundles
@@ -154,6 +175,12 @@ fn get_known_bundle_prefixes(apps: &[InstalledApp]) -> HashSet<String> {
         prefixes.insert(tool.to_string());
     }
     
+    for name in user_known_apps {
+        let lower = name.to_lowercase();
+        prefixes.insert(lower.replace(" ", "").replace("-", "").replace("_", ""));
+        prefixes.insert(lower);
+    }
+    
     prefixes
 }
 
@@ -278,8 +305,12 @@ fn scan_library_subdir(library_path: &std::path::Path, subdir: &str, orphan_type
 }
 
 /// Scan for all orphan files (internal)
-pub fn scan_orphans_with_custom_paths(apps: Vec<InstalledApp>, library_path: &std::path::Path) -> Vec<OrphanFile> {
-    let known_prefixes = get_known_bundle_prefixes(&apps);
+pub fn scan_orphans_with_custom_paths(
+    apps: Vec<InstalledApp>,
+    user_known_apps: &[String],
+    library_path: &std::path::Path,
+) -> Vec<OrphanFile> {
+    let known_prefixes = get_known_bundle_prefixes(&apps, user_known_apps);
     let mut all_orphans = Vec::new();
 
     all_orphans.extend(scan_library_subdir(library_path, "Application Support", OrphanType::ApplicationSupport, &known_prefixes));
@@ -297,93 +328,142 @@ pub fn scan_orphan_files() -> Vec<OrphanFile> {
     let apps = scan_installed_apps();
     if let Some(home) = get_home_dir() {
         let library_path = home.join("Library");
-        return scan_orphans_with_custom_paths(apps, &library_path);
+        return scan_orphans_with_custom_paths(apps, &crate::known_apps::load(), &library_path);
     }
     Vec::new()
 }
 
-/// Delete an orphan file or directory by moving it to trash
-pub fn delete_orphan(path: &str) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    
-    if !path.exists() {
-        return Ok(());
+/// Library folders holding per-app data counted in an app's footprint
+const APP_DATA_SUBDIRS: &[&str] = &["Application Support", "Caches", "Containers", "Logs", "Preferences"];
+
+/// An installed app's total disk usage, including its data in ~/Library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppFootprint {
+    pub app: InstalledApp,
+    pub bundle_size: u64,
+    pub data_size: u64,
+    pub total: u64,
+    pub is_running: bool, // Quit the app before cleaning its data
+}
+
+/// Size each app's bundle plus the Library data folders matching it, largest
+/// total first. Folders are matched the same way the orphan scan recognizes
+/// installed apps, so a folder matching several apps counts toward each.
+/// Apps in `running` are flagged as running.
+pub fn scan_app_footprints_in(
+    apps: Vec<InstalledApp>,
+    library_path: &std::path::Path,
+    running: &[RunningApp],
+) -> Vec<AppFootprint> {
+    let data_folders: Vec<(String, PathBuf)> = APP_DATA_SUBDIRS
+        .iter()
+        .filter_map(|subdir| fs::read_dir(library_path.join(subdir)).ok())
+        .flat_map(|read_dir| read_dir.filter_map(|e| e.ok()))
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+        .filter(|(name, _)| !name.starts_with('.'))
+        .collect();
+    let mut folder_sizes: HashMap<PathBuf, u64> = HashMap::new();
+
+    let mut footprints: Vec<AppFootprint> = apps
+        .into_iter()
+        .map(|app| {
+            let mut prefixes = HashSet::new();
+            add_app_prefixes(&app, &mut prefixes);
+            let data_size = data_folders
+                .iter()
+                .filter(|(name, _)| is_known_app(name, &prefixes))
+                .map(|(_, path)| *folder_sizes.entry(path.clone()).or_insert_with(|| get_path_size(path)))
+                .sum();
+            let bundle_size = get_path_size(std::path::Path::new(&app.path));
+            let is_running = running_apps::is_running_in(&app.bundle_id, running);
+            AppFootprint {
+                app,
+                bundle_size,
+                data_size,
+                total: bundle_size + data_size,
+                is_running,
+            }
+        })
+        .collect();
+
+    footprints.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.app.name.cmp(&b.app.name)));
+    footprints
+}
+
+/// Footprint of every installed app, bundle plus data, largest first
+pub fn scan_app_footprints() -> Vec<AppFootprint> {
+    let apps = scan_installed_apps();
+    match get_home_dir() {
+        Some(home) => scan_app_footprints_in(apps, &home.join("Library"), &running_apps::running_apps()),
+        None => Vec::new(),
     }
-    
-    // Check if we have permission to access the file
-    let needs_admin = if let Ok(metadata) = path.metadata() {
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let permissions = metadata.permissions();
-            let mode = permissions.mode();
-            
-            // Check if we have write permission (owner write bit)
-            mode & 0o200 == 0
-        }
-        #[cfg(not(unix))]
-        {
-            false
-        }
-    } else {
-        false
-    };
-    
-    // Try to move to trash normally first
-    match trash::delete(&path) {
-        Ok(_) => Ok(()),
-        Err(_) if needs_admin => {
-            // If normal deletion fails and we detected permission issues,
-            // try with admin privileges
-            delete_with_admin_privileges(&path)
-        }
-        Err(_) => {
-            // Try admin deletion as fallback for any error
-            delete_with_admin_privileges(&path)
-        }
+}
+
+/// Sum orphan counts and sizes per orphan type
+pub fn orphan_totals_by_type(orphans: &[OrphanFile]) -> HashMap<OrphanType, CategoryTotal> {
+    let mut totals: HashMap<OrphanType, CategoryTotal> = HashMap::new();
+    for orphan in orphans {
+        let total = totals.entry(orphan.orphan_type.clone()).or_default();
+        total.count += 1;
+        total.total_bytes += orphan.size;
     }
+    totals
 }
 
-/// Delete a file with administrator privileges using AppleScript
-fn delete_with_admin_privileges(path: &std::path::Path) -> Result<(), String> {
-    use std::process::Command;
-    
-    let path_str = path.to_string_lossy();
+/// Delete an orphan file or directory by moving it to trash.
+/// If that fails, the move is retried with administrator privileges.
+pub fn delete_orphan(path: &str) -> Result<(), String> {
+    crate::audit::logged("delete_orphan", path, || {
+        let path = PathBuf::from(path);
     
-    // Use AppleScript to request admin privileges and delete the file
-    // This will prompt the user for their password
-    let script = format!(
-        r#"do shell script "rm -rf '{}'" with administrator privileges"#,
-        path_str.replace("'", "'\\''") // Escape single quotes
-    );
+        if !path.exists() {
+            return Ok(());
+        }
     
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(&script)
-        .output()
-        .map_err(|e| format!("Failed to execute admin deletion: {}", e))?;
+        // Check if we have permission to access the file
+        let needs_admin = trash_scanner::lacks_write_permission(&path);
     
-    if output.status.success() {
-        Ok(())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        
-        // Check if user cancelled the password prompt
-        if stderr.contains("User canceled") || stderr.contains("-128") {
-            Err("Deletion cancelled by user".to_string())
-        } else {
-            Err(format!(
-                "Failed to delete with admin privileges: {}",
-                stderr.trim()
-            ))
+        // Try to move to trash normally first
+        match trash::delete(&path) {
+            Ok(_) => Ok(()),
+            Err(_) if needs_admin => {
+                // If normal deletion fails and we detected permission issues,
+                // try with admin privileges
+                trash_scanner::trash_with_admin_privileges(&path)
+            }
+            Err(_) => {
+                // Try admin deletion as fallback for any error
+                trash_scanner::trash_with_admin_privileges(&path)
+            }
         }
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_orphan_totals_by_type() {
+        let orphan = |orphan_type: OrphanType, size: u64| OrphanFile {
+            path: String::new(),
+            name: String::new(),
+            size,
+            orphan_type,
+            possible_app_name: String::new(),
+        };
+        let orphans = vec![
+            orphan(OrphanType::Caches, 10),
+            orphan(OrphanType::Caches, 5),
+            orphan(OrphanType::Containers, 7),
+        ];
+
+        let totals = orphan_totals_by_type(&orphans);
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[&OrphanType::Caches], CategoryTotal { count: 2, total_bytes: 15 });
+        assert_eq!(totals[&OrphanType::Containers], CategoryTotal { count: 1, total_bytes: 7 });
+    }
+
     #[test]
     fn test_extract_app_name() {
         assert_eq!(extract_app_name("com.apple.Music"), "Music");
@@ -458,11 +538,58 @@ mod tests {
             }
         ];
 
-        let orphans = scan_orphans_with_custom_paths(apps, &lib_dir);
+        let orphans = scan_orphans_with_custom_paths(apps.clone(), &[], &lib_dir);
         
         assert!(orphans.len() >= 1);
         let names: Vec<String> = orphans.iter().map(|o| o.name.clone()).collect();
         assert!(names.contains(&"OrphanApp".to_string()));
+        
+        // Apps on the user's known list are never flagged
+        let orphans = scan_orphans_with_custom_paths(apps, &["Orphan App".to_string()], &lib_dir);
+        assert!(orphans.iter().all(|o| o.name != "OrphanApp"));
+    }
+
+    #[test]
+    fn test_scan_app_footprints_in() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let lib_dir = temp_dir.path().join("Library");
+        let app_path = temp_dir.path().join("Editor.app");
+        fs::create_dir_all(app_path.join("Contents")).unwrap();
+        fs::write(app_path.join("Contents").join("binary"), vec![1u8; 8192]).unwrap();
+        for folder in [
+            lib_dir.join("Caches").join("com.example.editor"),
+            lib_dir.join("Application Support").join("Editor"),
+            lib_dir.join("Application Support").join("Unrelated"),
+        ] {
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(folder.join("data"), vec![1u8; 4096]).unwrap();
+        }
+
+        let apps = vec![
+            InstalledApp {
+                name: "Editor".to_string(),
+                bundle_id: "com.example.editor".to_string(),
+                path: app_path.to_string_lossy().to_string(),
+            },
+            InstalledApp {
+                name: "Missing".to_string(),
+                bundle_id: "org.other.missing".to_string(),
+                path: temp_dir.path().join("Missing.app").to_string_lossy().to_string(),
+            },
+        ];
+        let running = [RunningApp {
+            name: "Editor".to_string(),
+            bundle_id: "com.example.editor".to_string(),
+        }];
+        let footprints = scan_app_footprints_in(apps, &lib_dir, &running);
+
+        assert_eq!(footprints[0].app.name, "Editor");
+        assert!(footprints[0].bundle_size >= 8192);
+        assert!(footprints[0].data_size >= 2 * 4096 && footprints[0].data_size < 3 * 4096);
+        assert_eq!(footprints[0].total, footprints[0].bundle_size + footprints[0].data_size);
+        assert!(footprints[0].is_running);
+        assert_eq!(footprints[1].total, 0);
+        assert!(!footprints[1].is_running);
     }
 
     #[test]
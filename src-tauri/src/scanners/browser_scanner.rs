@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::disk_size::get_directory_size;
+use crate::path_guard::ensure_within_roots;
+use crate::removal::Removal;
+
+/// Kinds of per-profile browser data that can be cleared independently
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum BrowserDataType {
+    Cache,
+    CodeCache,
+    GpuCache,
+    ServiceWorker,
+    StartupCache,
+}
+
+impl BrowserDataType {
+    /// Whether clearing this data can sign the user out of sites
+    pub fn logs_out(&self) -> bool {
+        // Service workers hold offline data and push registrations for web apps
+        matches!(self, BrowserDataType::ServiceWorker)
+    }
+}
+
+/// A single clearable cache folder within a browser profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserCache {
+    pub path: String,
+    pub data_type: BrowserDataType,
+    pub size: u64,
+    pub logs_out: bool,
+}
+
+/// A browser profile and its clearable caches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserProfile {
+    pub browser: String,
+    pub profile: String,
+    pub caches: Vec<BrowserCache>,
+    pub total_size: u64,
+}
+
+/// Chromium sub-folders and the data type they hold
+const CHROMIUM_CACHE_DIRS: &[(&str, BrowserDataType)] = &[
+    ("Cache", BrowserDataType::Cache),
+    ("Code Cache", BrowserDataType::CodeCache),
+    ("GPUCache", BrowserDataType::GpuCache),
+    ("Service Worker", BrowserDataType::ServiceWorker),
+];
+
+/// Firefox sub-folders and the data type they hold
+const FIREFOX_CACHE_DIRS: &[(&str, BrowserDataType)] = &[
+    ("cache2", BrowserDataType::Cache),
+    ("startupCache", BrowserDataType::StartupCache),
+];
+
+/// Chromium-based browsers: (name, path under Application Support, path under Caches)
+const CHROMIUM_BROWSERS: &[(&str, &str, &str)] = &[
+    ("Google Chrome", "Google/Chrome", "Google/Chrome"),
+    ("Microsoft Edge", "Microsoft Edge", "Microsoft Edge"),
+    ("Brave", "BraveSoftware/Brave-Browser", "BraveSoftware/Brave-Browser"),
+    ("Arc", "Arc/User Data", "Arc/User Data"),
+];
+
+/// Chromium profile folders are "Default" or "Profile N"
+fn is_chromium_profile(name: &str) -> bool {
+    name == "Default" || name.starts_with("Profile ")
+}
+
+/// Collect the known cache folders for one profile, looking in each root in turn
+fn collect_profile_caches(roots: &[PathBuf], cache_dirs: &[(&str, BrowserDataType)]) -> Vec<BrowserCache> {
+    let mut caches = Vec::new();
+    for root in roots {
+        for (dir_name, data_type) in cache_dirs {
+            let path = root.join(dir_name);
+            if path.is_dir() {
+                caches.push(BrowserCache {
+                    path: path.to_string_lossy().to_string(),
+                    data_type: *data_type,
+                    size: get_directory_size(&path),
+                    logs_out: data_type.logs_out(),
+                });
+            }
+        }
+    }
+    caches.sort_by(|a, b| b.size.cmp(&a.size));
+    caches
+}
+
+fn profile(browser: &str, name: String, caches: Vec<BrowserCache>) -> Option<BrowserProfile> {
+    if caches.is_empty() {
+        return None;
+    }
+    let total_size = caches.iter().map(|c| c.size).sum();
+    Some(BrowserProfile {
+        browser: browser.to_string(),
+        profile: name,
+        caches,
+        total_size,
+    })
+}
+
+/// Enumerate the profiles of a Chromium-based browser
+fn scan_chromium_browser(browser: &str, data_root: &Path, cache_root: &Path) -> Vec<BrowserProfile> {
+    let Ok(entries) = fs::read_dir(data_root) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| is_chromium_profile(name))
+        .filter_map(|name| {
+            let roots = [data_root.join(&name), cache_root.join(&name)];
+            profile(browser, name, collect_profile_caches(&roots, CHROMIUM_CACHE_DIRS))
+        })
+        .collect()
+}
+
+/// Enumerate Firefox profiles from the Profiles folders
+fn scan_firefox(data_root: &Path, cache_root: &Path) -> Vec<BrowserProfile> {
+    let Ok(entries) = fs::read_dir(data_root) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter_map(|name| {
+            let roots = [data_root.join(&name), cache_root.join(&name)];
+            profile("Firefox", name, collect_profile_caches(&roots, FIREFOX_CACHE_DIRS))
+        })
+        .collect()
+}
+
+/// Safari's cache folder under the user's Caches
+const SAFARI_CACHE_DIR: &str = "com.apple.Safari";
+
+/// Folders under `home` holding the profiles `scan_browser_profiles` enumerates;
+/// every cache it reports lies inside one of them
+fn browser_roots_in(home: &Path) -> Vec<PathBuf> {
+    let app_support = home.join("Library").join("Application Support");
+    let caches = home.join("Library").join("Caches");
+
+    let mut roots = Vec::new();
+    for (_, data_dir, cache_dir) in CHROMIUM_BROWSERS {
+        roots.push(app_support.join(data_dir));
+        roots.push(caches.join(cache_dir));
+    }
+    roots.push(app_support.join("Firefox").join("Profiles"));
+    roots.push(caches.join("Firefox").join("Profiles"));
+    roots.push(caches.join(SAFARI_CACHE_DIR));
+    roots
+}
+
+/// Scan all supported browsers for per-profile caches
+pub fn scan_browser_profiles() -> Vec<BrowserProfile> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let app_support = home.join("Library").join("Application Support");
+    let caches = home.join("Library").join("Caches");
+
+    let mut profiles = Vec::new();
+    for (browser, data_dir, cache_dir) in CHROMIUM_BROWSERS {
+        profiles.extend(scan_chromium_browser(browser, &app_support.join(data_dir), &caches.join(cache_dir)));
+    }
+    profiles.extend(scan_firefox(
+        &app_support.join("Firefox").join("Profiles"),
+        &caches.join("Firefox").join("Profiles"),
+    ));
+
+    // Safari has a single implicit profile
+    let safari_caches = collect_profile_caches(&[caches.join(SAFARI_CACHE_DIR)], &[("WebKitCache", BrowserDataType::Cache)]);
+    profiles.extend(profile("Safari", "Default".to_string(), safari_caches));
+
+    profiles.sort_by(|a, b| b.total_size.cmp(&a.total_size));
+    profiles
+}
+
+/// Delete a single browser cache folder, through the Trash or permanently per
+/// `removal`. Only folders named like a known browser cache inside a browser's
+/// profile folders are accepted, so this can't be used to remove arbitrary data.
+pub fn delete_browser_cache(path: &str, removal: Removal) -> Result<(), String> {
+    crate::audit::logged("delete_browser_cache", path, || {
+        let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+        delete_browser_cache_within(path, &browser_roots_in(&home), removal)
+    })
+}
+
+/// Delete a browser cache folder after checking its name and that it lies inside one of `roots`
+fn delete_browser_cache_within(path: &str, roots: &[PathBuf], removal: Removal) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let known = CHROMIUM_CACHE_DIRS
+        .iter()
+        .chain(FIREFOX_CACHE_DIRS)
+        .any(|(dir_name, _)| *dir_name == name)
+        || name == "WebKitCache";
+    if !known {
+        return Err(format!("Not a browser cache folder: {}", path.display()));
+    }
+
+    if path.is_dir() {
+        let path = ensure_within_roots(&path, roots)?;
+        removal.remove(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_chromium_profile() {
+        assert!(is_chromium_profile("Default"));
+        assert!(is_chromium_profile("Profile 1"));
+        assert!(!is_chromium_profile("Crashpad"));
+        assert!(!is_chromium_profile("System Profile"));
+    }
+
+    #[test]
+    fn test_scan_chromium_browser() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data_root = temp_dir.path().join("support");
+        let cache_root = temp_dir.path().join("caches");
+        fs::create_dir_all(data_root.join("Default").join("Service Worker")).unwrap();
+        fs::create_dir_all(data_root.join("Default").join("GPUCache")).unwrap();
+        fs::create_dir_all(data_root.join("Profile 1").join("Code Cache")).unwrap();
+        fs::create_dir_all(data_root.join("Crashpad").join("Cache")).unwrap();
+        fs::create_dir_all(cache_root.join("Default").join("Cache")).unwrap();
+        fs::write(cache_root.join("Default").join("Cache").join("data_0"), vec![0u8; 4096]).unwrap();
+
+        let mut profiles = scan_chromium_browser("Google Chrome", &data_root, &cache_root);
+        profiles.sort_by(|a, b| a.profile.cmp(&b.profile));
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].profile, "Default");
+        assert_eq!(profiles[0].caches.len(), 3);
+        assert_eq!(profiles[0].caches[0].data_type, BrowserDataType::Cache);
+        let service_worker = profiles[0]
+            .caches
+            .iter()
+            .find(|c| c.data_type == BrowserDataType::ServiceWorker)
+            .unwrap();
+        assert!(service_worker.logs_out);
+        assert_eq!(profiles[1].profile, "Profile 1");
+    }
+
+    #[test]
+    fn test_scan_firefox() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data_root = temp_dir.path().join("Profiles");
+        let cache_root = temp_dir.path().join("CacheProfiles");
+        fs::create_dir_all(data_root.join("abc.default").join("startupCache")).unwrap();
+        fs::create_dir_all(cache_root.join("abc.default").join("cache2")).unwrap();
+
+        let profiles = scan_firefox(&data_root, &cache_root);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].caches.len(), 2);
+        assert!(profiles[0].caches.iter().all(|c| !c.logs_out));
+    }
+
+    #[test]
+    fn test_delete_browser_cache_rejects_unknown_folder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let profile = temp_dir.path().join("Default");
+        let roots = [temp_dir.path().to_path_buf()];
+        let cookies = profile.join("Cookies");
+        fs::create_dir_all(&cookies).unwrap();
+        assert!(delete_browser_cache_within(cookies.to_str().unwrap(), &roots, Removal::Permanent).is_err());
+        assert!(cookies.exists());
+
+        let gpu = profile.join("GPUCache");
+        fs::create_dir(&gpu).unwrap();
+        assert!(delete_browser_cache_within(gpu.to_str().unwrap(), &roots, Removal::Permanent).is_ok());
+        assert!(!gpu.exists());
+    }
+
+    #[test]
+    fn test_delete_browser_cache_rejects_folders_outside_browser_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let roots = [temp_dir.path().join("Google").join("Chrome")];
+        let elsewhere = temp_dir.path().join("Documents").join("Cache");
+        fs::create_dir_all(&roots[0]).unwrap();
+        fs::create_dir_all(&elsewhere).unwrap();
+
+        assert!(delete_browser_cache_within(elsewhere.to_str().unwrap(), &roots, Removal::Permanent).is_err());
+        assert!(elsewhere.exists());
+    }
+
+    #[test]
+    fn test_browser_roots_in() {
+        let home = Path::new("/Users/me");
+        let roots = browser_roots_in(home);
+        assert!(roots.contains(&home.join("Library/Application Support/Google/Chrome")));
+        assert!(roots.contains(&home.join("Library/Caches/Firefox/Profiles")));
+        assert!(roots.contains(&home.join("Library/Caches/com.apple.Safari")));
+    }
+
+    #[test]
+    fn test_scan_browser_profiles_sanity() {
+        let _ = scan_browser_profiles();
+    }
+}
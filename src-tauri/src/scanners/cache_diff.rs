@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::cache_scanner::CacheEntry;
+
+/// A saved `scan_all_caches` result to compare later scans against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    pub timestamp: u64, // Unix timestamp
+    pub caches: Vec<CacheEntry>,
+}
+
+/// How a cache changed since the snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CacheChange {
+    New,
+    Grown,
+    Shrunk,
+    Removed,
+}
+
+/// A cache whose size differs from the snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheDelta {
+    pub path: String,
+    pub name: String,
+    pub change: CacheChange,
+    pub previous_size: u64,
+    pub current_size: u64,
+    pub delta: i64, // current_size - previous_size
+}
+
+/// Compare two cache scans by path. Unchanged caches are omitted and the
+/// result is sorted by the size of the change, largest first.
+pub fn diff_caches(previous: &[CacheEntry], current: &[CacheEntry]) -> Vec<CacheDelta> {
+    let previous_by_path: HashMap<&str, &CacheEntry> = previous.iter().map(|c| (c.path.as_str(), c)).collect();
+    let current_by_path: HashMap<&str, &CacheEntry> = current.iter().map(|c| (c.path.as_str(), c)).collect();
+
+    let mut deltas = Vec::new();
+
+    for cache in current {
+        let previous_size = previous_by_path.get(cache.path.as_str()).map(|c| c.size);
+        let change = match previous_size {
+            None => CacheChange::New,
+            Some(size) if cache.size > size => CacheChange::Grown,
+            Some(size) if cache.size < size => CacheChange::Shrunk,
+            Some(_) => continue,
+        };
+        let previous_size = previous_size.unwrap_or(0);
+        deltas.push(CacheDelta {
+            path: cache.path.clone(),
+            name: cache.name.clone(),
+            change,
+            previous_size,
+            current_size: cache.size,
+            delta: cache.size as i64 - previous_size as i64,
+        });
+    }
+
+    for cache in previous {
+        if !current_by_path.contains_key(cache.path.as_str()) {
+            deltas.push(CacheDelta {
+                path: cache.path.clone(),
+                name: cache.name.clone(),
+                change: CacheChange::Removed,
+                previous_size: cache.size,
+                current_size: 0,
+                delta: -(cache.size as i64),
+            });
+        }
+    }
+
+    deltas.sort_by(|a, b| b.delta.unsigned_abs().cmp(&a.delta.unsigned_abs()));
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanners::cache_scanner::CacheType;
+
+    fn cache(path: &str, size: u64) -> CacheEntry {
+        CacheEntry {
+            path: path.to_string(),
+            name: path.trim_start_matches('/').to_string(),
+            size,
+            cache_type: CacheType::Application,
+            is_developer_related: false,
+            is_safe_to_delete: true,
+            description: String::new(),
+            possibly_corrupt: false,
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_diff_caches() {
+        let previous = vec![cache("/grown", 100), cache("/shrunk", 500), cache("/same", 10), cache("/removed", 50)];
+        let current = vec![cache("/grown", 1000), cache("/shrunk", 200), cache("/same", 10), cache("/new", 20)];
+
+        let deltas = diff_caches(&previous, &current);
+        let summary: Vec<(&str, CacheChange, i64)> =
+            deltas.iter().map(|d| (d.path.as_str(), d.change.clone(), d.delta)).collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("/grown", CacheChange::Grown, 900),
+                ("/shrunk", CacheChange::Shrunk, -300),
+                ("/removed", CacheChange::Removed, -50),
+                ("/new", CacheChange::New, 20),
+            ]
+        );
+    }
+}
@@ -1,8 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::fs;
 
+use super::directory_analyzer::analyze_directory;
+use super::disk_size::{allocated_size, get_directory_size_bounded, xattr_size, BoundedSize, DEFAULT_MAX_ENTRIES};
+use super::scan_control::ScanControl;
+use super::scan_options::ScanOptions;
+use crate::path_guard::ensure_within_roots;
+use crate::removal::Removal;
+
 /// Types of cache that can be found on macOS
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CacheType {
@@ -23,6 +30,14 @@ pub struct CacheEntry {
     pub is_developer_related: bool,
     pub is_safe_to_delete: bool,
     pub description: String,
+    /// Holds a zero-length database file; clearing the cache often fixes the
+    /// app. Only checked with `ScanOptions::check_cache_databases`.
+    #[serde(default)]
+    pub possibly_corrupt: bool,
+    /// Sizing stopped after `DEFAULT_MAX_ENTRIES` entries or was cancelled,
+    /// so `size` is a lower bound
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 /// Developer-related cache patterns
@@ -70,15 +85,55 @@ fn get_home_dir() -> Option<PathBuf> {
     dirs::home_dir()
 }
 
-/// Calculate the total size of a directory
-pub fn get_directory_size(path: &PathBuf) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| m.len())
-        .sum()
+/// On-disk size of a cache folder, honoring the walk options.
+///
+/// Without a depth limit, exclude patterns or extended attributes this is
+/// `disk_size::get_directory_size_bounded`, reused from the size cache.
+/// Otherwise the folder is walked with `options`, adding extended attribute
+/// sizes with `options.include_xattrs`. Either way sizing stops after
+/// `DEFAULT_MAX_ENTRIES` entries so one huge cache can't stall the scan, and
+/// the partial sum is marked truncated.
+pub fn get_directory_size(path: &Path, options: &ScanOptions) -> BoundedSize {
+    let bounded = if options.max_depth.is_none() && options.exclude.is_none() && !options.include_xattrs {
+        get_directory_size_bounded(path, options.control.as_deref(), Some(DEFAULT_MAX_ENTRIES))
+    } else {
+        let mut bounded = BoundedSize::default();
+        for (visited, e) in options.walk(path).enumerate() {
+            if visited >= DEFAULT_MAX_ENTRIES {
+                bounded.truncated = true;
+                break;
+            }
+            let Ok(metadata) = e.metadata() else {
+                continue;
+            };
+            if metadata.is_file() {
+                let xattrs = if options.include_xattrs { xattr_size(e.path()) } else { 0 };
+                bounded.size += allocated_size(&metadata) + xattrs;
+            }
+        }
+        bounded.truncated |= options.is_cancelled();
+        bounded
+    };
+    if bounded.truncated {
+        log::warn!("Stopped sizing {} after {} entries", path.display(), DEFAULT_MAX_ENTRIES);
+    }
+    bounded
+}
+
+/// Extensions of the database files checked for truncation
+const DATABASE_EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3"];
+
+/// Whether `path` contains a zero-length database file such as a truncated
+/// `Cache.db`. SQLite's `-wal` and `-shm` side files are often empty and are ignored.
+fn has_empty_database(path: &Path, options: &ScanOptions) -> bool {
+    options.walk(path).take(DEFAULT_MAX_ENTRIES).any(|e| {
+        let is_database = e
+            .path()
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| DATABASE_EXTENSIONS.contains(&ext.as_str()));
+        is_database && e.metadata().is_ok_and(|m| m.is_file() && m.len() == 0)
+    })
 }
 
 /// Determine the cache type based on the folder name
@@ -111,6 +166,16 @@ fn is_safe_to_delete(_name: &str, cache_type: &CacheType) -> bool {
     }
 }
 
+/// The user's per-path safety overrides from settings
+fn safety_overrides() -> BTreeMap<String, bool> {
+    crate::commands::config::load_settings().cache_safety_overrides
+}
+
+/// A user override for `path` wins over the built-in determination
+fn resolve_safety(path: &str, built_in: bool, overrides: &BTreeMap<String, bool>) -> bool {
+    overrides.get(path).copied().unwrap_or(built_in)
+}
+
 /// Get a human-readable description for the cache
 fn get_cache_description(name: &str, cache_type: &CacheType) -> String {
     match cache_type {
@@ -122,17 +187,30 @@ fn get_cache_description(name: &str, cache_type: &CacheType) -> String {
     }
 }
 
-/// Scan a specific directory for cache entries
-pub fn scan_directory_for_caches(path: &PathBuf, force_type: Option<CacheType>) -> Vec<CacheEntry> {
+/// Scan a specific directory for cache entries.
+///
+/// `options.max_depth` limits how deep each cache folder is walked when sizing it.
+/// Entries smaller than `min_size_bytes` are left out (0 keeps everything).
+/// Safety overrides from settings take precedence over the built-in heuristic.
+pub fn scan_directory_for_caches(
+    path: &PathBuf,
+    force_type: Option<CacheType>,
+    options: &ScanOptions,
+    min_size_bytes: u64,
+) -> Vec<CacheEntry> {
     let mut entries = Vec::new();
 
     if path.exists() {
+        let overrides = safety_overrides();
         if let Ok(read_dir) = fs::read_dir(path) {
             for entry in read_dir.filter_map(|e| e.ok()) {
                 let entry_path = entry.path();
-                if entry_path.is_dir() {
+                if entry_path.is_dir() && !options.is_excluded(&entry_path) {
                     let name = entry.file_name().to_string_lossy().to_string();
-                    let size = get_directory_size(&entry_path);
+                    let BoundedSize { size, truncated } = get_directory_size(&entry_path, options);
+                    if size < min_size_bytes {
+                        continue;
+                    }
                     
                     let cache_type = if let Some(ref t) = force_type {
                         t.clone()
@@ -141,17 +219,21 @@ pub fn scan_directory_for_caches(path: &PathBuf, force_type: Option<CacheType>)
                     };
                     
                     let is_dev = is_developer_cache(&name);
-                    let safe = is_safe_to_delete(&name, &cache_type);
+                    let path = entry_path.to_string_lossy().to_string();
+                    let safe = resolve_safety(&path, is_safe_to_delete(&name, &cache_type), &overrides);
                     let desc = get_cache_description(&name, &cache_type);
+                    let possibly_corrupt = options.check_cache_databases && has_empty_database(&entry_path, options);
                     
                     entries.push(CacheEntry {
-                        path: entry_path.to_string_lossy().to_string(),
+                        path,
                         name,
                         size,
                         cache_type,
                         is_developer_related: is_dev,
                         is_safe_to_delete: safe,
                         description: desc,
+                        possibly_corrupt,
+                        truncated,
                     });
                 }
             }
@@ -162,33 +244,118 @@ pub fn scan_directory_for_caches(path: &PathBuf, force_type: Option<CacheType>)
 }
 
 /// Scan the ~/Library/Caches directory for cache entries
-pub fn scan_user_caches() -> Vec<CacheEntry> {
+pub fn scan_user_caches(options: &ScanOptions, min_size_bytes: u64) -> Vec<CacheEntry> {
     if let Some(home) = get_home_dir() {
         let cache_path = home.join("Library").join("Caches");
-        return scan_directory_for_caches(&cache_path, None);
+        return scan_directory_for_caches(&cache_path, None, options, min_size_bytes);
     }
     Vec::new()
 }
 
 /// Scan the /Library/Caches directory for system cache entries
-pub fn scan_system_caches() -> Vec<CacheEntry> {
+pub fn scan_system_caches(options: &ScanOptions, min_size_bytes: u64) -> Vec<CacheEntry> {
     let cache_path = PathBuf::from("/Library/Caches");
-    scan_directory_for_caches(&cache_path, Some(CacheType::System))
+    scan_directory_for_caches(&cache_path, Some(CacheType::System), options, min_size_bytes)
 }
 
 /// Get all caches (user + system)
-pub fn scan_all_caches() -> Vec<CacheEntry> {
-    let mut all = scan_user_caches();
-    all.extend(scan_system_caches());
+pub fn scan_all_caches(options: &ScanOptions, min_size_bytes: u64) -> Vec<CacheEntry> {
+    let mut all = scan_user_caches(options, min_size_bytes);
+    all.extend(scan_system_caches(options, min_size_bytes));
     all.sort_by(|a, b| b.size.cmp(&a.size));
     all
 }
 
-/// Delete a cache directory
-pub fn delete_cache(path: &str) -> Result<(), String> {
+/// User and system caches kept apart for display under separate headers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupedCaches {
+    pub user: Vec<CacheEntry>,
+    pub system: Vec<CacheEntry>,
+}
+
+/// Get user and system caches as separate lists, each sorted by size
+pub fn scan_caches_grouped(options: &ScanOptions, min_size_bytes: u64) -> GroupedCaches {
+    GroupedCaches {
+        user: scan_user_caches(options, min_size_bytes),
+        system: scan_system_caches(options, min_size_bytes),
+    }
+}
+
+/// Most children `inspect_cache` returns; the rest are summarized by the counts
+pub const MAX_INSPECTED_CHILDREN: usize = 200;
+
+/// One immediate child of a cache directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheChild {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// What a cache directory holds, largest children first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheContents {
+    pub children: Vec<CacheChild>,
+    pub total_children: usize,
+    pub total_size: u64,
+    pub truncated: bool, // More than `children.len()` children exist
+}
+
+/// Folders that `delete_cache` is allowed to delete from
+pub fn cache_roots() -> Vec<PathBuf> {
+    let mut roots = vec![PathBuf::from("/Library/Caches")];
+    if let Some(home) = get_home_dir() {
+        roots.push(home.join("Library").join("Caches"));
+    }
+    roots
+}
+
+/// List the immediate children of a cache directory with their on-disk sizes,
+/// largest first, keeping at most `limit`. Paths outside the known cache roots are refused.
+pub fn inspect_cache(path: &str, limit: usize) -> Result<CacheContents, String> {
+    inspect_cache_within(Path::new(path), &cache_roots(), limit)
+}
+
+fn inspect_cache_within(path: &Path, roots: &[PathBuf], limit: usize) -> Result<CacheContents, String> {
+    let path = ensure_within_roots(path, roots)?;
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+
+    let entries = analyze_directory(&path, &ScanControl::default())?;
+    let total_children = entries.len();
+    let total_size = entries.iter().map(|e| e.size).sum();
+    let children = entries
+        .into_iter()
+        .take(limit)
+        .map(|e| CacheChild {
+            name: e.name,
+            path: e.path,
+            size: e.size,
+            is_dir: e.is_dir,
+        })
+        .collect::<Vec<_>>();
+    Ok(CacheContents {
+        truncated: children.len() < total_children,
+        children,
+        total_children,
+        total_size,
+    })
+}
+
+/// Delete a cache directory, through the Trash or permanently per `removal`.
+/// Paths outside the known cache roots are refused.
+pub fn delete_cache(path: &str, removal: Removal) -> Result<(), String> {
+    crate::audit::logged("delete_cache", path, || delete_cache_within(path, &cache_roots(), removal))
+}
+
+/// Delete a cache directory after checking it lies inside one of `roots`
+fn delete_cache_within(path: &str, roots: &[PathBuf], removal: Removal) -> Result<(), String> {
     let path = PathBuf::from(path);
     if path.exists() && path.is_dir() {
-        fs::remove_dir_all(&path).map_err(|e| e.to_string())?;
+        let path = ensure_within_roots(&path, roots)?;
+        removal.remove(&path)?;
     }
     Ok(())
 }
@@ -231,18 +398,186 @@ mod tests {
         // Create a file inside to give it size
         fs::write(cache_path.join("Cache.db"), "data").unwrap();
 
-        let entries = scan_directory_for_caches(&temp_dir.path().to_path_buf(), None);
+        let entries = scan_directory_for_caches(&temp_dir.path().to_path_buf(), None, &ScanOptions::default(), 0);
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].cache_type, CacheType::Browser);
         assert!(entries[0].is_safe_to_delete);
     }
 
+    #[test]
+    fn test_get_directory_size_include_xattrs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("Cache.db");
+        fs::write(&file, "1234").unwrap();
+        if xattr::set(&file, "user.com.example.blob", &vec![7u8; 3000]).is_err() {
+            return;
+        }
+
+        let on_disk = allocated_size(&fs::metadata(&file).unwrap());
+        let plain = get_directory_size(temp_dir.path(), &ScanOptions::default());
+        assert_eq!(plain, BoundedSize { size: on_disk, truncated: false });
+        let with_xattrs = get_directory_size(temp_dir.path(), &ScanOptions::default().with_include_xattrs(true));
+        assert!(with_xattrs.size >= on_disk + 3000);
+        assert!(!with_xattrs.truncated);
+    }
+
+    #[test]
+    fn test_get_directory_size_sees_nested_growth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("com.example.App").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+        let file = nested.join("blob.bin");
+        fs::write(&file, "x").unwrap();
+
+        let cache = temp_dir.path().join("com.example.App");
+        let before = get_directory_size(&cache, &ScanOptions::default()).size;
+        assert_eq!(get_directory_size(&cache, &ScanOptions::default()).size, before);
+
+        // Grows in place, leaving every directory mtime alone
+        fs::write(&file, vec![1u8; 1024 * 1024]).unwrap();
+        fs::File::open(&file)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        assert!(get_directory_size(&cache, &ScanOptions::default()).size > before);
+    }
+
+    #[test]
+    fn test_resolve_safety() {
+        let overrides = BTreeMap::from([
+            ("/Users/me/Library/Caches/com.keep.me".to_string(), false),
+            ("/Library/Caches/com.apple.stale".to_string(), true),
+        ]);
+        assert!(!resolve_safety("/Users/me/Library/Caches/com.keep.me", true, &overrides));
+        assert!(resolve_safety("/Library/Caches/com.apple.stale", false, &overrides));
+        assert!(resolve_safety("/Users/me/Library/Caches/other", true, &overrides));
+        assert!(!resolve_safety("/Library/Caches/other", false, &overrides));
+    }
+
+    #[test]
+    fn test_scan_directory_for_caches_min_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let big = temp_dir.path().join("com.example.Big");
+        let tiny = temp_dir.path().join("com.example.Tiny");
+        fs::create_dir(&big).unwrap();
+        fs::create_dir(&tiny).unwrap();
+        fs::write(big.join("data.db"), vec![1u8; 100_000]).unwrap();
+        fs::write(tiny.join("data.db"), "x").unwrap();
+
+        let root = temp_dir.path().to_path_buf();
+        let entries = scan_directory_for_caches(&root, None, &ScanOptions::default(), 50_000);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "com.example.Big");
+        assert_eq!(scan_directory_for_caches(&root, None, &ScanOptions::default(), 0).len(), 2);
+    }
+
+    #[test]
+    fn test_scan_directory_for_caches_flags_empty_databases() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let broken = root.join("com.example.broken");
+        let healthy = root.join("com.example.healthy");
+        fs::create_dir_all(broken.join("fsCachedData")).unwrap();
+        fs::create_dir_all(&healthy).unwrap();
+        fs::write(broken.join("Cache.db"), "").unwrap();
+        fs::write(broken.join("fsCachedData").join("blob"), vec![1u8; 1024]).unwrap();
+        fs::write(healthy.join("Cache.db"), vec![1u8; 1024]).unwrap();
+        fs::write(healthy.join("Cache.db-wal"), "").unwrap();
+
+        let is_corrupt = |entries: &[CacheEntry], name: &str| entries.iter().find(|e| e.name == name).unwrap().possibly_corrupt;
+
+        let unchecked = scan_directory_for_caches(&root, None, &ScanOptions::default(), 0);
+        assert!(!is_corrupt(&unchecked, "com.example.broken"));
+
+        let options = ScanOptions::default().with_check_cache_databases(true);
+        let checked = scan_directory_for_caches(&root, None, &options, 0);
+        assert!(is_corrupt(&checked, "com.example.broken"));
+        assert!(!is_corrupt(&checked, "com.example.healthy"));
+    }
+
+    #[test]
+    fn test_delete_cache_within_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("Caches");
+        let cache = root.join("com.example.App");
+        fs::create_dir_all(&cache).unwrap();
+
+        delete_cache_within(&cache.to_string_lossy(), &[root], Removal::Permanent).unwrap();
+        assert!(!cache.exists());
+    }
+
+    #[test]
+    fn test_inspect_cache_within() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("Caches");
+        let cache = root.join("com.example.App");
+        fs::create_dir_all(cache.join("fsCachedData")).unwrap();
+        fs::write(cache.join("fsCachedData").join("blob"), vec![1u8; 64 * 1024]).unwrap();
+        fs::write(cache.join("Cache.db"), vec![1u8; 8 * 1024]).unwrap();
+        fs::write(cache.join("small"), "x").unwrap();
+        let roots = [root.clone()];
+
+        let contents = inspect_cache_within(&cache, &roots, 10).unwrap();
+        let names: Vec<&str> = contents.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["fsCachedData", "Cache.db", "small"]);
+        assert!(contents.children[0].is_dir);
+        assert_eq!(contents.total_children, 3);
+        assert!(!contents.truncated);
+
+        let capped = inspect_cache_within(&cache, &roots, 2).unwrap();
+        assert_eq!(capped.children.len(), 2);
+        assert!(capped.truncated);
+        assert_eq!(capped.total_size, contents.total_size);
+
+        assert!(inspect_cache_within(temp_dir.path(), &roots, 10).is_err());
+        assert!(inspect_cache_within(&cache.join("small"), &roots, 10).is_err());
+    }
+
+    #[test]
+    fn test_delete_cache_rejects_paths_outside_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("Caches");
+        let documents = temp_dir.path().join("Documents");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&documents).unwrap();
+
+        let traversal = root.join("..").join("Documents");
+        let roots = [root];
+        assert!(delete_cache_within(&documents.to_string_lossy(), &roots, Removal::Permanent).is_err());
+        assert!(delete_cache_within(&traversal.to_string_lossy(), &roots, Removal::Permanent).is_err());
+        assert!(documents.exists());
+    }
+
     #[test]
     fn test_wrappers_sanity() {
-        let _ = scan_user_caches();
+        let options = ScanOptions::default();
+        let _ = scan_user_caches(&options, 0);
         // scan_system_caches reads /Library/Caches which exists on mac.
         // It should be fine to call.
-        let _ = scan_system_caches();
-        let _ = scan_all_caches();
+        let _ = scan_system_caches(&options, 0);
+        let _ = scan_all_caches(&options, 0);
+        let _ = scan_caches_grouped(&options, 0);
+    }
+
+    #[test]
+    fn test_scan_directory_for_caches_max_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_path = temp_dir.path().join("com.example.App");
+        let nested = cache_path.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(cache_path.join("top.db"), "1234").unwrap();
+        fs::write(nested.join("deep.db"), "12345678").unwrap();
+
+        let on_disk = |path: PathBuf| allocated_size(&fs::metadata(path).unwrap());
+        let top = on_disk(cache_path.join("top.db"));
+        let deep = on_disk(nested.join("deep.db"));
+
+        let root = temp_dir.path().to_path_buf();
+        let full = scan_directory_for_caches(&root, None, &ScanOptions::default(), 0);
+        assert_eq!(full[0].size, top + deep);
+        assert!(!full[0].truncated);
+
+        let shallow = scan_directory_for_caches(&root, None, &ScanOptions::with_max_depth(Some(1)), 0);
+        assert_eq!(shallow[0].size, top);
     }
 }
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::disk_size::get_directory_size;
+use crate::path_guard::ensure_within_roots;
+use crate::removal::Removal;
+
+/// Shared clang module cache inside DerivedData. Rebuilding it slows every
+/// project's next build, so it is only deleted when asked for explicitly.
+pub const MODULE_CACHE_FOLDER: &str = "ModuleCache.noindex";
+
+/// Length of the hash Xcode appends to DerivedData folder names
+const PROJECT_HASH_LEN: usize = 28;
+
+/// One project's folder in Xcode's DerivedData
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivedDataProject {
+    pub path: String,
+    pub folder_name: String,
+    pub project_name: String, // Folder name without the trailing hash
+    pub size: u64,
+    pub last_modified: Option<u64>, // Unix timestamp
+    pub is_module_cache: bool,
+}
+
+/// Xcode's DerivedData folder for the current user
+pub fn derived_data_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library").join("Developer").join("Xcode").join("DerivedData"))
+}
+
+/// Project name from a DerivedData folder name such as
+/// "MyApp-fzxdbkqcpiyvbdeqwsaotpmhyjns". Names without a hash are returned as-is.
+fn project_name_from_folder(folder_name: &str) -> String {
+    match folder_name.rsplit_once('-') {
+        Some((name, hash))
+            if !name.is_empty()
+                && hash.len() == PROJECT_HASH_LEN
+                && hash.chars().all(|c| c.is_ascii_lowercase()) =>
+        {
+            name.to_string()
+        }
+        _ => folder_name.to_string(),
+    }
+}
+
+/// Modification time of a path as a Unix timestamp
+fn modified_timestamp(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .ok()?
+        .modified()
+        .ok()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// List each immediate subfolder of `dir`, largest first
+pub fn scan_derived_data_in(dir: &Path) -> Vec<DerivedDataProject> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut projects: Vec<DerivedDataProject> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && !p.is_symlink())
+        .map(|path| {
+            let folder_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            DerivedDataProject {
+                project_name: project_name_from_folder(&folder_name),
+                is_module_cache: folder_name == MODULE_CACHE_FOLDER,
+                size: get_directory_size(&path),
+                last_modified: modified_timestamp(&path),
+                path: path.to_string_lossy().to_string(),
+                folder_name,
+            }
+        })
+        .collect();
+
+    projects.sort_by(|a, b| b.size.cmp(&a.size));
+    projects
+}
+
+/// Scan the user's DerivedData folder per project
+pub fn scan_derived_data() -> Vec<DerivedDataProject> {
+    derived_data_dir().map(|dir| scan_derived_data_in(&dir)).unwrap_or_default()
+}
+
+/// Delete one project folder directly inside `dir`, through the Trash or
+/// permanently per `removal`. The module cache is refused unless
+/// `allow_module_cache` is set. Returns the bytes freed.
+pub fn delete_derived_data_in(path: &str, dir: &Path, allow_module_cache: bool, removal: Removal) -> Result<u64, String> {
+    let roots = [dir.to_path_buf()];
+    let target = ensure_within_roots(Path::new(path), &roots)?;
+    let canonical_dir = dir.canonicalize().map_err(|e| e.to_string())?;
+    if target.parent() != Some(canonical_dir.as_path()) {
+        return Err(format!("Not a DerivedData project folder: {}", path));
+    }
+    if !allow_module_cache && target.file_name().map(|n| n == MODULE_CACHE_FOLDER).unwrap_or(false) {
+        return Err(format!("{} is shared by all projects; select it explicitly to delete it", MODULE_CACHE_FOLDER));
+    }
+
+    let size = get_directory_size(&target);
+    let result = crate::path_guard::ensure_not_protected(&target).and_then(|_| removal.remove(&target));
+    crate::audit::record("delete_derived_data", path, size, &result);
+    result.map(|_| size)
+}
+
+/// Delete a project folder from the user's DerivedData folder
+pub fn delete_derived_data(path: &str, allow_module_cache: bool, removal: Removal) -> Result<u64, String> {
+    let dir = derived_data_dir().ok_or("Could not determine home directory")?;
+    delete_derived_data_in(path, &dir, allow_module_cache, removal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_derived_data() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let app = temp_dir.path().join("MyApp-fzxdbkqcpiyvbdeqwsaotpmhyjns");
+        fs::create_dir_all(app.join("Build")).unwrap();
+        fs::write(app.join("Build").join("out.o"), vec![1u8; 16384]).unwrap();
+        fs::create_dir_all(temp_dir.path().join(MODULE_CACHE_FOLDER)).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_project_name_from_folder() {
+        assert_eq!(project_name_from_folder("MyApp-fzxdbkqcpiyvbdeqwsaotpmhyjns"), "MyApp");
+        assert_eq!(project_name_from_folder("My-App-fzxdbkqcpiyvbdeqwsaotpmhyjns"), "My-App");
+        assert_eq!(project_name_from_folder("ModuleCache.noindex"), "ModuleCache.noindex");
+        assert_eq!(project_name_from_folder("Tool-short"), "Tool-short");
+    }
+
+    #[test]
+    fn test_scan_derived_data_in() {
+        let dir = make_derived_data();
+        let projects = scan_derived_data_in(dir.path());
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].project_name, "MyApp");
+        assert!(projects[0].size >= 16384);
+        assert!(projects[0].last_modified.is_some());
+        assert!(projects.iter().any(|p| p.is_module_cache));
+    }
+
+    #[test]
+    fn test_delete_derived_data_in() {
+        let dir = make_derived_data();
+        let app = dir.path().join("MyApp-fzxdbkqcpiyvbdeqwsaotpmhyjns");
+
+        let freed = delete_derived_data_in(&app.to_string_lossy(), dir.path(), false, Removal::Permanent).unwrap();
+        assert!(freed >= 16384);
+        assert!(!app.exists());
+    }
+
+    #[test]
+    fn test_delete_derived_data_in_guards_module_cache() {
+        let dir = make_derived_data();
+        let module_cache = dir.path().join(MODULE_CACHE_FOLDER);
+        let path = module_cache.to_string_lossy().to_string();
+
+        assert!(delete_derived_data_in(&path, dir.path(), false, Removal::Permanent).is_err());
+        assert!(module_cache.exists());
+        assert!(delete_derived_data_in(&path, dir.path(), true, Removal::Permanent).is_ok());
+        assert!(!module_cache.exists());
+    }
+
+    #[test]
+    fn test_delete_derived_data_in_rejects_nested_and_outside_paths() {
+        let dir = make_derived_data();
+        let nested = dir.path().join("MyApp-fzxdbkqcpiyvbdeqwsaotpmhyjns").join("Build");
+        let outside = tempfile::tempdir().unwrap();
+
+        assert!(delete_derived_data_in(&nested.to_string_lossy(), dir.path(), false, Removal::Permanent).is_err());
+        assert!(delete_derived_data_in(&outside.path().to_string_lossy(), dir.path(), false, Removal::Permanent).is_err());
+        assert!(nested.exists());
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+
+use super::disk_size::get_path_size_with_control;
+use super::scan_control::ScanControl;
+
+/// On-disk usage of one immediate child of an analyzed directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DirEntryUsage {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool, // Symlinks are reported as files and not followed
+}
+
+/// Size every immediate child of `dir`, largest first, spreading the children
+/// across threads. Stops early once `control` is cancelled, returning only the
+/// children sized so far.
+pub fn analyze_directory(dir: &Path, control: &ScanControl) -> Result<Vec<DirEntryUsage>, String> {
+    let children: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(children.len().max(1));
+    // Workers pull from a shared queue so one huge folder doesn't hold up a whole chunk
+    let queue = Mutex::new(children.into_iter());
+    let next = || queue.lock().ok().and_then(|mut q| q.next());
+
+    let mut entries: Vec<DirEntryUsage> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut sized = Vec::new();
+                    while let Some(path) = next() {
+                        if let Some(entry) = entry_usage(&path, control) {
+                            sized.push(entry);
+                        }
+                    }
+                    sized
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    });
+
+    entries.sort_by(|a, b| b.size.cmp(&a.size));
+    Ok(entries)
+}
+
+/// Size a single child, or None if it can't be read or the scan was cancelled
+fn entry_usage(path: &Path, control: &ScanControl) -> Option<DirEntryUsage> {
+    let is_dir = fs::symlink_metadata(path).ok()?.is_dir();
+    let size = get_path_size_with_control(path, control)?;
+    Some(DirEntryUsage {
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        size,
+        is_dir,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let big = temp_dir.path().join("big");
+        fs::create_dir_all(big.join("nested")).unwrap();
+        fs::write(big.join("nested").join("data.bin"), vec![1u8; 200_000]).unwrap();
+        fs::create_dir(temp_dir.path().join("empty")).unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), vec![1u8; 20_000]).unwrap();
+
+        let entries = analyze_directory(temp_dir.path(), &ScanControl::default()).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["big", "notes.txt", "empty"]);
+        assert!(entries[0].is_dir);
+        assert!(entries[0].size >= 200_000);
+        assert!(!entries[1].is_dir);
+        assert_eq!(entries[2].size, 0);
+    }
+
+    #[test]
+    fn test_analyze_directory_cancelled_and_missing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("sub")).unwrap();
+
+        let control = ScanControl::default();
+        control.cancel();
+        assert!(analyze_directory(temp_dir.path(), &control).unwrap().is_empty());
+        assert!(analyze_directory(&temp_dir.path().join("missing"), &control).is_err());
+    }
+}
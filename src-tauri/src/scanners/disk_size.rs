@@ -0,0 +1,256 @@
+use std::fs::Metadata;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::scan_control::ScanControl;
+use super::size_cache;
+
+/// Actual disk usage of a single file, from its allocated blocks.
+/// This correctly handles sparse files like Docker.raw.
+pub fn allocated_size(metadata: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // blocks are in 512-byte units
+        metadata.blocks() * 512
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.len()
+    }
+}
+
+/// Total bytes stored in a path's extended attributes, including the resource
+/// fork (`com.apple.ResourceFork`). Symlinks are not followed.
+///
+/// APFS may store small attributes inline with the file's metadata rather than
+/// in data blocks, so `allocated_size` alone can under-count what `du` and
+/// Finder report for attribute-heavy files. Reading every attribute is slow,
+/// so callers only add this when asked.
+pub fn xattr_size(path: &Path) -> u64 {
+    let Ok(names) = xattr::list(path) else {
+        return 0;
+    };
+    names
+        .filter_map(|name| xattr::get(path, &name).ok().flatten())
+        .map(|value| value.len() as u64)
+        .sum()
+}
+
+/// Size of a directory both as allocated on disk and as the sum of file
+/// lengths. They differ for sparse and compressed files, like `du -h` vs `du -Ah`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DirectorySizes {
+    pub on_disk: u64,
+    pub apparent: u64,
+}
+
+/// Entries a directory size visits before giving up with a partial sum, so a
+/// tree with millions of tiny files can't hang a whole scan
+pub const DEFAULT_MAX_ENTRIES: usize = 2_000_000;
+
+/// Size from a walk that may have stopped early
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoundedSize {
+    pub size: u64,
+    /// The walk was cancelled or hit its entry budget, so `size` is a lower bound
+    pub truncated: bool,
+}
+
+/// Calculate directory size using actual disk blocks (handles sparse files correctly).
+/// Results are reused from the size cache while nothing in the tree has changed.
+/// Trees larger than `DEFAULT_MAX_ENTRIES` entries report a partial size.
+pub fn get_directory_size(path: &Path) -> u64 {
+    let bounded = get_directory_size_bounded(path, None, Some(DEFAULT_MAX_ENTRIES));
+    if bounded.truncated {
+        log::warn!("Stopped sizing {} after {} entries", path.display(), DEFAULT_MAX_ENTRIES);
+    }
+    bounded.size
+}
+
+/// Directory size that stops once `control` is cancelled or after visiting
+/// `max_entries` entries, returning the partial sum marked as truncated.
+/// Only complete sizes are cached.
+pub fn get_directory_size_bounded(path: &Path, control: Option<&ScanControl>, max_entries: Option<usize>) -> BoundedSize {
+    let mut partial = 0;
+    let complete = size_cache::try_cached_directory_size(path, || {
+        let bounded = compute_directory_size(path, control, max_entries);
+        partial = bounded.size;
+        (!bounded.truncated).then_some(bounded.size)
+    });
+    match complete {
+        Some(size) => BoundedSize { size, truncated: false },
+        None => BoundedSize { size: partial, truncated: true },
+    }
+}
+
+/// Walk a directory and sum its allocated file sizes, bypassing the cache
+fn compute_directory_size(path: &Path, control: Option<&ScanControl>, max_entries: Option<usize>) -> BoundedSize {
+    let mut result = BoundedSize::default();
+    for (visited, entry) in WalkDir::new(path).into_iter().filter_map(|e| e.ok()).enumerate() {
+        let cancelled = control.is_some_and(|c| !c.checkpoint());
+        if cancelled || max_entries.is_some_and(|max| visited >= max) {
+            result.truncated = true;
+            break;
+        }
+        if let Ok(m) = entry.metadata() {
+            if m.is_file() {
+                result.size += allocated_size(&m);
+            }
+        }
+    }
+    result
+}
+
+/// On-disk and apparent size of a directory, gathered in a single walk.
+/// Results are reused from the size cache while nothing in the tree has changed.
+pub fn get_directory_sizes(path: &Path) -> DirectorySizes {
+    size_cache::cached_directory_sizes(path, || {
+        WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .fold(DirectorySizes::default(), |sizes, m| DirectorySizes {
+                on_disk: sizes.on_disk + allocated_size(&m),
+                apparent: sizes.apparent + m.len(),
+            })
+    })
+}
+
+/// On-disk size of a path, whether it is a file or a directory
+pub fn get_path_size(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => get_directory_size(path),
+        Ok(m) => allocated_size(&m),
+        Err(_) => 0,
+    }
+}
+
+/// On-disk size of a path like `get_path_size`, but checking `control` between
+/// entries so a very large tree can be abandoned. Returns None once cancelled;
+/// partial sums are never cached.
+pub fn get_path_size_with_control(path: &Path, control: &ScanControl) -> Option<u64> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return Some(allocated_size(&metadata));
+    }
+
+    let bounded = get_directory_size_bounded(path, Some(control), None);
+    (!bounded.truncated).then_some(bounded.size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_get_directory_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("a.bin"), vec![1u8; 10_000]).unwrap();
+        fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        fs::write(temp_dir.path().join("sub").join("b.bin"), vec![1u8; 10_000]).unwrap();
+
+        // Allocated size is at least the written data
+        assert!(get_directory_size(temp_dir.path()) >= 20_000);
+    }
+
+    #[test]
+    fn test_get_directory_size_bounded_budget_truncates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for i in 0..10 {
+            fs::write(temp_dir.path().join(format!("{}.bin", i)), vec![1u8; 4096]).unwrap();
+        }
+
+        let partial = get_directory_size_bounded(temp_dir.path(), None, Some(4));
+        assert!(partial.truncated);
+
+        // The partial sum isn't cached, so a full walk still sees everything
+        let full = get_directory_size_bounded(temp_dir.path(), None, None);
+        assert!(!full.truncated);
+        assert!(full.size >= 40_960);
+        assert!(partial.size < full.size);
+    }
+
+    #[test]
+    fn test_get_directory_size_bounded_cancelled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("a.bin"), vec![1u8; 4096]).unwrap();
+
+        let control = ScanControl::default();
+        control.cancel();
+        let bounded = get_directory_size_bounded(temp_dir.path(), Some(&control), None);
+        assert_eq!(bounded, BoundedSize { size: 0, truncated: true });
+    }
+
+    #[test]
+    fn test_get_path_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("a.bin");
+        fs::write(&file, vec![1u8; 10_000]).unwrap();
+
+        assert!(get_path_size(&file) >= 10_000);
+        assert_eq!(get_path_size(temp_dir.path()), get_directory_size(temp_dir.path()));
+        assert_eq!(get_path_size(&temp_dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn test_get_path_size_with_control() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let empty = temp_dir.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        let full = temp_dir.path().join("full");
+        fs::create_dir(&full).unwrap();
+        fs::write(full.join("a.bin"), vec![1u8; 10_000]).unwrap();
+
+        let control = ScanControl::default();
+        assert_eq!(get_path_size_with_control(&empty, &control), Some(0));
+        assert!(get_path_size_with_control(&full, &control).unwrap() >= 10_000);
+        assert_eq!(get_path_size_with_control(&temp_dir.path().join("missing"), &control), None);
+
+        let cancelled_dir = temp_dir.path().join("cancelled");
+        fs::create_dir(&cancelled_dir).unwrap();
+        control.cancel();
+        assert_eq!(get_path_size_with_control(&cancelled_dir, &control), None);
+    }
+
+    #[test]
+    fn test_xattr_size_counts_large_attribute() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("tagged.txt");
+        fs::write(&file, "data").unwrap();
+        assert_eq!(xattr_size(&file), 0);
+
+        // Not every filesystem a test runs on supports extended attributes
+        if xattr::set(&file, "user.com.example.blob", &vec![7u8; 3000]).is_err() {
+            return;
+        }
+        assert!(xattr_size(&file) >= 3000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sparse_file_uses_allocated_blocks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("sparse.raw");
+        let f = fs::File::create(&file).unwrap();
+        f.set_len(100 * 1024 * 1024).unwrap(); // 100MB apparent, nothing written
+
+        assert!(get_path_size(&file) < 100 * 1024 * 1024);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_directory_sizes_sparse_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sparse = fs::File::create(temp_dir.path().join("disk.raw")).unwrap();
+        sparse.set_len(64 * 1024 * 1024).unwrap();
+        fs::write(temp_dir.path().join("small.txt"), vec![1u8; 100]).unwrap();
+
+        let sizes = get_directory_sizes(temp_dir.path());
+        assert_eq!(sizes.apparent, 64 * 1024 * 1024 + 100);
+        assert!(sizes.on_disk < sizes.apparent);
+        assert_eq!(sizes.on_disk, get_directory_size(temp_dir.path()));
+    }
+}
@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::ffi::OsStr;
 use std::fs;
-use std::path::PathBuf;
-use std::time::SystemTime;
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::disk_size::allocated_size;
+use super::scan_options::{scan_roots_concurrently, ScanOptions};
+use crate::pagination::Sortable;
+use crate::process::run_command_with_timeout;
+use crate::removal::Removal;
 
 /// Categories of large files
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -14,9 +22,35 @@ pub enum FileCategory {
     Document,
     Application,
     DiskImage,
+    Font,
+    Ebook,
+    VirtualMachine,
+    /// No built-in extensions; only assigned through user mappings
+    Project,
     Other,
 }
 
+impl FileCategory {
+    /// Look up a category by its variant name, e.g. "Image"
+    pub fn from_name(name: &str) -> Option<FileCategory> {
+        match name {
+            "Video" => Some(FileCategory::Video),
+            "Image" => Some(FileCategory::Image),
+            "Audio" => Some(FileCategory::Audio),
+            "Archive" => Some(FileCategory::Archive),
+            "Document" => Some(FileCategory::Document),
+            "Application" => Some(FileCategory::Application),
+            "DiskImage" => Some(FileCategory::DiskImage),
+            "Font" => Some(FileCategory::Font),
+            "Ebook" => Some(FileCategory::Ebook),
+            "VirtualMachine" => Some(FileCategory::VirtualMachine),
+            "Project" => Some(FileCategory::Project),
+            "Other" => Some(FileCategory::Other),
+            _ => None,
+        }
+    }
+}
+
 /// Represents a large file found on the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LargeFile {
@@ -26,6 +60,40 @@ pub struct LargeFile {
     pub category: FileCategory,
     pub last_modified: Option<u64>, // Unix timestamp
     pub extension: String,
+    /// Bytes actually allocated on disk; below `size` for sparse files
+    #[serde(default)]
+    pub on_disk_size: u64,
+    /// Set when the file occupies meaningfully less space than its length,
+    /// e.g. Docker.raw, so deleting it frees far less than `size`
+    #[serde(default)]
+    pub is_sparse: bool,
+}
+
+impl Sortable for LargeFile {
+    fn sort_size(&self) -> u64 {
+        self.size
+    }
+    fn sort_name(&self) -> &str {
+        &self.name
+    }
+    fn sort_date(&self) -> Option<u64> {
+        self.last_modified
+    }
+}
+
+/// Aggregate count and size for a single file category
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CategoryTotal {
+    pub count: u64,
+    pub total_bytes: u64,
+}
+
+/// Large files together with per-category totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeFileReport {
+    pub files: Vec<LargeFile>,
+    pub totals_by_category: HashMap<String, CategoryTotal>,
+    pub skipped_mounts: Vec<String>,
 }
 
 /// Video file extensions
@@ -54,15 +122,51 @@ const DOCUMENT_EXTENSIONS: &[&str] = &[
     "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "pages", "numbers", "keynote"
 ];
 
+/// Font file extensions
+const FONT_EXTENSIONS: &[&str] = &[
+    "ttf", "otf", "woff", "woff2"
+];
+
+/// Ebook file extensions
+const EBOOK_EXTENSIONS: &[&str] = &[
+    "epub", "mobi", "azw3"
+];
+
+/// Virtual machine image extensions
+const VIRTUAL_MACHINE_EXTENSIONS: &[&str] = &[
+    "vmdk", "qcow2", "vdi", "vbox"
+];
+
+/// Unallocated bytes a file needs before it is reported as sparse, so
+/// rounding to block size on small files doesn't count
+const SPARSE_MIN_UNALLOCATED: u64 = 1024 * 1024;
+
+/// How long to wait for a Spotlight query before falling back to a walk
+const SPOTLIGHT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Files that are legitimately empty or tiny and must not be flagged as clutter
+const LEGITIMATE_TINY_FILES: &[&str] = &[
+    ".gitkeep", ".keep", ".gitignore", ".npmignore", "__init__.py", "py.typed", ".nojekyll"
+];
+
 /// Get the user's home directory
 fn get_home_dir() -> Option<PathBuf> {
     dirs::home_dir()
 }
 
-/// Determine the file category based on extension
-fn get_file_category(extension: &str) -> FileCategory {
+/// The user's extension-to-category mappings from settings, keyed by lowercase extension
+fn extension_overrides() -> BTreeMap<String, FileCategory> {
+    crate::commands::config::load_settings().extension_categories
+}
+
+/// Determine the file category based on extension. User mappings in
+/// `overrides` take precedence over the built-in lists.
+fn get_file_category(extension: &str, overrides: &BTreeMap<String, FileCategory>) -> FileCategory {
     let ext = extension.to_lowercase();
     
+    if let Some(category) = overrides.get(&ext) {
+        return category.clone();
+    }
     if VIDEO_EXTENSIONS.contains(&ext.as_str()) {
         return FileCategory::Video;
     }
@@ -78,6 +182,15 @@ fn get_file_category(extension: &str) -> FileCategory {
     if DOCUMENT_EXTENSIONS.contains(&ext.as_str()) {
         return FileCategory::Document;
     }
+    if FONT_EXTENSIONS.contains(&ext.as_str()) {
+        return FileCategory::Font;
+    }
+    if EBOOK_EXTENSIONS.contains(&ext.as_str()) {
+        return FileCategory::Ebook;
+    }
+    if VIRTUAL_MACHINE_EXTENSIONS.contains(&ext.as_str()) {
+        return FileCategory::VirtualMachine;
+    }
     if ext == "app" {
         return FileCategory::Application;
     }
@@ -88,29 +201,62 @@ fn get_file_category(extension: &str) -> FileCategory {
     FileCategory::Other
 }
 
-/// Scan a directory for large files
-pub fn scan_large_files(
+/// Whether a file of `len` bytes using `on_disk` bytes should be reported as sparse:
+/// at least `SPARSE_MIN_UNALLOCATED` unallocated and under three quarters allocated
+fn is_sparse(len: u64, on_disk: u64) -> bool {
+    len.saturating_sub(on_disk) >= SPARSE_MIN_UNALLOCATED && on_disk < len / 4 * 3
+}
+
+/// Build a `LargeFile` entry from a path and its metadata
+fn file_entry(file_path: &Path, metadata: &fs::Metadata, overrides: &BTreeMap<String, FileCategory>) -> LargeFile {
+    let extension = file_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let last_modified = metadata.modified().ok().and_then(|t| {
+        t.duration_since(SystemTime::UNIX_EPOCH).ok().map(|d| d.as_secs())
+    });
+    let on_disk_size = allocated_size(metadata);
+    
+    LargeFile {
+        path: file_path.to_string_lossy().to_string(),
+        name: file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        size: metadata.len(),
+        on_disk_size,
+        is_sparse: is_sparse(metadata.len(), on_disk_size),
+        category: get_file_category(&extension, overrides),
+        last_modified,
+        extension,
+    }
+}
+
+/// Walk a directory and invoke `on_file` for every large file, in discovery order
+fn walk_large_files<F: FnMut(LargeFile)>(
     directory: &str,
     min_size_mb: u64,
-    categories: Option<Vec<FileCategory>>,
-) -> Vec<LargeFile> {
-    let mut large_files = Vec::new();
-    let min_size_bytes = min_size_mb * 1024 * 1024;
+    categories: &Option<Vec<FileCategory>>,
+    options: &ScanOptions,
+    mut on_file: F,
+) {
+    let min_size_bytes = min_size_mb.saturating_mul(1024 * 1024);
     let path = PathBuf::from(directory);
     
     if !path.exists() {
-        return large_files;
+        return;
     }
     
-    for entry in WalkDir::new(&path)
-        .into_iter()
-        .filter_map(|e| e.ok())
+    let overrides = extension_overrides();
+    for entry in options
+        .walk(&path)
         .filter(|e| e.file_type().is_file())
     {
         let file_path = entry.path();
         
-        // Skip hidden files
-        if file_path.file_name().map(|s| s.to_string_lossy().starts_with('.')).unwrap_or(false) {
+        // Skip hidden files unless asked to include them
+        if options.skips_hidden(file_path) {
             continue;
         }
         
@@ -118,98 +264,402 @@ pub fn scan_large_files(
             let size = metadata.len();
             
             if size >= min_size_bytes {
-                let extension = file_path
-                    .extension()
-                    .map(|e| e.to_string_lossy().to_string())
-                    .unwrap_or_default();
-                
-                let category = get_file_category(&extension);
+                let file = file_entry(file_path, &metadata, &overrides);
                 
                 // Filter by category if specified
-                if let Some(ref cats) = categories {
-                    if !cats.contains(&category) {
+                if let Some(cats) = categories {
+                    if !cats.contains(&file.category) {
                         continue;
                     }
                 }
                 
-                let last_modified = metadata.modified().ok().and_then(|t| {
-                    t.duration_since(SystemTime::UNIX_EPOCH).ok().map(|d| d.as_secs())
-                });
-                
-                large_files.push(LargeFile {
-                    path: file_path.to_string_lossy().to_string(),
-                    name: file_path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default(),
-                    size,
-                    category,
-                    last_modified,
-                    extension,
-                });
+                on_file(file);
             }
         }
     }
+}
+
+/// Scan a directory for large files
+pub fn scan_large_files(
+    directory: &str,
+    min_size_mb: u64,
+    categories: Option<Vec<FileCategory>>,
+    options: &ScanOptions,
+) -> Vec<LargeFile> {
+    let mut large_files = Vec::new();
+    walk_large_files(directory, min_size_mb, &categories, options, |file| large_files.push(file));
     
     // Sort by size descending
     large_files.sort_by(|a, b| b.size.cmp(&a.size));
     large_files
 }
 
-/// Scan common directories for large files
-pub fn scan_common_directories(min_size_mb: u64) -> Vec<LargeFile> {
-    let mut all_files = Vec::new();
+/// Scan a directory for large files, handing them to `on_batch` as they are found.
+///
+/// Files are buffered into batches of `batch_size`; each batch is sorted by size
+/// descending before it is flushed, but batches themselves arrive in discovery
+/// order, so the overall stream is not globally sorted. Returns the number of
+/// files emitted.
+pub fn scan_large_files_streaming<F: FnMut(Vec<LargeFile>)>(
+    directory: &str,
+    min_size_mb: u64,
+    categories: Option<Vec<FileCategory>>,
+    options: &ScanOptions,
+    batch_size: usize,
+    mut on_batch: F,
+) -> usize {
+    let batch_size = batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut emitted = 0;
     
-    if let Some(home) = get_home_dir() {
-        // Scan common large file locations
-        let directories = vec![
-            home.join("Downloads"),
-            home.join("Desktop"),
-            home.join("Documents"),
-            home.join("Movies"),
-            home.join("Music"),
-            home.join("Pictures"),
-        ];
-        
-        for dir in directories {
-            if dir.exists() {
-                all_files.extend(scan_large_files(
-                    &dir.to_string_lossy(),
-                    min_size_mb,
-                    None,
-                ));
+    let mut flush = |batch: &mut Vec<LargeFile>| {
+        batch.sort_by(|a, b| b.size.cmp(&a.size));
+        emitted += batch.len();
+        on_batch(std::mem::take(batch));
+    };
+    
+    walk_large_files(directory, min_size_mb, &categories, options, |file| {
+        batch.push(file);
+        if batch.len() >= batch_size {
+            flush(&mut batch);
+        }
+    });
+    
+    if !batch.is_empty() {
+        flush(&mut batch);
+    }
+    
+    emitted
+}
+
+/// Orders large files by size, then path, for the top-N heap
+struct BySize(LargeFile);
+
+impl PartialEq for BySize {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for BySize {}
+
+impl PartialOrd for BySize {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BySize {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.size.cmp(&other.0.size).then_with(|| other.0.path.cmp(&self.0.path))
+    }
+}
+
+/// The `n` largest files under `roots`, largest first. Only the current top `n`
+/// are held in memory (a min-heap), so even a whole-home walk stays small.
+/// Roots nested inside another root are skipped so no file is counted twice.
+/// Network and System volumes are skipped as `options` dictates, and the walk
+/// stops early, returning what it has, once `options` is cancelled.
+pub fn top_n_largest_files(roots: &[PathBuf], n: usize, options: &ScanOptions) -> Vec<LargeFile> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let overrides = extension_overrides();
+    let mut heap: BinaryHeap<Reverse<BySize>> = BinaryHeap::with_capacity(n + 1);
+
+    let outermost = roots
+        .iter()
+        .enumerate()
+        .filter(|(i, root)| !roots.iter().enumerate().any(|(j, other)| root.starts_with(other) && (other != *root || j < *i)));
+    for (_, root) in outermost {
+        for entry in options.walk(root).filter(|e| e.file_type().is_file()) {
+            if options.skips_hidden(entry.path()) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            // Don't build an entry for files that can't make the cut
+            let smallest = heap.peek().map(|Reverse(BySize(f))| f.size);
+            if heap.len() >= n && smallest.is_some_and(|min| metadata.len() <= min) {
+                continue;
+            }
+            heap.push(Reverse(BySize(file_entry(entry.path(), &metadata, &overrides))));
+            if heap.len() > n {
+                heap.pop();
             }
         }
     }
+
+    heap.into_sorted_vec().into_iter().map(|Reverse(BySize(file))| file).collect()
+}
+
+/// Scan a directory for large files and aggregate totals per category
+pub fn scan_large_files_with_report(
+    directory: &str,
+    min_size_mb: u64,
+    categories: Option<Vec<FileCategory>>,
+    options: &ScanOptions,
+) -> LargeFileReport {
+    let files = scan_large_files(directory, min_size_mb, categories, options);
+    let mut totals_by_category: HashMap<String, CategoryTotal> = HashMap::new();
+    
+    for file in &files {
+        let total = totals_by_category
+            .entry(format!("{:?}", file.category))
+            .or_default();
+        total.count += 1;
+        total.total_bytes += file.size;
+    }
+    
+    LargeFileReport {
+        files,
+        totals_by_category,
+        skipped_mounts: options.skipped_mounts(),
+    }
+}
+
+/// Scan a directory for zero-byte and tiny files at or below `max_size_bytes`.
+///
+/// Hidden files, anything inside hidden folders (e.g. `.git`) and known placeholder
+/// files are skipped. Results are sorted by parent directory, then name, so they can
+/// be grouped by folder.
+pub fn scan_tiny_files(directory: &str, max_size_bytes: u64, options: &ScanOptions) -> Vec<LargeFile> {
+    let root = PathBuf::from(directory);
+    if !root.exists() {
+        return Vec::new();
+    }
+    
+    let overrides = extension_overrides();
+    let mut files: Vec<LargeFile> = options
+        .walk(&root)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let relative = e.path().strip_prefix(&root).unwrap_or(e.path());
+            !relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        })
+        .filter(|e| !LEGITIMATE_TINY_FILES.contains(&e.file_name().to_string_lossy().as_ref()))
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            (metadata.len() <= max_size_bytes).then(|| file_entry(e.path(), &metadata, &overrides))
+        })
+        .collect();
     
-    // Sort and deduplicate
+    files.sort_by(|a, b| {
+        let parent_a = Path::new(&a.path).parent();
+        let parent_b = Path::new(&b.path).parent();
+        parent_a.cmp(&parent_b).then_with(|| a.name.cmp(&b.name))
+    });
+    files
+}
+
+/// Drop files that resolve to the same canonical path as an earlier entry, so
+/// overlapping or symlinked roots don't list a file twice
+pub fn dedupe_by_canonical_path(files: Vec<LargeFile>) -> Vec<LargeFile> {
+    let mut seen = std::collections::HashSet::new();
+    files
+        .into_iter()
+        .filter(|f| seen.insert(fs::canonicalize(&f.path).unwrap_or_else(|_| PathBuf::from(&f.path))))
+        .collect()
+}
+
+/// Scan each existing directory for large files, optionally limited to `categories`,
+/// with up to `max_concurrency` directories at once. Roots may overlap; each file is listed once.
+fn scan_directories(
+    directories: &[PathBuf],
+    min_size_mb: u64,
+    categories: Option<Vec<FileCategory>>,
+    options: &ScanOptions,
+    max_concurrency: usize,
+) -> Vec<LargeFile> {
+    let per_dir = scan_roots_concurrently(directories, max_concurrency, |dir| {
+        if !dir.exists() {
+            return Vec::new();
+        }
+        scan_large_files(&dir.to_string_lossy(), min_size_mb, categories.clone(), options)
+    });
+
+    let mut all_files = dedupe_by_canonical_path(per_dir.into_iter().flatten().collect());
     all_files.sort_by(|a, b| b.size.cmp(&a.size));
     all_files
 }
 
-/// Delete a file
-pub fn delete_file(path: &str) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if path.exists() && path.is_file() {
-        fs::remove_file(&path).map_err(|e| e.to_string())?;
+/// Scan the common scan roots from settings for large files, optionally limited to `categories`
+pub fn scan_common_directories(min_size_mb: u64, categories: Option<Vec<FileCategory>>) -> Vec<LargeFile> {
+    let settings = crate::commands::config::load_settings();
+    let directories = crate::commands::config::common_scan_roots(&settings);
+    let options = crate::commands::config::settings_scan_options(&settings);
+    scan_directories(&directories, min_size_mb, categories, &options, settings.max_scan_concurrency)
+}
+
+/// Turn `mdfind` output (one path per line) into large files, re-checking each
+/// path's size since the Spotlight index can be stale
+fn parse_spotlight_results(output: &str, min_size_bytes: u64) -> Vec<LargeFile> {
+    let overrides = extension_overrides();
+    let mut files: Vec<LargeFile> = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(Path::new)
+        .filter(|path| {
+            !path
+                .file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(true)
+        })
+        .filter_map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            (metadata.is_file() && metadata.len() >= min_size_bytes).then(|| file_entry(path, &metadata, &overrides))
+        })
+        .collect();
+    files.sort_by(|a, b| b.size.cmp(&a.size));
+    files
+}
+
+/// Query Spotlight for files under `scope` at least `min_size_bytes` large.
+/// Returns None if `mdfind` is unavailable or fails.
+fn spotlight_large_files(scope: &Path, min_size_bytes: u64) -> Option<Vec<LargeFile>> {
+    let query = format!("kMDItemFSSize > {}", min_size_bytes.saturating_sub(1));
+    let output = run_command_with_timeout(
+        "mdfind",
+        [OsStr::new("-onlyin"), scope.as_os_str(), OsStr::new(&query)],
+        SPOTLIGHT_TIMEOUT,
+    )
+    .ok()?;
+    if !output.status.success() {
+        return None;
     }
-    Ok(())
+    Some(parse_spotlight_results(&String::from_utf8_lossy(&output.stdout), min_size_bytes))
 }
 
-/// Move file to trash (macOS)
-pub fn move_to_trash(path: &str) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if path.exists() {
-        // Use macOS trash functionality via NSFileManager
-        // For now, we'll just simulate by moving to ~/.Trash
-        if let Some(home) = get_home_dir() {
-            let trash = home.join(".Trash");
-            let file_name = path.file_name().ok_or("Invalid file name")?;
-            let dest = trash.join(file_name);
-            fs::rename(&path, &dest).map_err(|e| e.to_string())?;
+/// Find large files in the home directory using the Spotlight index.
+///
+/// Much faster than walking the tree, but Spotlight skips excluded folders,
+/// most of ~/Library, and volumes with indexing disabled. When Spotlight is
+/// unavailable or returns nothing, this falls back to a full walk of home
+/// that skips the exclude patterns in settings.
+pub fn scan_large_files_spotlight(min_size_mb: u64) -> Vec<LargeFile> {
+    let Some(home) = get_home_dir() else {
+        return Vec::new();
+    };
+    let min_size_bytes = min_size_mb.saturating_mul(1024 * 1024);
+
+    match spotlight_large_files(&home, min_size_bytes) {
+        Some(files) if !files.is_empty() => files,
+        _ => {
+            let settings = crate::commands::config::load_settings();
+            let options = crate::commands::config::settings_scan_options(&settings);
+            scan_large_files(&home.to_string_lossy(), min_size_mb, None, &options)
         }
     }
-    Ok(())
+}
+
+/// Keep files modified at or after `since` (Unix timestamp), newest first
+fn filter_recent(files: Vec<LargeFile>, since: u64) -> Vec<LargeFile> {
+    let mut recent: Vec<LargeFile> = files
+        .into_iter()
+        .filter(|f| f.last_modified.map(|t| t >= since).unwrap_or(false))
+        .collect();
+    recent.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    recent
+}
+
+/// Scan common directories for large files modified within the last `within_hours`
+pub fn scan_recent_large_files(min_size_mb: u64, within_hours: u64) -> Vec<LargeFile> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let since = now.saturating_sub(within_hours.saturating_mul(60 * 60));
+    filter_recent(scan_common_directories(min_size_mb, None), since)
+}
+
+/// Keep files whose name contains `query`, ignoring case. An empty query keeps everything.
+pub fn filter_by_name(files: Vec<LargeFile>, query: &str) -> Vec<LargeFile> {
+    let query = query.to_lowercase();
+    files
+        .into_iter()
+        .filter(|f| f.name.to_lowercase().contains(&query))
+        .collect()
+}
+
+/// Drop files whose category is in `excluded`
+pub fn exclude_categories(files: Vec<LargeFile>, excluded: &[FileCategory]) -> Vec<LargeFile> {
+    files.into_iter().filter(|f| !excluded.contains(&f.category)).collect()
+}
+
+/// Delete a file, through the Trash or permanently per `removal`
+pub fn delete_file(path: &str, removal: Removal) -> Result<(), String> {
+    crate::audit::logged("delete_file", path, || {
+        let path = PathBuf::from(path);
+        if path.exists() && path.is_file() {
+            removal.remove(&path)?;
+        }
+        Ok(())
+    })
+}
+
+/// Most overwrite passes `secure_delete` will do
+pub const MAX_SHRED_PASSES: u8 = 3;
+
+/// Chunk size used when overwriting a file
+const SHRED_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Overwrite `file` in place with random data, then flush it to disk
+fn overwrite_with_random(file: &mut fs::File, len: u64, random: &mut fs::File) -> std::io::Result<()> {
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    let mut buffer = vec![0u8; SHRED_BUFFER_SIZE];
+    let mut remaining = len;
+    file.seek(SeekFrom::Start(0))?;
+    while remaining > 0 {
+        let chunk = remaining.min(SHRED_BUFFER_SIZE as u64) as usize;
+        random.read_exact(&mut buffer[..chunk])?;
+        file.write_all(&buffer[..chunk])?;
+        remaining -= chunk as u64;
+    }
+    file.sync_all()
+}
+
+/// Overwrite a file with random data `passes` times (1 to 3), then delete it.
+///
+/// This is an opt-in alternative to trashing for sensitive files. Any I/O
+/// error stops the shred and is returned, leaving the file in place. On SSDs
+/// and APFS this is best-effort only: wear leveling and copy-on-write can keep
+/// old blocks around, so FileVault is the reliable protection there.
+pub fn secure_delete(path: &str, passes: u8) -> Result<(), String> {
+    if !(1..=MAX_SHRED_PASSES).contains(&passes) {
+        return Err(format!("Passes must be between 1 and {}", MAX_SHRED_PASSES));
+    }
+
+    crate::audit::logged("secure_delete", path, || {
+        let path = PathBuf::from(path);
+        let metadata = fs::symlink_metadata(&path).map_err(|e| e.to_string())?;
+        if !metadata.is_file() {
+            return Err(format!("Not a regular file: {}", path.display()));
+        }
+
+        let mut file = fs::OpenOptions::new().write(true).open(&path).map_err(|e| e.to_string())?;
+        let mut random = fs::File::open("/dev/urandom").map_err(|e| e.to_string())?;
+        for pass in 1..=passes {
+            overwrite_with_random(&mut file, metadata.len(), &mut random)
+                .map_err(|e| format!("Overwrite pass {} failed: {}", pass, e))?;
+        }
+        drop(file);
+
+        fs::remove_file(&path).map_err(|e| e.to_string())
+    })
+}
+
+/// Move a file to the Trash, whatever the safe-mode setting
+pub fn move_to_trash(path: &str) -> Result<(), String> {
+    crate::audit::logged("move_to_trash", path, || {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            Removal::Trash.remove(&path)?;
+        }
+        Ok(())
+    })
 }
 
 #[cfg(test)]
@@ -217,16 +667,333 @@ mod tests {
     use super::*;
     use std::fs::File;
 
+    #[test]
+    fn test_parse_spotlight_results() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let big = temp_dir.path().join("big.mov");
+        let small = temp_dir.path().join("small.txt");
+        let hidden = temp_dir.path().join(".hidden.bin");
+        fs::write(&big, vec![0u8; 4096]).unwrap();
+        fs::write(&small, "x").unwrap();
+        fs::write(&hidden, vec![0u8; 4096]).unwrap();
+
+        let output = format!(
+            "{}\n{}\n{}\n/nonexistent/gone.mkv\n\n",
+            big.display(),
+            small.display(),
+            hidden.display()
+        );
+        let files = parse_spotlight_results(&output, 1024);
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "big.mov");
+        assert_eq!(files[0].category, FileCategory::Video);
+    }
+
+    #[test]
+    fn test_scan_large_files_with_huge_threshold() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("big.mov"), vec![0u8; 4096]).unwrap();
+        let files = scan_large_files(&temp_dir.path().to_string_lossy(), u64::MAX, None, &ScanOptions::default());
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn test_scan_directories_with_categories() {
+        let downloads = tempfile::tempdir().unwrap();
+        let movies = tempfile::tempdir().unwrap();
+        File::create(downloads.path().join("installer.dmg")).unwrap().set_len(1024 * 1024 * 2).unwrap();
+        File::create(downloads.path().join("clip.mov")).unwrap().set_len(1024 * 1024 * 2).unwrap();
+        File::create(movies.path().join("film.mkv")).unwrap().set_len(1024 * 1024 * 3).unwrap();
+        let directories = [downloads.path().to_path_buf(), movies.path().to_path_buf()];
+
+        let options = ScanOptions::default();
+        assert_eq!(scan_directories(&directories, 1, None, &options, 2).len(), 3);
+        let videos = scan_directories(&directories, 1, Some(vec![FileCategory::Video]), &options, 2);
+        assert_eq!(videos.len(), 2);
+        assert!(videos.iter().all(|f| f.category == FileCategory::Video));
+        assert!(videos[0].name == "film.mkv");
+    }
+
+    #[test]
+    fn test_top_n_largest_files() {
+        let home = tempfile::tempdir().unwrap();
+        let nested = home.path().join("Movies");
+        fs::create_dir(&nested).unwrap();
+        for (name, len) in [("a.bin", 10), ("b.bin", 40), ("c.bin", 30)] {
+            File::create(home.path().join(name)).unwrap().set_len(len * 1024).unwrap();
+        }
+        File::create(nested.join("film.mov")).unwrap().set_len(50 * 1024).unwrap();
+
+        let roots = [home.path().to_path_buf(), nested];
+        let top = top_n_largest_files(&roots, 3, &ScanOptions::default());
+        let names: Vec<&str> = top.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["film.mov", "b.bin", "c.bin"]);
+        assert!(top_n_largest_files(&roots, 0, &ScanOptions::default()).is_empty());
+        assert_eq!(top_n_largest_files(&roots, 10, &ScanOptions::default()).len(), 4);
+    }
+
+    #[test]
+    fn test_scan_directories_nested_roots() {
+        let home = tempfile::tempdir().unwrap();
+        let downloads = home.path().join("Downloads");
+        fs::create_dir(&downloads).unwrap();
+        File::create(downloads.join("movie.mp4")).unwrap().set_len(1024 * 1024 * 2).unwrap();
+        File::create(home.path().join("backup.zip")).unwrap().set_len(1024 * 1024 * 2).unwrap();
+
+        let files = scan_directories(&[home.path().to_path_buf(), downloads], 1, None, &ScanOptions::default(), 2);
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn test_scan_large_files_include_hidden() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+        let f = File::create(dir_path.join(".hidden_backup.zip")).unwrap();
+        f.set_len(1024 * 1024 * 2).unwrap();
+
+        assert!(scan_large_files(dir_path.to_str().unwrap(), 1, None, &ScanOptions::default()).is_empty());
+        let options = ScanOptions::default().with_include_hidden(true);
+        assert_eq!(scan_large_files(dir_path.to_str().unwrap(), 1, None, &options).len(), 1);
+    }
+
+    #[test]
+    fn test_filter_recent() {
+        let file = |name: &str, last_modified: Option<u64>| LargeFile {
+            path: format!("/tmp/{}", name),
+            name: name.to_string(),
+            size: 1,
+            category: FileCategory::Other,
+            last_modified,
+            extension: String::new(),
+            on_disk_size: 1,
+            is_sparse: false,
+        };
+        let files = vec![
+            file("old", Some(100)),
+            file("newer", Some(300)),
+            file("new", Some(200)),
+            file("unknown", None),
+        ];
+
+        let names: Vec<String> = filter_recent(files, 200).into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["newer", "new"]);
+    }
+
+    #[test]
+    fn test_scan_tiny_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let pkg = root.join("pkg");
+        let git = root.join(".git").join("refs");
+        fs::create_dir_all(&pkg).unwrap();
+        fs::create_dir_all(&git).unwrap();
+
+        fs::write(root.join("empty.txt"), "").unwrap();
+        fs::write(root.join("small.log"), "tiny").unwrap();
+        fs::write(root.join("big.bin"), vec![0u8; 2048]).unwrap();
+        fs::write(root.join(".hidden"), "").unwrap();
+        fs::write(pkg.join("__init__.py"), "").unwrap();
+        fs::write(pkg.join("a.txt"), "").unwrap();
+        fs::write(git.join("main"), "abc").unwrap();
+
+        let files = scan_tiny_files(root.to_str().unwrap(), 1024, &ScanOptions::default());
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+
+        // Sorted by directory: root entries first, then pkg/
+        assert_eq!(names, vec!["empty.txt", "small.log", "a.txt"]);
+        assert_eq!(files[0].size, 0);
+    }
+
     #[test]
     fn test_get_file_category() {
-        assert_eq!(get_file_category("jpg"), FileCategory::Image);
-        assert_eq!(get_file_category("JPG"), FileCategory::Image);
-        assert_eq!(get_file_category("mp4"), FileCategory::Video);
-        assert_eq!(get_file_category("doc"), FileCategory::Document);
-        assert_eq!(get_file_category("zip"), FileCategory::Archive);
-        assert_eq!(get_file_category("app"), FileCategory::Application);
-        assert_eq!(get_file_category("dmg"), FileCategory::DiskImage);
-        assert_eq!(get_file_category("unknown_ext"), FileCategory::Other);
+        let no_overrides = BTreeMap::new();
+        assert_eq!(get_file_category("jpg", &no_overrides), FileCategory::Image);
+        assert_eq!(get_file_category("JPG", &no_overrides), FileCategory::Image);
+        assert_eq!(get_file_category("mp4", &no_overrides), FileCategory::Video);
+        assert_eq!(get_file_category("doc", &no_overrides), FileCategory::Document);
+        assert_eq!(get_file_category("zip", &no_overrides), FileCategory::Archive);
+        assert_eq!(get_file_category("app", &no_overrides), FileCategory::Application);
+        assert_eq!(get_file_category("dmg", &no_overrides), FileCategory::DiskImage);
+        assert_eq!(get_file_category("unknown_ext", &no_overrides), FileCategory::Other);
+    }
+
+    #[test]
+    fn test_get_file_category_font_ebook_vm() {
+        let no_overrides = BTreeMap::new();
+        assert_eq!(get_file_category("ttf", &no_overrides), FileCategory::Font);
+        assert_eq!(get_file_category("OTF", &no_overrides), FileCategory::Font);
+        assert_eq!(get_file_category("woff2", &no_overrides), FileCategory::Font);
+        assert_eq!(get_file_category("epub", &no_overrides), FileCategory::Ebook);
+        assert_eq!(get_file_category("azw3", &no_overrides), FileCategory::Ebook);
+        assert_eq!(get_file_category("vmdk", &no_overrides), FileCategory::VirtualMachine);
+        assert_eq!(get_file_category("qcow2", &no_overrides), FileCategory::VirtualMachine);
+        assert_eq!(get_file_category("vdi", &no_overrides), FileCategory::VirtualMachine);
+    }
+
+    #[test]
+    fn test_get_file_category_user_overrides() {
+        let overrides = BTreeMap::from([
+            ("blend".to_string(), FileCategory::Project),
+            ("psd".to_string(), FileCategory::Document),
+        ]);
+        assert_eq!(get_file_category("blend", &overrides), FileCategory::Project);
+        assert_eq!(get_file_category("PSD", &overrides), FileCategory::Document);
+        assert_eq!(get_file_category("jpg", &overrides), FileCategory::Image);
+        assert_eq!(FileCategory::from_name("Project"), Some(FileCategory::Project));
+        assert_eq!(FileCategory::from_name("project"), None);
+    }
+
+    #[test]
+    fn test_is_sparse() {
+        assert!(is_sparse(100 * 1024 * 1024, 0));
+        assert!(!is_sparse(100 * 1024 * 1024, 100 * 1024 * 1024));
+        // Block rounding on a small file is not sparseness
+        assert!(!is_sparse(100, 4096));
+        assert!(!is_sparse(1000, 0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_large_files_reports_sparse_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+
+        // Docker.raw-style file: 64MB long, nothing written
+        let f = File::create(dir_path.join("Docker.raw")).unwrap();
+        f.set_len(64 * 1024 * 1024).unwrap();
+        fs::write(dir_path.join("dense.bin"), vec![1u8; 2 * 1024 * 1024]).unwrap();
+
+        let files = scan_large_files(dir_path.to_str().unwrap(), 1, None, &ScanOptions::default());
+        let sparse = files.iter().find(|f| f.name == "Docker.raw").unwrap();
+        assert!(sparse.is_sparse);
+        assert!(sparse.on_disk_size < sparse.size);
+
+        let dense = files.iter().find(|f| f.name == "dense.bin").unwrap();
+        assert!(!dense.is_sparse);
+    }
+
+    #[test]
+    fn test_scan_large_files_virtual_machine() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+
+        let vm_path = dir_path.join("ubuntu.qcow2");
+        let f = File::create(&vm_path).unwrap();
+        f.set_len(1024 * 1024 * 3).unwrap(); // 3MB
+
+        let files = scan_large_files(
+            dir_path.to_str().unwrap(),
+            1,
+            Some(vec![FileCategory::VirtualMachine]),
+            &ScanOptions::default(),
+        );
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].category, FileCategory::VirtualMachine);
+    }
+
+    #[test]
+    fn test_scan_large_files_streaming() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+
+        for i in 1..=5u64 {
+            let f = File::create(dir_path.join(format!("file{}.bin", i))).unwrap();
+            f.set_len(1024 * 1024 * i).unwrap();
+        }
+
+        let mut batches: Vec<Vec<LargeFile>> = Vec::new();
+        let emitted = scan_large_files_streaming(dir_path.to_str().unwrap(), 1, None, &ScanOptions::default(), 2, |batch| {
+            batches.push(batch)
+        });
+
+        assert_eq!(emitted, 5);
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches.iter().map(|b| b.len()).sum::<usize>(), 5);
+        for batch in &batches {
+            assert!(batch.windows(2).all(|w| w[0].size >= w[1].size));
+        }
+    }
+
+    #[test]
+    fn test_scan_large_files_streaming_empty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut calls = 0;
+        let emitted = scan_large_files_streaming(temp_dir.path().to_str().unwrap(), 1, None, &ScanOptions::default(), 10, |_| {
+            calls += 1
+        });
+        assert_eq!(emitted, 0);
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn test_scan_large_files_with_report() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+
+        for (name, mb) in [("a.mp4", 2), ("b.mov", 3), ("c.zip", 4)] {
+            let f = File::create(dir_path.join(name)).unwrap();
+            f.set_len(1024 * 1024 * mb).unwrap();
+        }
+
+        let report = scan_large_files_with_report(dir_path.to_str().unwrap(), 1, None, &ScanOptions::default());
+
+        assert_eq!(report.files.len(), 3);
+        assert_eq!(report.totals_by_category.len(), 2);
+        assert_eq!(
+            report.totals_by_category["Video"],
+            CategoryTotal { count: 2, total_bytes: 1024 * 1024 * 5 }
+        );
+        assert_eq!(
+            report.totals_by_category["Archive"],
+            CategoryTotal { count: 1, total_bytes: 1024 * 1024 * 4 }
+        );
+    }
+
+    #[test]
+    fn test_exclude_categories() {
+        let file = |name: &str, category: FileCategory| LargeFile {
+            path: format!("/tmp/{}", name),
+            name: name.to_string(),
+            size: 1,
+            category,
+            last_modified: None,
+            extension: String::new(),
+            on_disk_size: 1,
+            is_sparse: false,
+        };
+        let files = vec![
+            file("Xcode.app", FileCategory::Application),
+            file("movie.mov", FileCategory::Video),
+            file("Installer.app", FileCategory::Application),
+        ];
+
+        let names: Vec<String> = exclude_categories(files.clone(), &[FileCategory::Application])
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(names, vec!["movie.mov"]);
+        assert_eq!(exclude_categories(files, &[]).len(), 3);
+    }
+
+    #[test]
+    fn test_filter_by_name() {
+        let file = |name: &str| LargeFile {
+            path: format!("/tmp/{}", name),
+            name: name.to_string(),
+            size: 1,
+            category: FileCategory::Other,
+            last_modified: None,
+            extension: String::new(),
+            on_disk_size: 1,
+            is_sparse: false,
+        };
+        let files = vec![file("Holiday.MOV"), file("backup.zip"), file("holiday-notes.txt")];
+
+        let names: Vec<String> = filter_by_name(files.clone(), "HOLIDAY").into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["Holiday.MOV", "holiday-notes.txt"]);
+        assert_eq!(filter_by_name(files, "").len(), 3);
     }
 
     #[test]
@@ -244,11 +1011,54 @@ mod tests {
         let f = File::create(&small_file_path).unwrap();
         f.set_len(1024).unwrap(); // 1KB
 
-        let files = scan_large_files(dir_path.to_str().unwrap(), 1, None); // Min 1MB
+        let files = scan_large_files(dir_path.to_str().unwrap(), 1, None, &ScanOptions::default()); // Min 1MB
 
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].path, large_file_path.to_string_lossy());
         assert_eq!(files[0].category, FileCategory::Video);
     }
-}
 
+    #[test]
+    fn test_scan_large_files_max_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+        let nested = dir_path.join("nested");
+        fs::create_dir(&nested).unwrap();
+
+        let f = File::create(dir_path.join("top.mp4")).unwrap();
+        f.set_len(1024 * 1024 * 2).unwrap();
+        let f = File::create(nested.join("deep.mp4")).unwrap();
+        f.set_len(1024 * 1024 * 2).unwrap();
+
+        let shallow = scan_large_files(dir_path.to_str().unwrap(), 1, None, &ScanOptions::with_max_depth(Some(1)));
+        assert_eq!(shallow.len(), 1);
+        assert_eq!(shallow[0].name, "top.mp4");
+
+        let deep = scan_large_files(dir_path.to_str().unwrap(), 1, None, &ScanOptions::with_max_depth(Some(2)));
+        assert_eq!(deep.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secure_delete() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("secret.txt");
+        fs::write(&file_path, vec![7u8; SHRED_BUFFER_SIZE + 100]).unwrap();
+
+        secure_delete(file_path.to_str().unwrap(), 2).unwrap();
+        assert!(!file_path.exists());
+    }
+
+    #[test]
+    fn test_secure_delete_rejects_bad_input() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("secret.txt");
+        fs::write(&file_path, "data").unwrap();
+        let path = file_path.to_str().unwrap();
+
+        assert!(secure_delete(path, 0).is_err());
+        assert!(secure_delete(path, MAX_SHRED_PASSES + 1).is_err());
+        assert!(secure_delete(temp_dir.path().to_str().unwrap(), 1).is_err());
+        assert!(file_path.exists());
+    }
+}
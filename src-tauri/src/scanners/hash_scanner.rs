@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
-use sha2::{Sha256, Digest};
+use sha2::{Digest, Sha256, Sha512};
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::scan_options::{scan_roots_concurrently, ScanOptions};
+use crate::pagination::Sortable;
+use crate::removal::Removal;
 
 /// Represents a group of duplicate files
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,11 +19,49 @@ pub struct DuplicateGroup {
     pub total_wasted: u64, // (count - 1) * file_size
 }
 
+impl Sortable for DuplicateGroup {
+    /// Groups are ranked by the space their extra copies waste
+    fn sort_size(&self) -> u64 {
+        self.total_wasted
+    }
+    fn sort_name(&self) -> &str {
+        self.files.first().map(|f| f.name.as_str()).unwrap_or("")
+    }
+}
+
 /// Represents a single file in a duplicate group
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DuplicateFile {
     pub path: String,
     pub name: String,
+    /// A symlink to the content rather than a copy. It takes no extra space,
+    /// so it is never kept in place of a real copy or suggested for deletion.
+    #[serde(default)]
+    pub is_symlink: bool,
+}
+
+/// How to pick the copy to keep in a duplicate group
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum KeepStrategy {
+    OldestModified,
+    NewestModified,
+    ShortestPath,
+    OutsideDownloads, // Prefer a copy outside ~/Downloads and ~/Desktop
+}
+
+/// Which copy of a duplicate group to keep and which to delete
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeepSuggestion {
+    pub keep_index: usize,
+    pub delete: Vec<String>,
+}
+
+/// Outcome of keeping one copy of a duplicate group and trashing the rest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroupResolution {
+    pub kept: String,
+    pub trashed: Vec<String>,
+    pub bytes_freed: u64,
 }
 
 /// Scan progress information
@@ -31,67 +73,156 @@ pub struct ScanProgress {
     pub bytes_wasted: u64,
 }
 
-const PARTIAL_HASH_SIZE: usize = 8192; // 8KB for partial hash
+const PARTIAL_HASH_SIZE: usize = 8192; // 8KB per sampled chunk
 
-/// Calculate SHA-256 hash of a file
-fn calculate_full_hash(path: &PathBuf) -> Option<String> {
-    let file = File::open(path).ok()?;
-    let mut reader = BufReader::new(file);
-    let mut hasher = Sha256::new();
+/// Whether `path` itself is a symlink, without following it
+fn is_symlink(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink())
+}
+
+/// Whether `a` and `b` are the same file on disk, through symlinks, hard links
+/// or differently spelled paths
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Space wasted by the real copies in `files`; symlinks take none
+fn wasted_space(file_size: u64, files: &[DuplicateFile]) -> u64 {
+    let real_copies = files.iter().filter(|f| !f.is_symlink).count() as u64;
+    file_size * real_copies.saturating_sub(1)
+}
+
+/// Hash functions available for file digests
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+/// Hex digest of everything `reader` yields
+fn digest_hex<D: Digest, R: Read>(mut reader: R) -> std::io::Result<String> {
+    let mut hasher = D::new();
     let mut buffer = [0u8; 65536]; // 64KB buffer
     
     loop {
-        let bytes_read = reader.read(&mut buffer).ok()?;
+        let bytes_read = reader.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
         }
         hasher.update(&buffer[..bytes_read]);
     }
     
-    Some(hex::encode(hasher.finalize()))
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hex digest of a file's contents using `algorithm`
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    let file = File::open(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let reader = BufReader::new(file);
+    let digest = match algorithm {
+        HashAlgorithm::Sha256 => digest_hex::<Sha256, _>(reader),
+        HashAlgorithm::Sha512 => digest_hex::<Sha512, _>(reader),
+    };
+    digest.map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Calculate SHA-256 hash of a file
+fn calculate_full_hash(path: &Path) -> Option<String> {
+    hash_file(path, HashAlgorithm::Sha256).ok()
 }
 
-/// Calculate partial hash (first N bytes) for quick comparison
-fn calculate_partial_hash(path: &PathBuf) -> Option<String> {
-    let file = File::open(path).ok()?;
-    let mut reader = BufReader::new(file);
+/// Offsets of the head, middle and tail chunks sampled from a file of `len`
+/// bytes, or `None` when the file is small enough to hash whole
+fn partial_hash_offsets(len: u64) -> Option<[u64; 3]> {
+    let chunk = PARTIAL_HASH_SIZE as u64;
+    (len > chunk * 3).then(|| [0, len / 2 - chunk / 2, len - chunk])
+}
+
+/// Calculate partial hash for quick comparison: one digest over chunks from
+/// the head, middle and tail, so files sharing a common header (archives,
+/// model checkpoints) still tell apart without a full hash
+fn calculate_partial_hash(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let Some(offsets) = partial_hash_offsets(len) else {
+        return digest_hex::<Sha256, _>(BufReader::new(file)).ok();
+    };
+
     let mut hasher = Sha256::new();
     let mut buffer = [0u8; PARTIAL_HASH_SIZE];
-    
-    let bytes_read = reader.read(&mut buffer).ok()?;
-    if bytes_read > 0 {
-        hasher.update(&buffer[..bytes_read]);
-        Some(hex::encode(hasher.finalize()))
-    } else {
-        None
+    for offset in offsets {
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut buffer).ok()?;
+        hasher.update(buffer);
     }
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// How much work each phase of a duplicate scan did, to explain its cost
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScanStats {
+    pub files_considered: u64, // Non-hidden files seen by the walk
+    pub size_matched: u64,     // Files sharing their size with another file
+    pub partial_hashed: u64,
+    pub full_hashed: u64,
 }
 
-/// Scan for duplicate files in a directory
-pub fn scan_duplicates(directory: &str, min_size_mb: u64) -> Vec<DuplicateGroup> {
-    let min_size_bytes = min_size_mb * 1024 * 1024;
+/// Byte threshold for a minimum size given in MB. Never below one byte, so
+/// empty files are only compared when a 0 byte threshold is asked for explicitly.
+pub fn min_size_from_mb(min_size_mb: u64) -> u64 {
+    min_size_mb.saturating_mul(1024 * 1024).max(1)
+}
+
+/// Scan for duplicate files of at least `min_size_bytes` in a directory
+pub fn scan_duplicates(directory: &str, min_size_bytes: u64, options: &ScanOptions) -> Vec<DuplicateGroup> {
+    scan_duplicates_with_stats(directory, min_size_bytes, options).0
+}
+
+/// Scan for duplicate files of at least `min_size_bytes` in a directory,
+/// counting the work done in each phase
+pub fn scan_duplicates_with_stats(directory: &str, min_size_bytes: u64, options: &ScanOptions) -> (Vec<DuplicateGroup>, ScanStats) {
     let path = PathBuf::from(directory);
+    let mut stats = ScanStats::default();
     
     if !path.exists() {
-        return Vec::new();
+        return (Vec::new(), stats);
     }
     
     // Step 1: Group files by size
     let mut size_groups: HashMap<u64, Vec<PathBuf>> = HashMap::new();
     
-    for entry in WalkDir::new(&path)
-        .into_iter()
-        .filter_map(|e| e.ok())
+    for entry in options
+        .walk(&path)
         .filter(|e| e.file_type().is_file())
     {
         let file_path = entry.path().to_path_buf();
         
-        // Skip hidden files
-        if file_path.file_name().map(|s| s.to_string_lossy().starts_with('.')).unwrap_or(false) {
+        // Skip hidden files unless asked to include them
+        if options.skips_hidden(&file_path) {
             continue;
         }
         
         if let Ok(metadata) = std::fs::metadata(&file_path) {
+            stats.files_considered += 1;
             let size = metadata.len();
             if size >= min_size_bytes {
                 size_groups.entry(size).or_default().push(file_path);
@@ -106,8 +237,13 @@ pub fn scan_duplicates(directory: &str, min_size_mb: u64) -> Vec<DuplicateGroup>
         if files.len() < 2 {
             continue; // Need at least 2 files to have duplicates
         }
+        stats.size_matched += files.len() as u64;
         
         for file_path in files {
+            if !options.checkpoint() {
+                return (Vec::new(), stats);
+            }
+            stats.partial_hashed += 1;
             if let Some(partial_hash) = calculate_partial_hash(file_path) {
                 partial_hash_groups
                     .entry((*size, partial_hash))
@@ -127,6 +263,10 @@ pub fn scan_duplicates(directory: &str, min_size_mb: u64) -> Vec<DuplicateGroup>
         }
         
         for file_path in files {
+            if !options.checkpoint() {
+                return (Vec::new(), stats);
+            }
+            stats.full_hashed += 1;
             if let Some(full_hash) = calculate_full_hash(file_path) {
                 full_hash_groups
                     .entry(full_hash.clone())
@@ -153,71 +293,315 @@ pub fn scan_duplicates(directory: &str, min_size_mb: u64) -> Vec<DuplicateGroup>
                 name: p.file_name()
                     .map(|n| n.to_string_lossy().to_string())
                     .unwrap_or_default(),
+                is_symlink: is_symlink(p),
             })
             .collect();
         
+        // A file and links to it aren't duplicates; at least two real copies are needed
+        if duplicate_files.iter().filter(|f| !f.is_symlink).count() < 2 {
+            continue;
+        }
+        
         duplicates.push(DuplicateGroup {
             hash: hash.clone(),
+            total_wasted: wasted_space(file_size, &duplicate_files),
             files: duplicate_files,
             file_size,
-            total_wasted: file_size * (files.len() as u64 - 1),
         });
     }
     
     // Sort by wasted space descending
     duplicates.sort_by(|a, b| b.total_wasted.cmp(&a.total_wasted));
-    duplicates
+    (duplicates, stats)
 }
 
-/// Scan common directories for duplicates
-pub fn scan_common_directories_for_duplicates(min_size_mb: u64) -> Vec<DuplicateGroup> {
-    let mut all_duplicates = Vec::new();
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Pick the index to keep, treating anything under `transient_dirs` as a worse
+/// home. Symlinks are never kept in place of a real copy.
+fn choose_keep_index(files: &[DuplicateFile], strategy: KeepStrategy, transient_dirs: &[PathBuf]) -> usize {
+    let mut indices = (0..files.len()).filter(|&i| !files[i].is_symlink);
+    let chosen = match strategy {
+        // Files whose mtime can't be read are never preferred
+        KeepStrategy::OldestModified => indices
+            .min_by_key(|&i| modified_time(&files[i].path).unwrap_or(SystemTime::now())),
+        KeepStrategy::NewestModified => indices
+            .max_by_key(|&i| (modified_time(&files[i].path).unwrap_or(SystemTime::UNIX_EPOCH), std::cmp::Reverse(i))),
+        KeepStrategy::ShortestPath => indices.min_by_key(|&i| files[i].path.chars().count()),
+        KeepStrategy::OutsideDownloads => indices.find(|&i| {
+            let path = Path::new(&files[i].path);
+            !transient_dirs.iter().any(|dir| path.starts_with(dir))
+        }),
+    };
+    chosen.or_else(|| files.iter().position(|f| !f.is_symlink)).unwrap_or(0)
+}
+
+/// Suggest which copy to keep in a duplicate group; the other real copies are
+/// listed for deletion, while symlinks are left alone
+pub fn suggest_keep(group: &DuplicateGroup, strategy: KeepStrategy) -> KeepSuggestion {
+    let transient_dirs: Vec<PathBuf> = dirs::home_dir()
+        .map(|home| vec![home.join("Downloads"), home.join("Desktop")])
+        .unwrap_or_default();
+    let keep_index = choose_keep_index(&group.files, strategy, &transient_dirs);
+    let delete = group
+        .files
+        .iter()
+        .enumerate()
+        .filter(|(i, f)| *i != keep_index && !f.is_symlink)
+        .map(|(_, f)| f.path.clone())
+        .collect();
+    KeepSuggestion { keep_index, delete }
+}
+
+/// Merge groups with the same hash and drop copies that resolve to the same
+/// canonical path, so overlapping or symlinked roots don't count a file twice.
+/// Groups left with a single file are dropped and wasted space is recomputed.
+pub fn dedupe_groups_by_canonical_path(groups: Vec<DuplicateGroup>) -> Vec<DuplicateGroup> {
+    let mut merged: Vec<DuplicateGroup> = Vec::new();
+    let mut index_by_hash: HashMap<String, usize> = HashMap::new();
     
-    if let Some(home) = dirs::home_dir() {
-        let directories = vec![
-            home.join("Downloads"),
-            home.join("Desktop"),
-            home.join("Documents"),
-            home.join("Pictures"),
-        ];
-        
-        // We need to scan all directories together for cross-directory duplicates
-        // For now, scan them separately
-        for dir in directories {
-            if dir.exists() {
-                all_duplicates.extend(scan_duplicates(&dir.to_string_lossy(), min_size_mb));
+    for group in groups {
+        match index_by_hash.get(&group.hash) {
+            Some(&i) => merged[i].files.extend(group.files),
+            None => {
+                index_by_hash.insert(group.hash.clone(), merged.len());
+                merged.push(group);
             }
         }
     }
     
+    merged
+        .into_iter()
+        .filter_map(|mut group| {
+            let mut seen = std::collections::HashSet::new();
+            // Real copies first, so a symlink never displaces the file it points to
+            group.files.sort_by_key(|f| f.is_symlink);
+            group
+                .files
+                .retain(|f| seen.insert(std::fs::canonicalize(&f.path).unwrap_or_else(|_| PathBuf::from(&f.path))));
+            if group.files.iter().filter(|f| !f.is_symlink).count() < 2 {
+                return None;
+            }
+            group.total_wasted = wasted_space(group.file_size, &group.files);
+            Some(group)
+        })
+        .collect()
+}
+
+/// Scan each existing directory for duplicates, with up to `max_concurrency`
+/// directories at once. Roots may overlap.
+fn scan_directories_for_duplicates(
+    directories: &[PathBuf],
+    min_size_bytes: u64,
+    options: &ScanOptions,
+    max_concurrency: usize,
+) -> Vec<DuplicateGroup> {
+    // We need to scan all directories together for cross-directory duplicates
+    // For now, scan them separately
+    let per_dir = scan_roots_concurrently(directories, max_concurrency, |dir| {
+        if !dir.exists() {
+            return Vec::new();
+        }
+        scan_duplicates(&dir.to_string_lossy(), min_size_bytes, options)
+    });
+    let all_duplicates = per_dir.into_iter().flatten().collect();
+    
+    let mut all_duplicates = dedupe_groups_by_canonical_path(all_duplicates);
+    
     // Sort by wasted space
     all_duplicates.sort_by(|a, b| b.total_wasted.cmp(&a.total_wasted));
     all_duplicates
 }
 
-/// Delete a duplicate file
-pub fn delete_duplicate(path: &str) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if path.exists() && path.is_file() {
-        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+/// Scan the duplicate scan roots from settings for duplicates
+pub fn scan_common_directories_for_duplicates(min_size_mb: u64) -> Vec<DuplicateGroup> {
+    let settings = crate::commands::config::load_settings();
+    let directories = crate::commands::config::duplicate_scan_roots(&settings);
+    let options = crate::commands::config::settings_scan_options(&settings);
+    scan_directories_for_duplicates(&directories, min_size_from_mb(min_size_mb), &options, settings.max_scan_concurrency)
+}
+
+/// Drop groups wasting less than `min_wasted_bytes`, then keep at most
+/// `max_groups` of the rest. Expects groups sorted by wasted space, largest
+/// first, as `scan_duplicates` returns them, so the worst offenders are kept.
+pub fn cap_groups(mut groups: Vec<DuplicateGroup>, min_wasted_bytes: u64, max_groups: Option<usize>) -> Vec<DuplicateGroup> {
+    groups.retain(|g| g.total_wasted >= min_wasted_bytes);
+    if let Some(max) = max_groups {
+        groups.truncate(max);
     }
-    Ok(())
+    groups
 }
 
-/// Move a duplicate file to trash
-pub fn move_duplicate_to_trash(path: &str) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if path.exists() {
-        if let Some(home) = dirs::home_dir() {
-            let trash = home.join(".Trash");
-            let file_name = path.file_name().ok_or("Invalid file name")?;
-            let dest = trash.join(file_name);
-            std::fs::rename(&path, &dest).map_err(|e| e.to_string())?;
+/// Keep groups where any copy's file name contains `query`, ignoring case
+pub fn filter_groups_by_name(groups: Vec<DuplicateGroup>, query: &str) -> Vec<DuplicateGroup> {
+    let query = query.to_lowercase();
+    groups
+        .into_iter()
+        .filter(|g| g.files.iter().any(|f| f.name.to_lowercase().contains(&query)))
+        .collect()
+}
+
+/// Delete a duplicate file, through the Trash or permanently per `removal`
+pub fn delete_duplicate(path: &str, removal: Removal) -> Result<(), String> {
+    crate::audit::logged("delete_duplicate", path, || {
+        let path = PathBuf::from(path);
+        if path.exists() && path.is_file() {
+            removal.remove(&path)?;
         }
+        Ok(())
+    })
+}
+
+/// Check that `path` and `keep_path` both still exist and hash to `expected_hash`
+pub fn verify_duplicate(path: &str, expected_hash: &str, keep_path: &str) -> Result<(), String> {
+    if path == keep_path || is_same_file(Path::new(path), Path::new(keep_path)) {
+        return Err("Cannot delete the copy being kept".to_string());
+    }
+    if is_symlink(Path::new(keep_path)) {
+        return Err(format!("Keep the real file rather than the symlink {}", keep_path));
+    }
+
+    for candidate in [path, keep_path] {
+        verify_hash(candidate, expected_hash)?;
     }
     Ok(())
 }
 
+/// Check that `path` still exists and hashes to `expected_hash`
+fn verify_hash(path: &str, expected_hash: &str) -> Result<(), String> {
+    let file_path = PathBuf::from(path);
+    if !file_path.is_file() {
+        return Err(format!("File no longer exists: {}", path));
+    }
+    match calculate_full_hash(&file_path) {
+        Some(hash) if hash.eq_ignore_ascii_case(expected_hash) => Ok(()),
+        Some(_) => Err(format!("File has changed since the scan: {}", path)),
+        None => Err(format!("Failed to read file: {}", path)),
+    }
+}
+
+/// Keep the copy chosen by `strategy` and pass every other copy to `trash`.
+/// The group comes from the client, so symlinks are re-detected on disk and
+/// left alone, and a copy that is the kept file under another path is refused.
+/// All copies are re-hashed first; if any no longer matches, nothing is trashed.
+fn resolve_group_with<F>(group: &DuplicateGroup, strategy: KeepStrategy, trash: F) -> Result<GroupResolution, String>
+where
+    F: Fn(&str) -> Result<(), String>,
+{
+    let mut group = group.clone();
+    for file in &mut group.files {
+        file.is_symlink = is_symlink(Path::new(&file.path));
+    }
+
+    let suggestion = suggest_keep(&group, strategy);
+    let kept = group
+        .files
+        .get(suggestion.keep_index)
+        .map(|f| f.path.clone())
+        .ok_or("Duplicate group is empty")?;
+    if is_symlink(Path::new(&kept)) {
+        return Err(format!("Keep the real file rather than the symlink {}", kept));
+    }
+
+    let mut delete: Vec<String> = Vec::with_capacity(suggestion.delete.len());
+    for path in suggestion.delete {
+        if path == kept || is_same_file(Path::new(&path), Path::new(&kept)) {
+            return Err(format!("Cannot delete {}: it is the copy being kept", path));
+        }
+        if !delete.iter().any(|d| *d == path || is_same_file(Path::new(d), Path::new(&path))) {
+            delete.push(path);
+        }
+    }
+
+    for path in std::iter::once(&kept).chain(&delete) {
+        verify_hash(path, &group.hash)?;
+    }
+
+    let mut trashed = Vec::with_capacity(delete.len());
+    for path in delete {
+        if is_symlink(Path::new(&path)) {
+            return Err(format!("Stopped after trashing {} copies: {} became a symlink", trashed.len(), path));
+        }
+        trash(&path).map_err(|e| format!("Stopped after trashing {} copies: {}", trashed.len(), e))?;
+        trashed.push(path);
+    }
+
+    Ok(GroupResolution {
+        kept,
+        bytes_freed: group.file_size * trashed.len() as u64,
+        trashed,
+    })
+}
+
+/// Keep one copy of a duplicate group chosen by `strategy` and move the rest to trash
+pub fn resolve_duplicate_group(group: &DuplicateGroup, strategy: KeepStrategy) -> Result<GroupResolution, String> {
+    resolve_group_with(group, strategy, move_duplicate_to_trash)
+}
+
+/// Re-verify a duplicate against the kept copy, then move it to trash
+pub fn delete_duplicate_verified(path: &str, expected_hash: &str, keep_path: &str) -> Result<(), String> {
+    verify_duplicate(path, expected_hash, keep_path)?;
+    move_duplicate_to_trash(path)
+}
+
+/// Replace `duplicate_path` with a hard link to `keep_path`, returning the bytes reclaimed.
+///
+/// Both files must have identical content and live on the same volume. The duplicate
+/// is renamed aside first and restored if the link can't be created.
+#[cfg(unix)]
+pub fn dedupe_via_hardlink(keep_path: &str, duplicate_path: &str) -> Result<u64, String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let keep = PathBuf::from(keep_path);
+    let duplicate = PathBuf::from(duplicate_path);
+    let keep_meta = std::fs::metadata(&keep).map_err(|e| format!("{}: {}", keep_path, e))?;
+    let dup_meta = std::fs::metadata(&duplicate).map_err(|e| format!("{}: {}", duplicate_path, e))?;
+
+    if !keep_meta.is_file() || !dup_meta.is_file() {
+        return Err("Both paths must be regular files".to_string());
+    }
+    if keep_meta.dev() != dup_meta.dev() {
+        return Err("Files are on different volumes; hard links are not possible".to_string());
+    }
+    if keep_meta.ino() == dup_meta.ino() {
+        return Err("Files are already hard linked".to_string());
+    }
+    if keep_meta.len() != dup_meta.len() || calculate_full_hash(&keep) != calculate_full_hash(&duplicate) {
+        return Err("Files are no longer identical".to_string());
+    }
+
+    let file_name = duplicate.file_name().ok_or("Invalid file name")?.to_string_lossy();
+    let backup = duplicate.with_file_name(format!(".{}.dedupe-backup", file_name));
+    std::fs::rename(&duplicate, &backup).map_err(|e| e.to_string())?;
+
+    if let Err(e) = std::fs::hard_link(&keep, &duplicate) {
+        std::fs::rename(&backup, &duplicate)
+            .map_err(|restore| format!("Failed to link ({}) and failed to restore original ({})", e, restore))?;
+        return Err(format!("Failed to create hard link: {}", e));
+    }
+
+    std::fs::remove_file(&backup).map_err(|e| e.to_string())?;
+    Ok(dup_meta.len())
+}
+
+#[cfg(not(unix))]
+pub fn dedupe_via_hardlink(_keep_path: &str, _duplicate_path: &str) -> Result<u64, String> {
+    Err("Hard link deduplication is not supported on this platform".to_string())
+}
+
+/// Move a duplicate file to the Trash, whatever the safe-mode setting
+pub fn move_duplicate_to_trash(path: &str) -> Result<(), String> {
+    crate::audit::logged("move_duplicate_to_trash", path, || {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            Removal::Trash.remove(&path)?;
+        }
+        Ok(())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +632,49 @@ mod tests {
         assert_eq!(hash, expected);
     }
 
+    #[test]
+    fn test_partial_hash_samples_middle_and_tail() {
+        assert_eq!(partial_hash_offsets(100), None);
+        assert_eq!(partial_hash_offsets(3 * 8192), None);
+        assert_eq!(partial_hash_offsets(64 * 1024), Some([0, 28672, 57344]));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = vec![7u8; 64 * 1024];
+        let mut middle = base.clone();
+        middle[32 * 1024] = 0;
+        let mut tail = base.clone();
+        tail[64 * 1024 - 1] = 0;
+        let mut unsampled = base.clone();
+        unsampled[16 * 1024] = 0;
+        let hash = |name: &str, data: &[u8]| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, data).unwrap();
+            calculate_partial_hash(&path).unwrap()
+        };
+
+        let base_hash = hash("base", &base);
+        assert_ne!(hash("middle", &middle), base_hash);
+        assert_ne!(hash("tail", &tail), base_hash);
+        assert_eq!(hash("unsampled", &unsampled), base_hash);
+    }
+
+    #[test]
+    fn test_shared_header_files_skip_full_hash() {
+        // Same-size files with an identical preamble used to all collide on
+        // the head-only partial hash and each need a full hash
+        let temp_dir = tempfile::tempdir().unwrap();
+        for i in 0..8u8 {
+            let mut data = vec![0u8; 256 * 1024];
+            data[128 * 1024..].fill(i + 1);
+            std::fs::write(temp_dir.path().join(format!("checkpoint{}.bin", i)), data).unwrap();
+        }
+
+        let (groups, stats) = scan_duplicates_with_stats(temp_dir.path().to_str().unwrap(), 0, &ScanOptions::default());
+        assert!(groups.is_empty());
+        assert_eq!(stats.partial_hashed, 8);
+        assert_eq!(stats.full_hashed, 0);
+    }
+
     #[test]
     fn test_scan_duplicates() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -269,7 +696,7 @@ mod tests {
         write!(f3, "unique content").unwrap();
 
         // Min size 0 to catch these small files
-        let duplicates = scan_duplicates(dir_path.to_str().unwrap(), 0);
+        let duplicates = scan_duplicates(dir_path.to_str().unwrap(), 0, &ScanOptions::default());
 
         assert_eq!(duplicates.len(), 1);
         assert_eq!(duplicates[0].files.len(), 2);
@@ -284,4 +711,380 @@ mod tests {
         assert!(names.contains(&"file1.txt".to_string()));
         assert!(names.contains(&"file2.txt".to_string()));
     }
+
+    #[test]
+    fn test_verify_duplicate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let keep = temp_dir.path().join("keep.txt");
+        let dup = temp_dir.path().join("dup.txt");
+        std::fs::write(&keep, "content").unwrap();
+        std::fs::write(&dup, "content").unwrap();
+        let hash = calculate_full_hash(&keep).unwrap();
+        let (keep, dup) = (keep.to_str().unwrap(), dup.to_str().unwrap());
+
+        assert!(verify_duplicate(dup, &hash, keep).is_ok());
+        assert!(verify_duplicate(dup, &hash, dup).is_err());
+
+        // Modified after the scan
+        std::fs::write(dup, "changed").unwrap();
+        assert!(verify_duplicate(dup, &hash, keep).is_err());
+
+        // Kept copy removed after the scan
+        std::fs::write(dup, "content").unwrap();
+        std::fs::remove_file(keep).unwrap();
+        assert!(verify_duplicate(dup, &hash, keep).is_err());
+    }
+
+    fn duplicate_file(path: &Path) -> DuplicateFile {
+        DuplicateFile {
+            path: path.to_string_lossy().to_string(),
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            is_symlink: false,
+        }
+    }
+
+    #[test]
+    fn test_choose_keep_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let downloads = temp_dir.path().join("Downloads");
+        let archive = temp_dir.path().join("archive").join("2024");
+        std::fs::create_dir_all(&downloads).unwrap();
+        std::fs::create_dir_all(&archive).unwrap();
+
+        let old = archive.join("photo.jpg");
+        let new = downloads.join("photo.jpg");
+        std::fs::write(&old, "x").unwrap();
+        std::fs::write(&new, "x").unwrap();
+        let old_file = File::options().write(true).open(&old).unwrap();
+        old_file.set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000)).unwrap();
+
+        let files = vec![duplicate_file(&new), duplicate_file(&old)];
+        let transient = vec![downloads.clone()];
+
+        assert_eq!(choose_keep_index(&files, KeepStrategy::OldestModified, &transient), 1);
+        assert_eq!(choose_keep_index(&files, KeepStrategy::NewestModified, &transient), 0);
+        assert_eq!(choose_keep_index(&files, KeepStrategy::ShortestPath, &transient), 0);
+        assert_eq!(choose_keep_index(&files, KeepStrategy::OutsideDownloads, &transient), 1);
+    }
+
+    #[test]
+    fn test_filter_groups_by_name() {
+        let group = |hash: &str, paths: &[&str]| DuplicateGroup {
+            hash: hash.to_string(),
+            files: paths.iter().map(|p| duplicate_file(Path::new(p))).collect(),
+            file_size: 1,
+            total_wasted: 1,
+        };
+        let groups = vec![
+            group("a", &["/x/Invoice.pdf", "/y/Invoice copy.pdf"]),
+            group("b", &["/x/photo.jpg", "/y/photo.jpg"]),
+        ];
+
+        let filtered = filter_groups_by_name(groups, "invoice");
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].hash, "a");
+    }
+
+    #[test]
+    fn test_suggest_keep_lists_others_for_deletion() {
+        let group = DuplicateGroup {
+            hash: "abc".to_string(),
+            files: vec![
+                duplicate_file(Path::new("/a/long/path/file.txt")),
+                duplicate_file(Path::new("/b/file.txt")),
+                duplicate_file(Path::new("/c/d/file.txt")),
+            ],
+            file_size: 1,
+            total_wasted: 2,
+        };
+
+        let suggestion = suggest_keep(&group, KeepStrategy::ShortestPath);
+        assert_eq!(suggestion.keep_index, 1);
+        assert_eq!(suggestion.delete, vec!["/a/long/path/file.txt", "/c/d/file.txt"]);
+    }
+
+    fn identical_group(dir: &Path, names: &[&str]) -> DuplicateGroup {
+        let files: Vec<DuplicateFile> = names
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                std::fs::write(&path, "same content").unwrap();
+                duplicate_file(&path)
+            })
+            .collect();
+        DuplicateGroup {
+            hash: calculate_full_hash(&dir.join(names[0])).unwrap(),
+            total_wasted: 12 * (files.len() as u64 - 1),
+            file_size: 12,
+            files,
+        }
+    }
+
+    #[test]
+    fn test_resolve_group_trashes_all_but_kept() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let group = identical_group(temp_dir.path(), &["a.txt", "copy of a.txt", "b.txt"]);
+
+        let remove = |path: &str| std::fs::remove_file(path).map_err(|e| e.to_string());
+        let resolution = resolve_group_with(&group, KeepStrategy::ShortestPath, remove).unwrap();
+
+        assert!(resolution.kept.ends_with("a.txt"));
+        assert!(Path::new(&resolution.kept).exists());
+        assert_eq!(resolution.trashed.len(), 2);
+        assert_eq!(resolution.bytes_freed, 24);
+        assert!(resolution.trashed.iter().all(|p| !Path::new(p).exists()));
+    }
+
+    #[test]
+    fn test_resolve_group_aborts_when_a_copy_changed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let group = identical_group(temp_dir.path(), &["a.txt", "b.txt", "c.txt"]);
+        std::fs::write(temp_dir.path().join("c.txt"), "edited since the scan").unwrap();
+
+        let remove = |path: &str| std::fs::remove_file(path).map_err(|e| e.to_string());
+        let err = resolve_group_with(&group, KeepStrategy::ShortestPath, remove).unwrap_err();
+
+        assert!(err.contains("changed since the scan"));
+        assert!(group.files.iter().all(|f| Path::new(&f.path).exists()));
+    }
+
+    #[test]
+    fn test_resolve_group_refuses_the_kept_file_under_another_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut group = identical_group(temp_dir.path(), &["a.txt", "b.txt"]);
+        let kept = group.files[0].clone();
+        let mut respelled = kept.clone();
+        respelled.path = temp_dir.path().join(".").join("a.txt").to_string_lossy().to_string();
+        group.files = vec![kept.clone(), kept, respelled];
+
+        let remove = |path: &str| std::fs::remove_file(path).map_err(|e| e.to_string());
+        let err = resolve_group_with(&group, KeepStrategy::ShortestPath, remove).unwrap_err();
+
+        assert!(err.contains("copy being kept"));
+        assert!(temp_dir.path().join("a.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_group_checks_symlinks_on_disk() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut group = identical_group(temp_dir.path(), &["a.txt", "b.txt"]);
+        let link = temp_dir.path().join("l.txt");
+        std::os::unix::fs::symlink(temp_dir.path().join("b.txt"), &link).unwrap();
+        // The client lists the symlink as a real file
+        group.files.push(duplicate_file(&link));
+
+        let remove = |path: &str| std::fs::remove_file(path).map_err(|e| e.to_string());
+        let resolution = resolve_group_with(&group, KeepStrategy::ShortestPath, remove).unwrap();
+
+        assert!(resolution.kept.ends_with("a.txt"));
+        assert_eq!(resolution.trashed.len(), 1);
+        assert!(resolution.trashed[0].ends_with("b.txt"));
+        assert!(std::fs::symlink_metadata(&link).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedupe_via_hardlink() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let keep = temp_dir.path().join("keep.bin");
+        let dup = temp_dir.path().join("dup.bin");
+        std::fs::write(&keep, "same bytes").unwrap();
+        std::fs::write(&dup, "same bytes").unwrap();
+        let (keep_str, dup_str) = (keep.to_str().unwrap(), dup.to_str().unwrap());
+
+        assert_eq!(dedupe_via_hardlink(keep_str, dup_str).unwrap(), 10);
+        let keep_ino = std::fs::metadata(&keep).unwrap().ino();
+        assert_eq!(std::fs::metadata(&dup).unwrap().ino(), keep_ino);
+        assert_eq!(std::fs::read_to_string(&dup).unwrap(), "same bytes");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        // Already linked
+        assert!(dedupe_via_hardlink(keep_str, dup_str).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedupe_via_hardlink_rejects_different_content() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let keep = temp_dir.path().join("keep.bin");
+        let dup = temp_dir.path().join("dup.bin");
+        std::fs::write(&keep, "aaaa").unwrap();
+        std::fs::write(&dup, "bbbb").unwrap();
+
+        assert!(dedupe_via_hardlink(keep.to_str().unwrap(), dup.to_str().unwrap()).is_err());
+        assert_eq!(std::fs::read_to_string(&dup).unwrap(), "bbbb");
+    }
+
+    #[test]
+    fn test_scan_duplicates_max_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+        let nested = dir_path.join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        std::fs::write(dir_path.join("top.txt"), "same content").unwrap();
+        std::fs::write(nested.join("deep.txt"), "same content").unwrap();
+
+        let shallow = scan_duplicates(dir_path.to_str().unwrap(), 0, &ScanOptions::with_max_depth(Some(1)));
+        assert!(shallow.is_empty());
+
+        let deep = scan_duplicates(dir_path.to_str().unwrap(), 0, &ScanOptions::default());
+        assert_eq!(deep.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_duplicates_include_hidden() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+        std::fs::write(dir_path.join(".zshrc"), "export PATH=/usr/bin").unwrap();
+        std::fs::write(dir_path.join(".zshrc.bak"), "export PATH=/usr/bin").unwrap();
+
+        let default = scan_duplicates(dir_path.to_str().unwrap(), 0, &ScanOptions::default());
+        assert!(default.is_empty());
+
+        let options = ScanOptions::default().with_include_hidden(true);
+        let hidden = scan_duplicates(dir_path.to_str().unwrap(), 0, &options);
+        assert_eq!(hidden.len(), 1);
+        assert_eq!(hidden[0].files.len(), 2);
+    }
+
+    #[test]
+    fn test_cap_groups() {
+        let group = |wasted: u64| DuplicateGroup {
+            hash: wasted.to_string(),
+            files: Vec::new(),
+            file_size: wasted,
+            total_wasted: wasted,
+        };
+        let groups = vec![group(500), group(300), group(100), group(10)];
+
+        let capped = cap_groups(groups.clone(), 50, Some(2));
+        assert_eq!(capped.iter().map(|g| g.total_wasted).collect::<Vec<_>>(), vec![500, 300]);
+
+        assert_eq!(cap_groups(groups.clone(), 200, None).len(), 2);
+        assert_eq!(cap_groups(groups, 0, None).len(), 4);
+    }
+
+    #[test]
+    fn test_scan_duplicates_with_byte_threshold() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let icon = vec![9u8; 200 * 1024];
+        std::fs::write(temp_dir.path().join("icon.png"), &icon).unwrap();
+        std::fs::write(temp_dir.path().join("icon copy.png"), &icon).unwrap();
+        std::fs::write(temp_dir.path().join("tiny.txt"), "x").unwrap();
+        std::fs::write(temp_dir.path().join("tiny copy.txt"), "x").unwrap();
+        let dir = temp_dir.path().to_str().unwrap();
+
+        let groups = scan_duplicates(dir, 100 * 1024, &ScanOptions::default());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].file_size, 200 * 1024);
+        assert!(scan_duplicates(dir, min_size_from_mb(1), &ScanOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_empty_files_only_with_zero_threshold() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("a.lock"), "").unwrap();
+        std::fs::write(temp_dir.path().join("b.lock"), "").unwrap();
+        let dir = temp_dir.path().to_str().unwrap();
+
+        assert_eq!(min_size_from_mb(0), 1);
+        assert!(scan_duplicates(dir, min_size_from_mb(0), &ScanOptions::default()).is_empty());
+        let groups = scan_duplicates(dir, 0, &ScanOptions::default());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].total_wasted, 0);
+    }
+
+    #[test]
+    fn test_scan_duplicates_with_stats() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path();
+        std::fs::write(dir_path.join("a.txt"), "same").unwrap();
+        std::fs::write(dir_path.join("b.txt"), "same").unwrap();
+        std::fs::write(dir_path.join("c.txt"), "diff").unwrap();
+        std::fs::write(dir_path.join("unique.txt"), "a different length").unwrap();
+
+        let (groups, stats) = scan_duplicates_with_stats(dir_path.to_str().unwrap(), 0, &ScanOptions::default());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            stats,
+            ScanStats {
+                files_considered: 4,
+                size_matched: 3,
+                partial_hashed: 3,
+                full_hashed: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("abc.txt");
+        std::fs::write(&file, "abc").unwrap();
+
+        assert_eq!(
+            hash_file(&file, HashAlgorithm::Sha256).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(hash_file(&file, HashAlgorithm::Sha512).unwrap().starts_with("ddaf35a193617aba"));
+        assert!(hash_file(&temp_dir.path().join("missing"), HashAlgorithm::Sha256).unwrap_err().contains("Cannot read"));
+        assert!(hash_file(temp_dir.path(), HashAlgorithm::Sha256).unwrap_err().contains("not a file"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlink_is_never_a_deletable_copy() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let a = temp_dir.path().join("a.txt");
+        let b = temp_dir.path().join("b.txt");
+        let link = temp_dir.path().join("0-link.txt");
+        std::fs::write(&a, "same content").unwrap();
+        std::fs::write(&b, "same content").unwrap();
+        std::os::unix::fs::symlink(&a, &link).unwrap();
+        // A lone file with a link to it is not a duplicate
+        let lone = temp_dir.path().join("lone.txt");
+        std::fs::write(&lone, "unique content").unwrap();
+        std::os::unix::fs::symlink(&lone, temp_dir.path().join("lone-link.txt")).unwrap();
+
+        let options = ScanOptions::default().with_follow_symlinks(true);
+        let groups = scan_duplicates(&temp_dir.path().to_string_lossy(), 0, &options);
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.files.len(), 3);
+        assert_eq!(group.files.iter().filter(|f| f.is_symlink).count(), 1);
+        assert_eq!(group.total_wasted, "same content".len() as u64);
+
+        for strategy in [KeepStrategy::ShortestPath, KeepStrategy::NewestModified, KeepStrategy::OldestModified] {
+            let suggestion = suggest_keep(group, strategy);
+            assert!(!group.files[suggestion.keep_index].is_symlink);
+            assert_eq!(suggestion.delete.len(), 1);
+            assert!(!suggestion.delete.contains(&link.to_string_lossy().to_string()));
+        }
+
+        // Canonical dedupe keeps the real file even when its link is listed first
+        let deduped = dedupe_groups_by_canonical_path(groups.clone());
+        assert_eq!(deduped[0].files.len(), 2);
+        assert!(deduped[0].files.iter().all(|f| !f.is_symlink));
+
+        assert!(verify_duplicate(&a.to_string_lossy(), &group.hash, &link.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn test_scan_directories_for_duplicates_nested_roots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("Downloads");
+        std::fs::create_dir(&nested).unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "same content").unwrap();
+        std::fs::write(nested.join("b.txt"), "same content").unwrap();
+        std::fs::write(nested.join("c.txt"), "same content").unwrap();
+
+        let groups = scan_directories_for_duplicates(&[temp_dir.path().to_path_buf(), nested], 0, &ScanOptions::default(), 2);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files.len(), 3);
+        assert_eq!(groups[0].total_wasted, 2 * "same content".len() as u64);
+    }
 }
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::disk_size::get_directory_size;
+
+/// Localizations that are never offered for removal
+const ALWAYS_KEEP: &[&str] = &["Base", "en", "English"];
+
+/// A single localization folder inside an app bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageFile {
+    pub path: String,
+    pub language: String,
+    pub size: u64,
+}
+
+/// Removable localizations for one app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLanguageFiles {
+    pub app_name: String,
+    pub app_path: String,
+    pub files: Vec<LanguageFile>,
+    pub total_size: u64,
+}
+
+/// Language code of a `.lproj` folder, e.g. "fr" for `fr.lproj`
+fn lproj_language(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy();
+    name.strip_suffix(".lproj").map(|s| s.to_string())
+}
+
+/// Whether `language` should be kept. Keeping "pt" also keeps regional
+/// variants like "pt_BR" and "pt-PT".
+fn should_keep(language: &str, keep: &[String]) -> bool {
+    let language = language.to_ascii_lowercase();
+    ALWAYS_KEEP
+        .iter()
+        .map(|s| s.to_string())
+        .chain(keep.iter().cloned())
+        .map(|k| k.to_ascii_lowercase())
+        .any(|k| match language.strip_prefix(&k) {
+            Some(rest) => rest.is_empty() || rest.starts_with('_') || rest.starts_with('-'),
+            None => false,
+        })
+}
+
+/// Find removable `.lproj` folders anywhere inside an app bundle
+fn scan_app_bundle(app_path: &Path, keep: &[String]) -> Vec<LanguageFile> {
+    let mut files = Vec::new();
+    let mut walker = WalkDir::new(app_path).into_iter();
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let Some(language) = lproj_language(entry.path()) else {
+            continue;
+        };
+        // Never descend into localization folders
+        walker.skip_current_dir();
+        if should_keep(&language, keep) {
+            continue;
+        }
+        files.push(LanguageFile {
+            path: entry.path().to_string_lossy().to_string(),
+            language,
+            size: get_directory_size(entry.path()),
+        });
+    }
+
+    files.sort_by(|a, b| b.size.cmp(&a.size));
+    files
+}
+
+/// Scan every `.app` directly inside `apps_dir` for removable localizations
+pub fn scan_language_files_in(apps_dir: &Path, keep: &[String]) -> Vec<AppLanguageFiles> {
+    let Ok(entries) = fs::read_dir(apps_dir) else {
+        return Vec::new();
+    };
+
+    let mut apps: Vec<AppLanguageFiles> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && p.extension().map(|e| e == "app").unwrap_or(false))
+        .filter_map(|app_path| {
+            let files = scan_app_bundle(&app_path, keep);
+            if files.is_empty() {
+                return None;
+            }
+            Some(AppLanguageFiles {
+                app_name: app_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                app_path: app_path.to_string_lossy().to_string(),
+                total_size: files.iter().map(|f| f.size).sum(),
+                files,
+            })
+        })
+        .collect();
+
+    apps.sort_by(|a, b| b.total_size.cmp(&a.total_size));
+    apps
+}
+
+/// Scan /Applications for localizations not in `keep`
+pub fn scan_language_files(keep: &[String]) -> Vec<AppLanguageFiles> {
+    scan_language_files_in(&PathBuf::from("/Applications"), keep)
+}
+
+/// Move a localization folder to the trash. Refuses anything that isn't a
+/// removable `.lproj` folder.
+pub fn remove_language_file(path: &str) -> Result<(), String> {
+    crate::audit::logged("remove_language_file", path, || {
+        let path = PathBuf::from(path);
+        match lproj_language(&path) {
+            Some(language) if !should_keep(&language, &[]) => {}
+            Some(_) => return Err(format!("Refusing to remove required localization: {}", path.display())),
+            None => return Err(format!("Not a localization folder: {}", path.display())),
+        }
+
+        if !path.is_dir() {
+            return Ok(());
+        }
+        trash::delete(&path).map_err(|e| e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_keep() {
+        let keep = vec!["pt".to_string()];
+        assert!(should_keep("Base", &keep));
+        assert!(should_keep("en", &keep));
+        assert!(should_keep("en_GB", &keep));
+        assert!(should_keep("pt_BR", &keep));
+        assert!(should_keep("PT-pt", &keep));
+        assert!(!should_keep("fr", &keep));
+        assert!(!should_keep("ptx", &keep));
+    }
+
+    #[test]
+    fn test_scan_language_files_in() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let resources = temp_dir.path().join("Demo.app").join("Contents").join("Resources");
+        for lang in ["Base", "en", "fr", "de", "es"] {
+            fs::create_dir_all(resources.join(format!("{}.lproj", lang))).unwrap();
+            fs::write(resources.join(format!("{}.lproj", lang)).join("Localizable.strings"), "x").unwrap();
+        }
+        // Nested framework localizations are found too
+        let framework = temp_dir.path().join("Demo.app").join("Contents").join("Frameworks").join("Kit.framework");
+        fs::create_dir_all(framework.join("ja.lproj")).unwrap();
+        // Not an app bundle
+        fs::create_dir_all(temp_dir.path().join("Folder").join("it.lproj")).unwrap();
+
+        let apps = scan_language_files_in(temp_dir.path(), &["de".to_string()]);
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].app_name, "Demo");
+
+        let mut languages: Vec<&str> = apps[0].files.iter().map(|f| f.language.as_str()).collect();
+        languages.sort();
+        assert_eq!(languages, vec!["es", "fr", "ja"]);
+    }
+
+    #[test]
+    fn test_remove_language_file_refuses_required() {
+        assert!(remove_language_file("/Applications/Demo.app/Contents/Resources/Base.lproj").is_err());
+        assert!(remove_language_file("/Applications/Demo.app/Contents/Resources/en.lproj").is_err());
+        assert!(remove_language_file("/Applications/Demo.app/Contents/Resources").is_err());
+        // Missing folder is a no-op
+        assert!(remove_language_file("/nonexistent/fr.lproj").is_ok());
+    }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::trash_scanner;
+use crate::path_guard::ensure_within_roots;
+use crate::privileges;
+use crate::process::run_command_with_timeout;
+use crate::removal::Removal;
+
+/// Folders under /Library that may hold launchd job definitions
+const LAUNCH_DIR_NAMES: &[&str] = &["LaunchAgents", "LaunchDaemons"];
+
+/// How long to wait for `launchctl unload`
+const LAUNCHCTL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A launchd plist whose program no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanLaunchItem {
+    pub path: String,
+    pub label: String,
+    pub program: String,
+    pub requires_admin: bool, // Lives outside the user's Library
+}
+
+/// Label and executable path declared by a launchd plist
+fn read_launch_plist(path: &Path) -> Option<(String, String)> {
+    let plist = plist::from_file::<_, plist::Value>(path).ok()?;
+    let dict = plist.as_dictionary()?;
+
+    let label = dict
+        .get("Label")
+        .and_then(|l| l.as_string())
+        .unwrap_or_default()
+        .to_string();
+    let program = dict
+        .get("Program")
+        .and_then(|p| p.as_string())
+        .or_else(|| {
+            dict.get("ProgramArguments")
+                .and_then(|a| a.as_array())
+                .and_then(|a| a.first())
+                .and_then(|p| p.as_string())
+        })?
+        .to_string();
+
+    Some((label, program))
+}
+
+/// Label and program of a launchd plist whose absolute program path no longer
+/// exists. Relative programs (resolved via PATH) can't be checked and yield None.
+fn read_orphaned_program(path: &Path) -> Option<(String, String)> {
+    let (label, program) = read_launch_plist(path)?;
+    let program_path = Path::new(&program);
+    if !program_path.is_absolute() || program_path.exists() {
+        return None;
+    }
+    Some((label, program))
+}
+
+/// Find plists in `dir` that point at an absolute program path that no longer exists.
+/// Relative programs (resolved via PATH) can't be checked and are left alone.
+pub fn scan_launch_dir(dir: &Path, requires_admin: bool) -> Vec<OrphanLaunchItem> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "plist").unwrap_or(false))
+        .filter_map(|path| {
+            let (label, program) = read_orphaned_program(&path)?;
+            Some(OrphanLaunchItem {
+                path: path.to_string_lossy().to_string(),
+                label,
+                program,
+                requires_admin,
+            })
+        })
+        .collect()
+}
+
+/// The user's LaunchAgents folder under `home`
+fn user_launch_dir(home: &Path) -> PathBuf {
+    home.join("Library").join("LaunchAgents")
+}
+
+/// The system LaunchAgents and LaunchDaemons folders, which need admin rights to change
+fn system_launch_dirs() -> Vec<PathBuf> {
+    LAUNCH_DIR_NAMES.iter().map(|d| PathBuf::from("/Library").join(d)).collect()
+}
+
+/// Scan user and system LaunchAgents/LaunchDaemons for orphaned items
+pub fn scan_orphan_launch_items() -> Vec<OrphanLaunchItem> {
+    let mut items = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        items.extend(scan_launch_dir(&user_launch_dir(&home), false));
+    }
+    for dir in system_launch_dirs() {
+        items.extend(scan_launch_dir(&dir, true));
+    }
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+    items
+}
+
+/// Check that `path` is a plist directly inside one of `launch_dirs` and that
+/// its program is still missing. Returns the canonical plist path.
+fn validate_orphan_launch_item(path: &Path, launch_dirs: &[PathBuf]) -> Result<PathBuf, String> {
+    let is_plist = path.extension().map(|e| e == "plist").unwrap_or(false);
+    if !is_plist {
+        return Err(format!("Not a launch agent or daemon plist: {}", path.display()));
+    }
+    let canonical = ensure_within_roots(path, launch_dirs)?;
+    let in_launch_dir = canonical
+        .parent()
+        .is_some_and(|parent| launch_dirs.iter().any(|dir| dir.canonicalize().is_ok_and(|dir| dir == parent)));
+    if !in_launch_dir {
+        return Err(format!("Not a launch agent or daemon plist: {}", path.display()));
+    }
+    if read_orphaned_program(&canonical).is_none() {
+        return Err(format!("The program of {} exists again or can't be read; leaving it alone", path.display()));
+    }
+    Ok(canonical)
+}
+
+/// Unload an orphaned launch item and remove its plist, through the Trash or
+/// permanently per `removal`. Items outside the user's Library are unloaded and
+/// removed with admin privileges.
+pub fn remove_orphan_launch_item(path: &str, removal: Removal) -> Result<(), String> {
+    crate::audit::logged("remove_orphan_launch_item", path, || {
+        let plist_path = PathBuf::from(path);
+        if fs::symlink_metadata(&plist_path).is_err() {
+            return Ok(());
+        }
+
+        let user_dir = dirs::home_dir().map(|home| user_launch_dir(&home));
+        let mut launch_dirs = system_launch_dirs();
+        launch_dirs.extend(user_dir.clone());
+        let plist_path = validate_orphan_launch_item(&plist_path, &launch_dirs)?;
+
+        let in_user_library = user_dir
+            .and_then(|dir| dir.canonicalize().ok())
+            .is_some_and(|dir| plist_path.starts_with(dir));
+
+        if in_user_library {
+            // Unloading fails harmlessly if the job isn't loaded
+            let _ = run_command_with_timeout("launchctl", [OsStr::new("unload"), plist_path.as_os_str()], LAUNCHCTL_TIMEOUT);
+            removal.remove(&plist_path)
+        } else {
+            let plist = plist_path.to_string_lossy();
+            privileges::with_admin_session(|| {
+                // Unloading fails harmlessly if the job isn't loaded
+                let _ = privileges::run_commands_with_admin(&[&["launchctl", "unload", &plist]]);
+                match removal {
+                    Removal::Trash => trash_scanner::trash_with_admin_privileges(&plist_path),
+                    Removal::Permanent => privileges::run_commands_with_admin(&[&["rm", "-f", &plist]]),
+                }
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plist(path: &Path, label: &str, key: &str, program: &str) {
+        let mut dict = plist::Dictionary::new();
+        dict.insert("Label".to_string(), plist::Value::String(label.to_string()));
+        if key == "Program" {
+            dict.insert(key.to_string(), plist::Value::String(program.to_string()));
+        } else {
+            dict.insert(
+                key.to_string(),
+                plist::Value::Array(vec![plist::Value::String(program.to_string())]),
+            );
+        }
+        plist::Value::Dictionary(dict).to_file_xml(path).unwrap();
+    }
+
+    #[test]
+    fn test_scan_launch_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let agents = temp_dir.path().join("LaunchAgents");
+        fs::create_dir(&agents).unwrap();
+        let existing = temp_dir.path().join("helper");
+        fs::write(&existing, "").unwrap();
+
+        write_plist(&agents.join("com.gone.agent.plist"), "com.gone.agent", "Program", "/Applications/Gone.app/helper");
+        write_plist(&agents.join("com.gone.args.plist"), "com.gone.args", "ProgramArguments", "/opt/gone/bin/run");
+        write_plist(&agents.join("com.ok.agent.plist"), "com.ok.agent", "Program", existing.to_str().unwrap());
+        write_plist(&agents.join("com.path.agent.plist"), "com.path.agent", "ProgramArguments", "node");
+        fs::write(agents.join("broken.plist"), "not a plist").unwrap();
+
+        let mut labels: Vec<String> = scan_launch_dir(&agents, false).into_iter().map(|i| i.label).collect();
+        labels.sort();
+        assert_eq!(labels, vec!["com.gone.agent", "com.gone.args"]);
+    }
+
+    #[test]
+    fn test_validate_orphan_launch_item() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let agents = temp_dir.path().join("LaunchAgents");
+        let other = temp_dir.path().join("Other").join("LaunchAgents");
+        fs::create_dir_all(agents.join("nested")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let program = temp_dir.path().join("helper");
+        fs::write(&program, "").unwrap();
+        let roots = [agents.clone()];
+
+        let orphan = agents.join("com.gone.plist");
+        write_plist(&orphan, "com.gone", "Program", "/Applications/Gone.app/helper");
+        assert_eq!(validate_orphan_launch_item(&orphan, &roots).unwrap(), orphan.canonicalize().unwrap());
+
+        // Only launch folders count, not any folder that happens to share their name
+        let elsewhere = other.join("com.gone.plist");
+        write_plist(&elsewhere, "com.gone", "Program", "/Applications/Gone.app/helper");
+        assert!(validate_orphan_launch_item(&elsewhere, &roots).is_err());
+
+        let nested = agents.join("nested").join("com.gone.plist");
+        write_plist(&nested, "com.gone", "Program", "/Applications/Gone.app/helper");
+        assert!(validate_orphan_launch_item(&nested, &roots).is_err());
+
+        let notes = agents.join("notes.txt");
+        fs::write(&notes, "").unwrap();
+        assert!(validate_orphan_launch_item(&notes, &roots).is_err());
+
+        // The program was reinstalled since the scan
+        let restored = agents.join("com.ok.plist");
+        write_plist(&restored, "com.ok", "Program", program.to_str().unwrap());
+        assert!(validate_orphan_launch_item(&restored, &roots).is_err());
+    }
+
+    #[test]
+    fn test_remove_orphan_launch_item_rejects_other_paths() {
+        assert!(remove_orphan_launch_item("/etc/hosts", Removal::Trash).is_err());
+    }
+}
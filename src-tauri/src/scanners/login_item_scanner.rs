@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::process::run_command_with_timeout;
+use crate::scanners::trash_scanner::current_uid;
+
+/// How long to wait for `launchctl` and System Events queries
+const LOGIN_ITEM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a login item is registered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LoginItemSource {
+    UserLaunchAgent,   // ~/Library/LaunchAgents
+    SystemLaunchAgent, // /Library/LaunchAgents
+    OpenAtLogin,       // Apps listed under "Open at Login" in System Settings
+}
+
+/// Something that starts automatically when the user logs in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginItem {
+    pub name: String,
+    pub label: String,
+    pub path: String,
+    pub enabled: bool,
+    pub source: LoginItemSource,
+}
+
+/// The launchd domain for the logged-in user's GUI session
+fn gui_domain() -> String {
+    format!("gui/{}", current_uid())
+}
+
+/// Parse `launchctl print-disabled` output into label -> disabled.
+/// Newer macOS prints `=> disabled`/`=> enabled`, older versions `=> true`/`=> false`.
+fn parse_print_disabled(output: &str) -> HashMap<String, bool> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (label, state) = line.split_once("=>")?;
+            let label = label.trim().trim_matches('"');
+            let disabled = match state.trim() {
+                "disabled" | "true" => true,
+                "enabled" | "false" => false,
+                _ => return None,
+            };
+            Some((label.to_string(), disabled))
+        })
+        .collect()
+}
+
+/// Labels the user has disabled in their GUI domain
+fn disabled_labels() -> HashMap<String, bool> {
+    run_command_with_timeout("launchctl", ["print-disabled", &gui_domain()], LOGIN_ITEM_TIMEOUT)
+        .map(|output| parse_print_disabled(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Read the label and `Disabled` key of a launch agent plist
+fn read_agent_plist(path: &Path) -> Option<(String, bool)> {
+    let plist = plist::from_file::<_, plist::Value>(path).ok()?;
+    let dict = plist.as_dictionary()?;
+    let label = dict.get("Label").and_then(|l| l.as_string())?.to_string();
+    let disabled = dict.get("Disabled").and_then(|d| d.as_boolean()).unwrap_or(false);
+    Some((label, disabled))
+}
+
+/// List the launch agents in `dir`. An agent is enabled unless its plist sets
+/// `Disabled` or launchd has an override disabling its label.
+pub fn scan_launch_agents(dir: &Path, source: LoginItemSource, overrides: &HashMap<String, bool>) -> Vec<LoginItem> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "plist").unwrap_or(false))
+        .filter_map(|path| {
+            let (label, plist_disabled) = read_agent_plist(&path)?;
+            let disabled = overrides.get(&label).copied().unwrap_or(plist_disabled);
+            Some(LoginItem {
+                name: path.file_stem()?.to_string_lossy().to_string(),
+                label,
+                path: path.to_string_lossy().to_string(),
+                enabled: !disabled,
+                source: source.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Apps registered to open at login, via System Events. These include items
+/// added through `SMAppService`; they are listed as enabled since disabled
+/// ones aren't reported.
+fn open_at_login_items() -> Vec<LoginItem> {
+    let script = r#"tell application "System Events" to get the {name, path} of every login item"#;
+    let Ok(output) = run_command_with_timeout("osascript", ["-s", "s", "-e", script], LOGIN_ITEM_TIMEOUT) else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    parse_open_at_login(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse osascript's `{{"Name", ...}, {"/path", ...}}` record of login items.
+/// Strings are read with their `\"` and `\\` escapes, so names containing
+/// quotes or commas keep their place in the list.
+fn parse_open_at_login(output: &str) -> Vec<LoginItem> {
+    let mut lists: Vec<Vec<String>> = Vec::new();
+    let mut depth = 0usize;
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                depth += 1;
+                if depth == 2 {
+                    lists.push(Vec::new());
+                }
+            }
+            '}' => depth = depth.saturating_sub(1),
+            '"' => {
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some(escaped) => value.push(escaped),
+                            None => break,
+                        },
+                        c => value.push(c),
+                    }
+                }
+                if let Some(list) = lists.last_mut() {
+                    list.push(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    let [names, paths] = lists.as_slice() else {
+        return Vec::new();
+    };
+    names
+        .iter()
+        .zip(paths)
+        .map(|(name, path)| LoginItem {
+            name: name.to_string(),
+            label: name.to_string(),
+            path: path.to_string(),
+            enabled: true,
+            source: LoginItemSource::OpenAtLogin,
+        })
+        .collect()
+}
+
+/// All login items: user and system launch agents plus "Open at Login" apps
+pub fn scan_login_items() -> Vec<LoginItem> {
+    let overrides = disabled_labels();
+    let mut items = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        items.extend(scan_launch_agents(
+            &home.join("Library").join("LaunchAgents"),
+            LoginItemSource::UserLaunchAgent,
+            &overrides,
+        ));
+    }
+    items.extend(scan_launch_agents(
+        &PathBuf::from("/Library/LaunchAgents"),
+        LoginItemSource::SystemLaunchAgent,
+        &overrides,
+    ));
+    items.extend(open_at_login_items());
+    items.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    items
+}
+
+/// Check that `path` is a plist directly inside a LaunchAgents folder
+fn validate_agent_path(path: &Path) -> Result<(), String> {
+    let is_plist = path.extension().map(|e| e == "plist").unwrap_or(false);
+    let in_agents_dir = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n == "LaunchAgents")
+        .unwrap_or(false);
+
+    if is_plist && in_agents_dir {
+        Ok(())
+    } else {
+        Err(format!(
+            "Only launch agents can be toggled here; manage {} in System Settings > General > Login Items",
+            path.display()
+        ))
+    }
+}
+
+/// Run launchctl, turning a failure into an error with its output
+fn launchctl(args: &[&str]) -> Result<(), String> {
+    let output = run_command_with_timeout("launchctl", args, LOGIN_ITEM_TIMEOUT)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "launchctl {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Enable or disable a launch agent for the current user.
+///
+/// Disabling boots the agent out of the GUI session and records a launchd
+/// override so it stays off at the next login; enabling clears the override
+/// and bootstraps it again. The plist itself is left untouched.
+pub fn set_login_item_enabled(path: &str, enabled: bool) -> Result<(), String> {
+    let plist_path = Path::new(path);
+    validate_agent_path(plist_path)?;
+    let (label, _) = read_agent_plist(plist_path).ok_or_else(|| format!("Cannot read launch agent {}", path))?;
+
+    let domain = gui_domain();
+    let service = format!("{}/{}", domain, label);
+    if enabled {
+        launchctl(&["enable", &service])?;
+        // Bootstrapping fails if the agent is already loaded, which is fine
+        let _ = launchctl(&["bootstrap", &domain, path]);
+        Ok(())
+    } else {
+        // Booting out fails if the agent isn't running, which is fine
+        let _ = launchctl(&["bootout", &domain, path]);
+        launchctl(&["disable", &service])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_agent(path: &Path, label: &str, disabled: Option<bool>) {
+        let mut dict = plist::Dictionary::new();
+        dict.insert("Label".to_string(), plist::Value::String(label.to_string()));
+        if let Some(disabled) = disabled {
+            dict.insert("Disabled".to_string(), plist::Value::Boolean(disabled));
+        }
+        plist::Value::Dictionary(dict).to_file_xml(path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_print_disabled() {
+        let output = "disabled services = {\n\t\"com.example.off\" => disabled\n\t\"com.example.on\" => enabled\n\t\"com.example.legacy\" => true\n}\n";
+        let parsed = parse_print_disabled(output);
+        assert_eq!(parsed.get("com.example.off"), Some(&true));
+        assert_eq!(parsed.get("com.example.on"), Some(&false));
+        assert_eq!(parsed.get("com.example.legacy"), Some(&true));
+        assert_eq!(parsed.len(), 3);
+    }
+
+    #[test]
+    fn test_scan_launch_agents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_agent(&temp_dir.path().join("com.example.a.plist"), "com.example.a", None);
+        write_agent(&temp_dir.path().join("com.example.b.plist"), "com.example.b", Some(true));
+        write_agent(&temp_dir.path().join("com.example.c.plist"), "com.example.c", None);
+        fs::write(temp_dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let overrides = HashMap::from([("com.example.c".to_string(), true)]);
+        let mut items = scan_launch_agents(temp_dir.path(), LoginItemSource::UserLaunchAgent, &overrides);
+        items.sort_by(|a, b| a.label.cmp(&b.label));
+
+        let states: Vec<(&str, bool)> = items.iter().map(|i| (i.label.as_str(), i.enabled)).collect();
+        assert_eq!(states, vec![("com.example.a", true), ("com.example.b", false), ("com.example.c", false)]);
+    }
+
+    #[test]
+    fn test_parse_open_at_login() {
+        let items = parse_open_at_login(r#"{{"Dropbox", "Rectangle"}, {"/Applications/Dropbox.app", "/Applications/Rectangle.app"}}"#);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].name, "Rectangle");
+        assert_eq!(items[1].path, "/Applications/Rectangle.app");
+        assert!(parse_open_at_login("{{}, {}}").is_empty());
+    }
+
+    #[test]
+    fn test_parse_open_at_login_with_quotes_in_names() {
+        let items = parse_open_at_login(
+            r#"{{"My \"Big\" App", "Back\\slash, Inc"}, {"/Applications/My \"Big\" App.app", "/Applications/B.app"}}"#,
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, r#"My "Big" App"#);
+        assert_eq!(items[0].path, r#"/Applications/My "Big" App.app"#);
+        assert_eq!(items[1].name, r"Back\slash, Inc");
+        assert_eq!(items[1].path, "/Applications/B.app");
+    }
+
+    #[test]
+    fn test_set_login_item_enabled_rejects_non_agents() {
+        assert!(set_login_item_enabled("/Applications/Dropbox.app", false).is_err());
+        assert!(set_login_item_enabled("/Library/LaunchDaemons/com.example.plist", false).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::process::run_command_with_timeout;
+
+/// How long to wait for `pgrep`
+const PGREP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Envelope index database of one Mail data version (`~/Library/Mail/V10` etc.)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MailIndex {
+    pub version: String,
+    pub path: String,
+    pub size: u64, // Including its -wal and -shm files
+}
+
+/// Mail's index databases, and whether Mail is open (it must be quit before a rebuild)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailIndexReport {
+    pub indexes: Vec<MailIndex>,
+    pub total_size: u64,
+    pub mail_running: bool,
+}
+
+/// Apple Mail's data folder
+pub fn mail_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library").join("Mail"))
+}
+
+/// Whether a folder name is a Mail data version such as `V10`
+fn is_version_dir(name: &str) -> bool {
+    name.strip_prefix('V')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The `Envelope Index` database and its side files in a `MailData` folder
+fn envelope_index_files(mail_data: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(mail_data) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().starts_with("Envelope Index"))
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| e.path())
+        .collect()
+}
+
+/// `MailData` folders of every Mail data version under `mail_dir`, as (version, folder)
+fn mail_data_dirs(mail_dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let entries = match fs::read_dir(mail_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Cannot read {}: {}", mail_dir.display(), e)),
+    };
+    Ok(entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            is_version_dir(&name).then(|| (name, e.path().join("MailData")))
+        })
+        .collect())
+}
+
+/// Envelope indexes under `mail_dir`, largest first
+pub fn scan_mail_indexes_in(mail_dir: &Path) -> Result<Vec<MailIndex>, String> {
+    let mut indexes: Vec<MailIndex> = mail_data_dirs(mail_dir)?
+        .into_iter()
+        .filter_map(|(version, mail_data)| {
+            let files = envelope_index_files(&mail_data);
+            if files.is_empty() {
+                return None;
+            }
+            let size = files.iter().filter_map(|f| fs::metadata(f).ok()).map(|m| m.len()).sum();
+            Some(MailIndex {
+                version,
+                path: mail_data.join("Envelope Index").to_string_lossy().to_string(),
+                size,
+            })
+        })
+        .collect();
+
+    indexes.sort_by(|a, b| b.size.cmp(&a.size));
+    Ok(indexes)
+}
+
+/// Whether Apple Mail is running, via `pgrep`
+pub fn is_mail_running() -> bool {
+    run_command_with_timeout("pgrep", ["-x", "Mail"], PGREP_TIMEOUT)
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Delete every envelope index under `mail_dir` so Mail rebuilds them on its
+/// next launch, returning the bytes freed. Refuses while Mail is running, since
+/// it would keep using (and rewrite) the open database.
+pub fn rebuild_mail_index_in(mail_dir: &Path, mail_running: bool) -> Result<u64, String> {
+    if mail_running {
+        return Err("Quit Mail before rebuilding its index".to_string());
+    }
+
+    let mut freed = 0;
+    for (_, mail_data) in mail_data_dirs(mail_dir)? {
+        for file in envelope_index_files(&mail_data) {
+            let size = fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+            crate::audit::logged("rebuild_mail_index", &file.to_string_lossy(), || {
+                fs::remove_file(&file).map_err(|e| format!("{}: {}", file.display(), e))
+            })?;
+            freed += size;
+        }
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_mail_dir() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let v10 = temp_dir.path().join("V10").join("MailData");
+        let v9 = temp_dir.path().join("V9").join("MailData");
+        fs::create_dir_all(&v10).unwrap();
+        fs::create_dir_all(&v9).unwrap();
+        fs::write(v10.join("Envelope Index"), vec![0u8; 4096]).unwrap();
+        fs::write(v10.join("Envelope Index-wal"), vec![0u8; 1024]).unwrap();
+        fs::write(v10.join("Signatures.plist"), "keep").unwrap();
+        fs::write(v9.join("Envelope Index"), vec![0u8; 100]).unwrap();
+        fs::create_dir_all(temp_dir.path().join("Bundles").join("MailData")).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_is_version_dir() {
+        assert!(is_version_dir("V10"));
+        assert!(!is_version_dir("V"));
+        assert!(!is_version_dir("Bundles"));
+        assert!(!is_version_dir("V10 copy"));
+    }
+
+    #[test]
+    fn test_scan_mail_indexes_in() {
+        let mail_dir = make_mail_dir();
+        let indexes = scan_mail_indexes_in(mail_dir.path()).unwrap();
+
+        assert_eq!(indexes.len(), 2);
+        assert_eq!(indexes[0].version, "V10");
+        assert_eq!(indexes[0].size, 5120);
+        assert_eq!(indexes[1].size, 100);
+        assert!(scan_mail_indexes_in(&mail_dir.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_mail_index_in() {
+        let mail_dir = make_mail_dir();
+        assert!(rebuild_mail_index_in(mail_dir.path(), true).is_err());
+        assert!(mail_dir.path().join("V10/MailData/Envelope Index").exists());
+
+        assert_eq!(rebuild_mail_index_in(mail_dir.path(), false).unwrap(), 5220);
+        assert!(scan_mail_indexes_in(mail_dir.path()).unwrap().is_empty());
+        assert!(mail_dir.path().join("V10/MailData/Signatures.plist").exists());
+    }
+}
@@ -1,6 +1,24 @@
 // Scanner modules
 pub mod cache_scanner;
+pub mod cache_diff;
 pub mod app_scanner;
 pub mod app_data_scanner;
+pub mod browser_scanner;
+pub mod derived_data_scanner;
+pub mod directory_analyzer;
 pub mod file_scanner;
 pub mod hash_scanner;
+pub mod language_scanner;
+pub mod launch_item_scanner;
+pub mod login_item_scanner;
+pub mod mail_scanner;
+pub mod disk_size;
+pub mod quarantine_scanner;
+pub mod running_apps;
+pub mod scan_control;
+pub mod scan_options;
+pub mod shared_junk_scanner;
+pub mod stale_download_scanner;
+pub mod size_cache;
+pub mod storage_breakdown;
+pub mod trash_scanner;
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::disk_size::get_path_size;
+
+/// Extended attribute set by macOS on files downloaded from the internet
+const QUARANTINE_XATTR: &str = "com.apple.quarantine";
+
+/// Extended attribute holding the download's source URLs (binary plist array)
+const WHERE_FROMS_XATTR: &str = "com.apple.metadata:kMDItemWhereFroms";
+
+/// A downloaded file carrying a quarantine record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedDownload {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub downloaded_at: Option<u64>, // Unix timestamp
+    pub agent: Option<String>,      // App that downloaded it, e.g. "Safari"
+    pub origin_url: Option<String>,
+    pub referrer_url: Option<String>,
+}
+
+/// Fields of a `com.apple.quarantine` value: `flags;hex_timestamp;agent;uuid`
+#[derive(Debug, PartialEq)]
+struct QuarantineInfo {
+    downloaded_at: Option<u64>,
+    agent: Option<String>,
+}
+
+fn parse_quarantine_value(value: &[u8]) -> QuarantineInfo {
+    let text = String::from_utf8_lossy(value);
+    let mut fields = text.split(';');
+    let _flags = fields.next();
+    let downloaded_at = fields.next().and_then(|t| u64::from_str_radix(t.trim(), 16).ok());
+    let agent = fields
+        .next()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+    QuarantineInfo { downloaded_at, agent }
+}
+
+/// Parse the kMDItemWhereFroms plist: the download URL followed by the referrer
+fn parse_where_froms(value: &[u8]) -> Vec<String> {
+    plist::from_bytes::<Vec<String>>(value).unwrap_or_default()
+}
+
+/// Read the quarantine record of a single file, if it has one
+fn quarantined_download(path: &Path) -> Option<QuarantinedDownload> {
+    let quarantine = xattr::get(path, QUARANTINE_XATTR).ok().flatten()?;
+    let info = parse_quarantine_value(&quarantine);
+    let mut urls = xattr::get(path, WHERE_FROMS_XATTR)
+        .ok()
+        .flatten()
+        .map(|v| parse_where_froms(&v))
+        .unwrap_or_default()
+        .into_iter();
+
+    Some(QuarantinedDownload {
+        path: path.to_string_lossy().to_string(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        size: get_path_size(path),
+        downloaded_at: info.downloaded_at,
+        agent: info.agent,
+        origin_url: urls.next(),
+        referrer_url: urls.next(),
+    })
+}
+
+/// List the top-level items in `directory` that carry a quarantine record, oldest first
+pub fn scan_quarantined_in(directory: &Path) -> Vec<QuarantinedDownload> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut downloads: Vec<QuarantinedDownload> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| quarantined_download(&e.path()))
+        .collect();
+    downloads.sort_by_key(|d| d.downloaded_at.unwrap_or(u64::MAX));
+    downloads
+}
+
+/// Scan ~/Downloads for quarantined files
+pub fn scan_quarantined_downloads() -> Vec<QuarantinedDownload> {
+    dirs::home_dir()
+        .map(|home| scan_quarantined_in(&home.join("Downloads")))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quarantine_value() {
+        let info = parse_quarantine_value(b"0083;5f3e1a2b;Safari;9A3C1E2F-0000-4000-8000-000000000000");
+        assert_eq!(info.downloaded_at, Some(0x5f3e1a2b));
+        assert_eq!(info.agent.as_deref(), Some("Safari"));
+
+        let partial = parse_quarantine_value(b"0081;zzzz;;");
+        assert_eq!(partial, QuarantineInfo { downloaded_at: None, agent: None });
+    }
+
+    #[test]
+    fn test_parse_where_froms() {
+        let urls = vec![
+            "https://example.com/file.dmg".to_string(),
+            "https://example.com/download".to_string(),
+        ];
+        let mut buffer = Vec::new();
+        plist::to_writer_binary(&mut buffer, &urls).unwrap();
+
+        assert_eq!(parse_where_froms(&buffer), urls);
+        assert!(parse_where_froms(b"garbage").is_empty());
+    }
+
+    #[test]
+    fn test_scan_quarantined_in_skips_plain_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("plain.txt"), "x").unwrap();
+        assert!(scan_quarantined_in(temp_dir.path()).is_empty());
+    }
+}
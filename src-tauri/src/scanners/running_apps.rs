@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::process::run_command_with_timeout;
+
+/// Prefix of the error returned when data belongs to an app that is running
+pub const APP_IS_RUNNING: &str = "AppIsRunning";
+
+/// How long to wait for `lsappinfo`
+const LSAPPINFO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An application currently running in the user's session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunningApp {
+    pub name: String,
+    pub bundle_id: String, // Empty for processes without a bundle
+}
+
+/// Parse the records printed by `lsappinfo list`, e.g.
+/// ` 12) "Slack" ASN:0x0-0x5e05e:` followed by `bundleID="com.tinyspeck.slackmacgap"`
+fn parse_lsappinfo_list(output: &str) -> Vec<RunningApp> {
+    let mut apps: Vec<RunningApp> = Vec::new();
+    for line in output.lines().map(str::trim) {
+        if line.contains(" ASN:") && line.ends_with(':') {
+            if let Some((_, rest)) = line.split_once('"') {
+                if let Some((name, _)) = rest.split_once('"') {
+                    apps.push(RunningApp {
+                        name: name.to_string(),
+                        bundle_id: String::new(),
+                    });
+                }
+            }
+        } else if let Some(id) = line.strip_prefix("bundleID=\"").and_then(|v| v.strip_suffix('"')) {
+            if let Some(app) = apps.last_mut() {
+                app.bundle_id = id.to_string();
+            }
+        }
+    }
+    apps
+}
+
+/// Apps running in the current session, via `lsappinfo`. Empty if it can't be run.
+pub fn running_apps() -> Vec<RunningApp> {
+    match run_command_with_timeout("lsappinfo", ["list"], LSAPPINFO_TIMEOUT) {
+        Ok(output) if output.status.success() => parse_lsappinfo_list(&String::from_utf8_lossy(&output.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether an app with `bundle_id` is running in `running`
+pub fn is_running_in(bundle_id: &str, running: &[RunningApp]) -> bool {
+    !bundle_id.is_empty() && running.iter().any(|app| app.bundle_id.eq_ignore_ascii_case(bundle_id))
+}
+
+/// Whether an app with `bundle_id` is running
+pub fn is_app_running(bundle_id: &str) -> bool {
+    is_running_in(bundle_id, &running_apps())
+}
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace([' ', '-', '_'], "")
+}
+
+/// Whether a Library data folder or file called `name` belongs to `app`: it is
+/// named after the app's bundle ID (or a helper ID under it), or after the app
+fn belongs_to(name: &str, app: &RunningApp) -> bool {
+    let name = name.to_lowercase();
+    let name = name
+        .strip_suffix(".plist")
+        .or_else(|| name.strip_suffix(".savedstate"))
+        .unwrap_or(&name);
+    let bundle_id = app.bundle_id.to_lowercase();
+    if !bundle_id.is_empty() && (name == bundle_id || name.starts_with(&format!("{}.", bundle_id))) {
+        return true;
+    }
+    !app.name.is_empty() && normalize(name) == normalize(&app.name)
+}
+
+/// The running app whose data `path` is, judged by its file name
+pub fn running_app_using<'a>(path: &Path, running: &'a [RunningApp]) -> Option<&'a RunningApp> {
+    let name = path.file_name()?.to_string_lossy();
+    running.iter().find(|app| belongs_to(&name, app))
+}
+
+/// Refuse to touch `path` while the app it belongs to is running
+pub fn ensure_not_running(path: &str, running: &[RunningApp]) -> Result<(), String> {
+    match running_app_using(Path::new(path), running) {
+        Some(app) => Err(format!("{}: {} is running; quit it before deleting {}", APP_IS_RUNNING, app.name, path)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, bundle_id: &str) -> RunningApp {
+        RunningApp {
+            name: name.to_string(),
+            bundle_id: bundle_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_lsappinfo_list() {
+        let output = r#"
+ 1) "loginwindow" ASN:0x0-0x1001:
+    bundleID="com.apple.loginwindow"
+    bundle path="/System/Library/CoreServices/loginwindow.app"
+12) "Visual Studio Code" ASN:0x0-0x5e05e:
+    bundleID="com.microsoft.VSCode"
+13) "node" ASN:0x0-0x6f06f:
+    bundleID=[ NULL ]
+"#;
+        assert_eq!(
+            parse_lsappinfo_list(output),
+            vec![
+                app("loginwindow", "com.apple.loginwindow"),
+                app("Visual Studio Code", "com.microsoft.VSCode"),
+                app("node", ""),
+            ]
+        );
+        assert!(parse_lsappinfo_list("").is_empty());
+    }
+
+    #[test]
+    fn test_is_running_in() {
+        let running = [app("Visual Studio Code", "com.microsoft.VSCode"), app("node", "")];
+        assert!(is_running_in("com.microsoft.vscode", &running));
+        assert!(!is_running_in("com.microsoft.Word", &running));
+        assert!(!is_running_in("", &running));
+    }
+
+    #[test]
+    fn test_ensure_not_running() {
+        let running = [app("Visual Studio Code", "com.microsoft.VSCode")];
+        let library = Path::new("/Users/me/Library");
+        let check = |path: &Path| ensure_not_running(&path.to_string_lossy(), &running);
+
+        assert!(check(&library.join("Caches/com.microsoft.VSCode")).unwrap_err().starts_with(APP_IS_RUNNING));
+        assert!(check(&library.join("Caches/com.microsoft.VSCode.ShipIt")).is_err());
+        assert!(check(&library.join("Preferences/com.microsoft.VSCode.plist")).is_err());
+        assert!(check(&library.join("Application Support/Visual Studio Code")).is_err());
+        assert!(check(&library.join("Application Support/VisualStudioCode")).is_err());
+        assert!(check(&library.join("Caches/com.microsoft.VSCodeInsiders")).is_ok());
+        assert!(check(&library.join("Caches/com.apple.Safari")).is_ok());
+    }
+}
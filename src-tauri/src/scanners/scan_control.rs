@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+
+/// Run state of a scan that the frontend can pause, resume or cancel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// Shared pause/cancel flag for one running scan. Scanners call
+/// `checkpoint` between entries; it blocks while the scan is paused.
+#[derive(Debug)]
+pub struct ScanControl {
+    state: Mutex<ScanState>,
+    changed: Condvar,
+}
+
+impl Default for ScanControl {
+    fn default() -> Self {
+        ScanControl {
+            state: Mutex::new(ScanState::Running),
+            changed: Condvar::new(),
+        }
+    }
+}
+
+impl ScanControl {
+    fn set_state(&self, state: ScanState) {
+        if let Ok(mut current) = self.state.lock() {
+            // A cancelled scan stays cancelled
+            if *current != ScanState::Cancelled {
+                *current = state;
+            }
+        }
+        self.changed.notify_all();
+    }
+
+    /// Pause the scan at its next checkpoint
+    pub fn pause(&self) {
+        self.set_state(ScanState::Paused);
+    }
+
+    /// Resume a paused scan
+    pub fn resume(&self) {
+        self.set_state(ScanState::Running);
+    }
+
+    /// Stop the scan, waking it if it is paused
+    pub fn cancel(&self) {
+        self.set_state(ScanState::Cancelled);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().map(|s| *s == ScanState::Cancelled).unwrap_or(false)
+    }
+
+    /// Block without using CPU while the scan is paused.
+    /// Returns false once the scan has been cancelled.
+    pub fn checkpoint(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        while *state == ScanState::Paused {
+            state = match self.changed.wait(state) {
+                Ok(state) => state,
+                Err(_) => return true,
+            };
+        }
+        *state != ScanState::Cancelled
+    }
+}
+
+/// Controls for the scans currently running, keyed by the frontend's scan ID
+fn registry() -> &'static Mutex<HashMap<String, Arc<ScanControl>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Arc<ScanControl>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Mutex::default)
+}
+
+/// A scan's entry in the registry, removed again when dropped
+pub struct ScanRegistration {
+    scan_id: String,
+    control: Arc<ScanControl>,
+}
+
+impl ScanRegistration {
+    pub fn control(&self) -> Arc<ScanControl> {
+        Arc::clone(&self.control)
+    }
+}
+
+impl Drop for ScanRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut scans) = registry().lock() {
+            // Only remove our own entry in case the ID was reused
+            if scans.get(&self.scan_id).is_some_and(|c| Arc::ptr_eq(c, &self.control)) {
+                scans.remove(&self.scan_id);
+            }
+        }
+    }
+}
+
+/// Register a new scan under `scan_id`, replacing any stale entry
+pub fn register(scan_id: &str) -> ScanRegistration {
+    let control = Arc::new(ScanControl::default());
+    if let Ok(mut scans) = registry().lock() {
+        scans.insert(scan_id.to_string(), Arc::clone(&control));
+    }
+    ScanRegistration {
+        scan_id: scan_id.to_string(),
+        control,
+    }
+}
+
+/// Look up the control for a running scan
+fn find(scan_id: &str) -> Result<Arc<ScanControl>, String> {
+    registry()
+        .lock()
+        .ok()
+        .and_then(|scans| scans.get(scan_id).cloned())
+        .ok_or_else(|| format!("No running scan with ID '{}'", scan_id))
+}
+
+/// Pause the scan registered under `scan_id`
+pub fn pause_scan(scan_id: &str) -> Result<(), String> {
+    find(scan_id).map(|control| control.pause())
+}
+
+/// Resume the scan registered under `scan_id`
+pub fn resume_scan(scan_id: &str) -> Result<(), String> {
+    find(scan_id).map(|control| control.resume())
+}
+
+/// Cancel the scan registered under `scan_id`, even while it is paused
+pub fn cancel_scan(scan_id: &str) -> Result<(), String> {
+    find(scan_id).map(|control| control.cancel())
+}
+
+/// Prefix of the error returned when an identical scan is already in flight,
+/// so the frontend can recognize it
+pub const SCAN_ALREADY_RUNNING: &str = "ScanAlreadyRunning";
+
+/// Identifies a scan in flight: its kind and canonical root
+type ScanKey = (String, String);
+
+/// Scans in flight, with their scan ID if they have one
+fn active_scans() -> &'static Mutex<HashMap<ScanKey, Option<String>>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<ScanKey, Option<String>>>> = OnceLock::new();
+    ACTIVE.get_or_init(Mutex::default)
+}
+
+/// A scan marked as in flight, released again when dropped. Holds the
+/// scan's control registration when it was started with a scan ID.
+pub struct ActiveScan {
+    key: ScanKey,
+    registration: Option<ScanRegistration>,
+}
+
+impl ActiveScan {
+    /// The scan's pause/cancel control, if it has a scan ID
+    pub fn control(&self) -> Option<Arc<ScanControl>> {
+        self.registration.as_ref().map(|r| r.control())
+    }
+}
+
+impl Drop for ActiveScan {
+    fn drop(&mut self) {
+        if let Ok(mut active) = active_scans().lock() {
+            active.remove(&self.key);
+        }
+    }
+}
+
+/// Mark a `kind` scan of `root` as running, registering `scan_id` for pause
+/// and cancel. Fails with a `ScanAlreadyRunning` error naming the running
+/// scan's ID when the same kind of scan over the same root is already in flight.
+pub fn begin_scan(kind: &str, root: &str, scan_id: Option<&str>) -> Result<ActiveScan, String> {
+    let root = std::fs::canonicalize(root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| root.to_string());
+    let key = (kind.to_string(), root);
+
+    let mut active = active_scans().lock().map_err(|e| e.to_string())?;
+    if let Some(running_id) = active.get(&key) {
+        return Err(match running_id {
+            Some(id) => format!("{}: a {} scan of {} is already running (scan ID '{}')", SCAN_ALREADY_RUNNING, kind, key.1, id),
+            None => format!("{}: a {} scan of {} is already running", SCAN_ALREADY_RUNNING, kind, key.1),
+        });
+    }
+    active.insert(key.clone(), scan_id.map(str::to_string));
+    Ok(ActiveScan {
+        key,
+        registration: scan_id.map(register),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_checkpoint_blocks_while_paused() {
+        let control = Arc::new(ScanControl::default());
+        control.pause();
+        let passed = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let control = Arc::clone(&control);
+            let passed = Arc::clone(&passed);
+            thread::spawn(move || {
+                let running = control.checkpoint();
+                passed.store(true, Ordering::SeqCst);
+                running
+            })
+        };
+
+        thread::sleep(Duration::from_millis(50));
+        assert!(!passed.load(Ordering::SeqCst));
+
+        control.resume();
+        assert!(handle.join().unwrap());
+        assert!(passed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_cancel_wakes_paused_scan() {
+        let control = Arc::new(ScanControl::default());
+        control.pause();
+
+        let handle = {
+            let control = Arc::clone(&control);
+            thread::spawn(move || control.checkpoint())
+        };
+
+        thread::sleep(Duration::from_millis(20));
+        control.cancel();
+        assert!(!handle.join().unwrap());
+
+        // Cancelling is final
+        control.resume();
+        assert!(control.is_cancelled());
+        assert!(!control.checkpoint());
+    }
+
+    #[test]
+    fn test_begin_scan_rejects_overlapping_scans() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+
+        let first = begin_scan("test_kind", &root, Some("test-overlap-scan")).unwrap();
+        assert!(first.control().is_some());
+        let error = begin_scan("test_kind", &format!("{}/.", root), None).err().unwrap();
+        assert!(error.starts_with(SCAN_ALREADY_RUNNING));
+        assert!(error.contains("test-overlap-scan"));
+
+        // A different kind of scan over the same root may run alongside
+        assert!(begin_scan("other_kind", &root, None).unwrap().control().is_none());
+
+        drop(first);
+        assert!(begin_scan("test_kind", &root, None).is_ok());
+        assert!(cancel_scan("test-overlap-scan").is_err());
+    }
+
+    #[test]
+    fn test_registry() {
+        let registration = register("test-registry-scan");
+        pause_scan("test-registry-scan").unwrap();
+        assert_eq!(*registration.control.state.lock().unwrap(), ScanState::Paused);
+        resume_scan("test-registry-scan").unwrap();
+        cancel_scan("test-registry-scan").unwrap();
+        assert!(registration.control().is_cancelled());
+
+        drop(registration);
+        assert!(pause_scan("test-registry-scan").is_err());
+    }
+}
@@ -0,0 +1,543 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use walkdir::{DirEntry, WalkDir};
+
+use super::scan_control::ScanControl;
+
+/// Folders scanned at once by multi-folder scans unless settings say otherwise
+pub const DEFAULT_SCAN_CONCURRENCY: usize = 2;
+
+/// `f_flags` bits from <sys/mount.h>
+const MNT_RDONLY: u32 = 0x0000_0001;
+const MNT_LOCAL: u32 = 0x0000_1000;
+
+/// Options controlling how a scanner walks a directory tree
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Maximum depth to descend, relative to the scan root.
+    /// Depth 1 means immediate children only; `None` means unlimited.
+    pub max_depth: Option<usize>,
+    /// Glob patterns matched against absolute paths; matching entries are
+    /// skipped and matching directories are not descended into.
+    pub exclude: Option<GlobSet>,
+    /// Follow symbolic links while walking. Symlink loops are detected by
+    /// `WalkDir`, logged, and skipped.
+    pub follow_symlinks: bool,
+    /// Don't descend into network or read-only filesystems mounted below
+    /// the scan root. The root itself is always scanned.
+    pub skip_network_mounts: bool,
+    /// Don't descend into directories on the sealed, read-only System volume.
+    /// On APFS, paths like `/usr/bin` or `/System` live there while firmlinked
+    /// paths such as `/Users` and `/Applications` map to the Data volume.
+    /// The root itself is always scanned.
+    pub skip_system_volume: bool,
+    /// Include files whose name starts with `.`. Hidden files are skipped by default.
+    pub include_hidden: bool,
+    /// Add each file's extended attribute sizes when sizing. Closer to what
+    /// `du` and Finder show for some files, but much slower; off by default.
+    pub include_xattrs: bool,
+    /// Look inside each cache folder for zero-length `.db`/`.sqlite` files, a
+    /// sign of a truncated or corrupt cache database. Cache scans only; off by default.
+    pub check_cache_databases: bool,
+    /// Pause/cancel control for the scan using these options. Walks block
+    /// while it is paused and stop early once it is cancelled.
+    pub control: Option<Arc<ScanControl>>,
+    /// Device ID of the boot System volume, if there is a separate one
+    system_device: Option<u64>,
+    /// Mount points skipped during walks with these options
+    skipped_mounts: Arc<Mutex<Vec<String>>>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            max_depth: None,
+            exclude: None,
+            follow_symlinks: false,
+            skip_network_mounts: true,
+            skip_system_volume: true,
+            include_xattrs: false,
+            check_cache_databases: false,
+            include_hidden: false,
+            control: None,
+            system_device: system_volume_device(),
+            skipped_mounts: Arc::default(),
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Options with only a depth limit set
+    pub fn with_max_depth(max_depth: Option<usize>) -> Self {
+        ScanOptions {
+            max_depth,
+            ..Default::default()
+        }
+    }
+
+    /// Add exclusion globs. A leading `~/` is expanded to the home directory.
+    /// Returns an error naming the first invalid pattern.
+    pub fn with_exclude(mut self, patterns: &[String]) -> Result<Self, String> {
+        if patterns.is_empty() {
+            return Ok(self);
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let expanded = expand_home(pattern);
+            let glob = Glob::new(&expanded)
+                .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+            builder.add(glob);
+        }
+
+        self.exclude = Some(
+            builder
+                .build()
+                .map_err(|e| format!("Invalid exclude patterns: {}", e))?,
+        );
+        Ok(self)
+    }
+
+    /// Enable or disable following symbolic links
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Enable or disable skipping network and read-only mounts
+    pub fn with_skip_network_mounts(mut self, skip_network_mounts: bool) -> Self {
+        self.skip_network_mounts = skip_network_mounts;
+        self
+    }
+
+    /// Enable or disable counting extended attributes in sizes
+    pub fn with_include_xattrs(mut self, include_xattrs: bool) -> Self {
+        self.include_xattrs = include_xattrs;
+        self
+    }
+
+    /// Enable or disable checking cache folders for empty database files
+    pub fn with_check_cache_databases(mut self, check_cache_databases: bool) -> Self {
+        self.check_cache_databases = check_cache_databases;
+        self
+    }
+
+    /// Include or skip hidden files
+    pub fn with_include_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+
+    /// Whether a file should be skipped as hidden under these options
+    pub fn skips_hidden(&self, path: &Path) -> bool {
+        !self.include_hidden
+            && path
+                .file_name()
+                .map(|s| s.to_string_lossy().starts_with('.'))
+                .unwrap_or(false)
+    }
+
+    /// Attach a pause/cancel control
+    pub fn with_control(mut self, control: Option<Arc<ScanControl>>) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Wait while the scan is paused. Returns false if it has been cancelled.
+    pub fn checkpoint(&self) -> bool {
+        self.control.as_ref().map(|c| c.checkpoint()).unwrap_or(true)
+    }
+
+    /// Whether the scan was cancelled through its control
+    pub fn is_cancelled(&self) -> bool {
+        self.control.as_ref().map(|c| c.is_cancelled()).unwrap_or(false)
+    }
+
+    /// Mount points that were skipped so far
+    pub fn skipped_mounts(&self) -> Vec<String> {
+        self.skipped_mounts
+            .lock()
+            .map(|mounts| mounts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Check whether a path matches one of the exclusion globs
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude
+            .as_ref()
+            .map(|set| set.is_match(path))
+            .unwrap_or(false)
+    }
+
+    /// Build a `WalkDir` for `root` configured with these options
+    fn walker<P: AsRef<Path>>(&self, root: P) -> WalkDir {
+        let mut walker = WalkDir::new(root).follow_links(self.follow_symlinks);
+        if let Some(depth) = self.max_depth {
+            walker = walker.max_depth(depth);
+        }
+        walker
+    }
+
+    /// Remember a path that a walk did not descend into
+    fn record_skipped(&self, path: &Path) {
+        if let Ok(mut mounts) = self.skipped_mounts.lock() {
+            mounts.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    /// Whether `entry` is a directory below the root that lives on the System volume.
+    /// This is checked before the mount check since a walk from `/` starts on the
+    /// System volume itself, so its system directories share the root's device.
+    fn is_system_volume_dir(&self, entry: &DirEntry) -> bool {
+        if !self.skip_system_volume || entry.depth() == 0 || !entry.file_type().is_dir() {
+            return false;
+        }
+        let (Some(system_device), Some(device)) = (self.system_device, entry_device(entry)) else {
+            return false;
+        };
+        if device != system_device {
+            return false;
+        }
+
+        log::info!("Skipping System volume path: {}", entry.path().display());
+        self.record_skipped(entry.path());
+        true
+    }
+
+    /// Whether `entry` is a directory on a different, skippable filesystem than the root.
+    /// Results are remembered per device so each mount is only checked once.
+    fn is_skipped_mount(&self, entry: &DirEntry, root_device: Option<u64>, checked: &mut HashMap<u64, bool>) -> bool {
+        if !self.skip_network_mounts || entry.depth() == 0 || !entry.file_type().is_dir() {
+            return false;
+        }
+        let (Some(root_device), Some(device)) = (root_device, entry_device(entry)) else {
+            return false;
+        };
+        if device == root_device {
+            return false;
+        }
+
+        if let Some(&skip) = checked.get(&device) {
+            return skip;
+        }
+        let skip = mount_flags(entry.path()).map(is_remote_or_read_only).unwrap_or(false);
+        checked.insert(device, skip);
+        if skip {
+            log::info!("Skipping network or read-only mount: {}", entry.path().display());
+            self.record_skipped(entry.path());
+        }
+        skip
+    }
+
+    /// Walk `root`, pruning excluded subtrees, skipped mounts and unreadable entries.
+    /// Pauses between entries while the control is paused and ends early on cancel.
+    pub fn walk<'a, P: AsRef<Path>>(&'a self, root: P) -> impl Iterator<Item = DirEntry> + 'a {
+        let root_device = path_device(root.as_ref());
+        let mut checked_devices = HashMap::new();
+        self.walker(root)
+            .into_iter()
+            .filter_entry(move |e| {
+                !self.is_excluded(e.path())
+                    && !self.is_system_volume_dir(e)
+                    && !self.is_skipped_mount(e, root_device, &mut checked_devices)
+            })
+            .filter_map(|e| match e {
+                Ok(entry) => Some(entry),
+                Err(err) => {
+                    if err.loop_ancestor().is_some() {
+                        log::warn!("Skipping symlink loop: {}", err);
+                    }
+                    None
+                }
+            })
+            .take_while(move |_| self.checkpoint())
+    }
+}
+
+/// Device ID of the filesystem holding `path`
+fn path_device(path: &Path) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(path).ok().map(|m| m.dev())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+fn entry_device(entry: &DirEntry) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        entry.metadata().ok().map(|m| m.dev())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = entry;
+        None
+    }
+}
+
+/// Device ID of the sealed System volume that `/System` lives on.
+/// Only macOS splits the boot disk into System and Data volumes.
+fn system_volume_device() -> Option<u64> {
+    static DEVICE: OnceLock<Option<u64>> = OnceLock::new();
+    *DEVICE.get_or_init(|| {
+        if cfg!(target_os = "macos") {
+            path_device(Path::new("/System"))
+        } else {
+            None
+        }
+    })
+}
+
+/// Mount flags of the filesystem holding `path`, via statfs
+#[cfg(target_os = "macos")]
+fn mount_flags(path: &Path) -> Option<u32> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } == 0 {
+        Some(stat.f_flags)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn mount_flags(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Whether `path` lives on a filesystem mounted read-only
+pub fn is_read_only_volume(path: &Path) -> bool {
+    mount_flags(path).is_some_and(|flags| flags & MNT_RDONLY != 0)
+}
+
+/// A mount is skipped if it is not local (SMB, AFP, NFS...) or is read-only
+fn is_remote_or_read_only(flags: u32) -> bool {
+    flags & MNT_LOCAL == 0 || flags & MNT_RDONLY != 0
+}
+
+/// Run `scan` on each of `roots` using at most `max_concurrency` threads,
+/// returning the results in the order of `roots` whichever finishes first.
+/// Keeping concurrency low avoids thrashing spinning disks.
+pub fn scan_roots_concurrently<T, F>(roots: &[PathBuf], max_concurrency: usize, scan: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&Path) -> T + Sync,
+{
+    let workers = max_concurrency.clamp(1, roots.len().max(1));
+    let queue = Mutex::new(roots.iter().enumerate());
+    let next = || queue.lock().ok().and_then(|mut q| q.next());
+
+    let mut results: Vec<(usize, T)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    while let Some((i, root)) = next() {
+                        done.push((i, scan(root)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    });
+
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Expand a leading `~/` to the user's home directory
+fn expand_home(pattern: &str) -> String {
+    if let Some(rest) = pattern.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest).to_string_lossy().to_string();
+        }
+    }
+    pattern.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_scan_roots_concurrently_keeps_root_order() {
+        let roots: Vec<PathBuf> = (0..6).map(|i| PathBuf::from(format!("/root-{}", i))).collect();
+        let results = scan_roots_concurrently(&roots, 3, |root| {
+            // Earlier roots finish last
+            let index: u64 = root.to_string_lossy().trim_start_matches("/root-").parse().unwrap();
+            thread::sleep(std::time::Duration::from_millis(5 * (6 - index)));
+            root.to_path_buf()
+        });
+        assert_eq!(results, roots);
+        assert!(scan_roots_concurrently(&[], 0, |root| root.to_path_buf()).is_empty());
+    }
+
+    #[test]
+    fn test_walker_max_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join("top.txt"), "x").unwrap();
+        fs::write(nested.join("deep.txt"), "x").unwrap();
+
+        let count_files = |options: &ScanOptions| {
+            options
+                .walk(temp_dir.path())
+                .filter(|e| e.file_type().is_file())
+                .count()
+        };
+
+        assert_eq!(count_files(&ScanOptions::default()), 2);
+        assert_eq!(count_files(&ScanOptions::with_max_depth(Some(1))), 1);
+        assert_eq!(count_files(&ScanOptions::with_max_depth(Some(3))), 2);
+    }
+
+    #[test]
+    fn test_walk_prunes_excluded_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skipped = temp_dir.path().join("Projects");
+        fs::create_dir_all(skipped.join("deep")).unwrap();
+        fs::write(skipped.join("deep").join("a.txt"), "x").unwrap();
+        fs::write(temp_dir.path().join("kept.txt"), "x").unwrap();
+
+        let options = ScanOptions::default()
+            .with_exclude(&["**/Projects".to_string()])
+            .unwrap();
+        let paths: Vec<_> = options.walk(temp_dir.path()).map(|e| e.into_path()).collect();
+
+        assert!(paths.iter().any(|p| p.ends_with("kept.txt")));
+        assert!(!paths.iter().any(|p| p.starts_with(&skipped)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_follow_symlinks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("external");
+        let root = temp_dir.path().join("root");
+        fs::create_dir(&target).unwrap();
+        fs::create_dir(&root).unwrap();
+        fs::write(target.join("movie.mp4"), "x").unwrap();
+        std::os::unix::fs::symlink(&target, root.join("linked")).unwrap();
+
+        let count_files = |options: &ScanOptions| {
+            options.walk(&root).filter(|e| e.file_type().is_file()).count()
+        };
+
+        assert_eq!(count_files(&ScanOptions::default()), 0);
+        assert_eq!(count_files(&ScanOptions::default().with_follow_symlinks(true)), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_follow_symlinks_loop_terminates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("root");
+        fs::create_dir(&root).unwrap();
+        fs::write(root.join("file.txt"), "x").unwrap();
+        // Self-referential link back to the root
+        std::os::unix::fs::symlink(&root, root.join("loop")).unwrap();
+
+        let options = ScanOptions::default().with_follow_symlinks(true);
+        let files = options.walk(&root).filter(|e| e.file_type().is_file()).count();
+        assert_eq!(files, 1);
+    }
+
+    #[test]
+    fn test_is_remote_or_read_only() {
+        assert!(!is_remote_or_read_only(MNT_LOCAL));
+        assert!(is_remote_or_read_only(MNT_LOCAL | MNT_RDONLY));
+        assert!(is_remote_or_read_only(0));
+    }
+
+    #[test]
+    fn test_skip_network_mounts_defaults_on() {
+        let options = ScanOptions::with_max_depth(Some(2));
+        assert!(options.skip_network_mounts);
+        assert!(!options.with_skip_network_mounts(false).skip_network_mounts);
+    }
+
+    #[test]
+    fn test_walk_same_device_not_skipped() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        fs::write(temp_dir.path().join("sub").join("a.txt"), "x").unwrap();
+
+        let options = ScanOptions::default();
+        let files = options.walk(temp_dir.path()).filter(|e| e.file_type().is_file()).count();
+        assert_eq!(files, 1);
+        assert!(options.skipped_mounts().is_empty());
+    }
+
+    #[test]
+    fn test_walk_stops_when_cancelled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "x").unwrap();
+
+        let control = Arc::new(ScanControl::default());
+        let options = ScanOptions::default().with_control(Some(Arc::clone(&control)));
+        assert_eq!(options.walk(temp_dir.path()).count(), 2);
+
+        control.cancel();
+        assert_eq!(options.walk(temp_dir.path()).count(), 0);
+        assert!(options.is_cancelled());
+    }
+
+    #[test]
+    fn test_walk_skips_system_volume_dirs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bin = temp_dir.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        fs::write(bin.join("ls"), "x").unwrap();
+        fs::write(temp_dir.path().join("top.txt"), "x").unwrap();
+
+        // Treat the temp dir's filesystem as the System volume
+        let options = ScanOptions {
+            system_device: path_device(temp_dir.path()),
+            ..Default::default()
+        };
+        let files: Vec<_> = options
+            .walk(temp_dir.path())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+        assert_eq!(files, vec![temp_dir.path().join("top.txt")]);
+        assert_eq!(options.skipped_mounts(), vec![bin.to_string_lossy().to_string()]);
+
+        let unskipped = ScanOptions {
+            skip_system_volume: false,
+            ..options.clone()
+        };
+        assert_eq!(unskipped.walk(temp_dir.path()).filter(|e| e.file_type().is_file()).count(), 2);
+    }
+
+    #[test]
+    fn test_with_exclude_invalid_pattern() {
+        let result = ScanOptions::default().with_exclude(&["[unclosed".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~/Documents"), home.join("Documents").to_string_lossy());
+        assert_eq!(expand_home("**/node_modules"), "**/node_modules");
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::disk_size::get_directory_size;
+
+/// A system-wide folder outside the home directory that can collect junk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedJunkLocation {
+    pub name: String,
+    pub path: String,
+    pub size: u64, // On disk; only what this user can read is counted
+    pub description: String,
+    pub exists: bool,
+    pub safe_to_clean: bool, // False means the folder may hold data someone still needs
+    pub requires_admin: bool,
+}
+
+/// Shared locations checked by `scan_shared_junk`
+/// (name, path, description, safe_to_clean, requires_admin)
+const SHARED_JUNK_LOCATIONS: &[(&str, &str, &str, bool, bool)] = &[
+    (
+        "Shared Folder",
+        "/Users/Shared",
+        "Files shared between all users; review before deleting anything",
+        false,
+        false,
+    ),
+    (
+        "System Temporary Files",
+        "/private/var/tmp",
+        "Temporary files kept across restarts; files from other users need an administrator",
+        true,
+        true,
+    ),
+    (
+        "System Logs",
+        "/Library/Logs",
+        "Logs written by system services and installers",
+        true,
+        true,
+    ),
+];
+
+/// Size one shared location
+fn shared_junk_entry(name: &str, path: &Path, description: &str, safe_to_clean: bool, requires_admin: bool) -> SharedJunkLocation {
+    let exists = path.is_dir();
+    SharedJunkLocation {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        size: if exists { get_directory_size(path) } else { 0 },
+        description: description.to_string(),
+        exists,
+        safe_to_clean,
+        requires_admin,
+    }
+}
+
+/// Report the size of system-wide junk locations the home-folder scans never see, largest first
+pub fn scan_shared_junk() -> Vec<SharedJunkLocation> {
+    let mut locations: Vec<SharedJunkLocation> = SHARED_JUNK_LOCATIONS
+        .iter()
+        .map(|&(name, path, description, safe, admin)| shared_junk_entry(name, Path::new(path), description, safe, admin))
+        .collect();
+    locations.sort_by(|a, b| b.size.cmp(&a.size));
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_shared_junk_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("install.log"), vec![1u8; 10_000]).unwrap();
+
+        let entry = shared_junk_entry("Logs", temp_dir.path(), "Logs", true, true);
+        assert!(entry.exists);
+        assert!(entry.size >= 10_000);
+        assert!(entry.requires_admin);
+
+        let missing = shared_junk_entry("Missing", &temp_dir.path().join("missing"), "", true, false);
+        assert!(!missing.exists);
+        assert_eq!(missing.size, 0);
+    }
+
+    #[test]
+    fn test_shared_folder_is_not_marked_safe() {
+        let shared = scan_shared_junk().into_iter().find(|l| l.path == "/Users/Shared").unwrap();
+        assert!(!shared.safe_to_clean);
+    }
+}
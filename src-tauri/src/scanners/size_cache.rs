@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::disk_size::{DirectorySizes, DEFAULT_MAX_ENTRIES};
+
+/// File under the app support dir holding cached directory sizes
+const SIZE_CACHE_FILE: &str = "size_cache.json";
+
+/// A computed size together with the tree's latest mtime when it was computed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+struct CachedSize {
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    size: u64,
+    #[serde(default)]
+    apparent: Option<u64>, // Only recorded by `cached_directory_sizes`
+}
+
+/// Directory sizes keyed by path, invalidated when anything in the tree changes.
+///
+/// An entry is checked against the latest mtime of the directory and everything
+/// below it, so files growing in place and changes deep in the tree are both
+/// noticed. Checking still stats every entry, but skips reading extended
+/// attributes and summing sizes.
+#[derive(Debug, Default)]
+pub struct SizeCache {
+    file: Option<PathBuf>,
+    entries: HashMap<String, CachedSize>,
+    dirty: bool,
+}
+
+/// A modification time as (seconds, nanoseconds) since the epoch
+fn since_epoch(modified: SystemTime) -> Option<(u64, u32)> {
+    let since_epoch = modified.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+/// Latest modification time of `path` and everything below it. Symlinks are
+/// not followed. Trees past `DEFAULT_MAX_ENTRIES` entries give None, since
+/// their sizes are partial and never cached.
+fn tree_mtime_of(path: &Path) -> Option<(u64, u32)> {
+    let mut latest = since_epoch(std::fs::metadata(path).ok()?.modified().ok()?)?;
+    for (visited, entry) in WalkDir::new(path).min_depth(1).into_iter().filter_map(|e| e.ok()).enumerate() {
+        if visited >= DEFAULT_MAX_ENTRIES {
+            return None;
+        }
+        if let Some(modified) = entry.metadata().ok().and_then(|m| m.modified().ok()).and_then(since_epoch) {
+            latest = latest.max(modified);
+        }
+    }
+    Some(latest)
+}
+
+impl SizeCache {
+    /// Load a cache backed by `file`, starting empty if it is missing or unreadable
+    pub fn load(file: PathBuf) -> Self {
+        let entries = crate::storage::read_json(&file).unwrap_or_default();
+        SizeCache {
+            file: Some(file),
+            entries,
+            dirty: false,
+        }
+    }
+
+    /// Cached entry for `path` if nothing in the tree changed since it was recorded
+    fn lookup_entry(&self, path: &Path) -> Option<CachedSize> {
+        let cached = self.entries.get(path.to_string_lossy().as_ref())?;
+        let (secs, nanos) = tree_mtime_of(path)?;
+        (cached.mtime_secs == secs && cached.mtime_nanos == nanos).then_some(*cached)
+    }
+
+    /// Cached size for `path` if nothing in the tree changed
+    fn lookup(&self, path: &Path) -> Option<u64> {
+        self.lookup_entry(path).map(|c| c.size)
+    }
+
+    /// Cached on-disk and apparent sizes for `path`, if both were recorded
+    fn lookup_sizes(&self, path: &Path) -> Option<DirectorySizes> {
+        let entry = self.lookup_entry(path)?;
+        Some(DirectorySizes {
+            on_disk: entry.size,
+            apparent: entry.apparent?,
+        })
+    }
+
+    /// Record a freshly computed size for `path`
+    fn insert(&mut self, path: &Path, size: u64) {
+        self.insert_entry(path, size, None);
+    }
+
+    fn insert_entry(&mut self, path: &Path, size: u64, apparent: Option<u64>) {
+        if let Some((mtime_secs, mtime_nanos)) = tree_mtime_of(path) {
+            self.entries.insert(
+                path.to_string_lossy().to_string(),
+                CachedSize { mtime_secs, mtime_nanos, size, apparent },
+            );
+            self.dirty = true;
+        }
+    }
+
+    /// Write the cache to its backing file if anything changed
+    pub fn save(&mut self) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(file) = &self.file {
+            crate::storage::write_json_atomic(file, &self.entries)?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Drop every cached entry and remove the backing file
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.entries.clear();
+        self.dirty = false;
+        if let Some(file) = &self.file {
+            if file.exists() {
+                std::fs::remove_file(file).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Process-wide cache, loaded lazily from the app support dir
+static GLOBAL_CACHE: Mutex<Option<SizeCache>> = Mutex::new(None);
+
+fn with_global_cache<T>(f: impl FnOnce(&mut SizeCache) -> T) -> T {
+    let mut guard = GLOBAL_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = guard.get_or_insert_with(|| {
+        // Tests stay in memory so they never touch the real app support dir
+        if cfg!(test) {
+            return SizeCache::default();
+        }
+        match crate::storage::app_support_file(SIZE_CACHE_FILE) {
+            Ok(file) => SizeCache::load(file),
+            Err(e) => {
+                log::warn!("Size cache disabled: {}", e);
+                SizeCache::default()
+            }
+        }
+    });
+    f(cache)
+}
+
+/// Size of `path` from the global cache, computing it on a miss. `compute`
+/// may give up by returning None, in which case nothing is cached.
+/// The lock is not held while computing, so concurrent scans don't serialize.
+pub fn try_cached_directory_size<F: FnOnce() -> Option<u64>>(path: &Path, compute: F) -> Option<u64> {
+    if let Some(size) = with_global_cache(|cache| cache.lookup(path)) {
+        return Some(size);
+    }
+    let size = compute()?;
+    with_global_cache(|cache| cache.insert(path, size));
+    Some(size)
+}
+
+/// On-disk and apparent sizes of `path` from the global cache, computing both on a miss
+pub fn cached_directory_sizes<F: FnOnce() -> DirectorySizes>(path: &Path, compute: F) -> DirectorySizes {
+    if let Some(sizes) = with_global_cache(|cache| cache.lookup_sizes(path)) {
+        return sizes;
+    }
+    let sizes = compute();
+    with_global_cache(|cache| cache.insert_entry(path, sizes.on_disk, Some(sizes.apparent)));
+    sizes
+}
+
+/// Persist the global cache after a scan
+pub fn save_global_cache() {
+    if let Err(e) = with_global_cache(|cache| cache.save()) {
+        log::warn!("Failed to save size cache: {}", e);
+    }
+}
+
+/// Clear the global cache and its file on disk
+pub fn clear_global_cache() -> Result<(), String> {
+    with_global_cache(|cache| cache.clear())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use std::time::Duration;
+
+    fn set_mtime(path: &Path, secs: u64) {
+        let dir = fs::File::open(path).unwrap();
+        dir.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)).unwrap();
+    }
+
+    #[test]
+    fn test_cached_size_reused_until_mtime_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        set_mtime(&target, 1_000);
+
+        let mut cache = SizeCache::load(temp_dir.path().join(SIZE_CACHE_FILE));
+        assert_eq!(cache.lookup(&target), None);
+        cache.insert(&target, 10);
+
+        // Unchanged mtime: the cached size is reused
+        assert_eq!(cache.lookup(&target), Some(10));
+
+        // Changed mtime: the entry is stale and must be recomputed
+        set_mtime(&target, 2_000);
+        assert_eq!(cache.lookup(&target), None);
+    }
+
+    #[test]
+    fn test_cached_size_invalidated_by_nested_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("target");
+        let nested = target.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        let file = nested.join("Docker.raw");
+        fs::write(&file, "x").unwrap();
+        for path in [&file, &nested, &target.join("a"), &target] {
+            set_mtime(path, 1_000);
+        }
+
+        let mut cache = SizeCache::default();
+        cache.insert(&target, 10);
+        assert_eq!(cache.lookup(&target), Some(10));
+
+        // A file growing in place leaves every directory mtime alone
+        fs::OpenOptions::new().append(true).open(&file).unwrap().write_all(b"more").unwrap();
+        set_mtime(&file, 2_000);
+        set_mtime(&nested, 1_000);
+        assert_eq!(cache.lookup(&target), None);
+    }
+
+    #[test]
+    fn test_size_cache_persists_and_clears() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join(SIZE_CACHE_FILE);
+        let target = temp_dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+
+        let mut cache = SizeCache::load(file.clone());
+        cache.insert(&target, 42);
+        cache.save().unwrap();
+        assert!(file.exists());
+
+        let mut reloaded = SizeCache::load(file.clone());
+        assert_eq!(reloaded.lookup(&target), Some(42));
+
+        reloaded.clear().unwrap();
+        assert!(!file.exists());
+        assert_eq!(reloaded.lookup(&target), None);
+    }
+
+    #[test]
+    fn test_cached_directory_sizes_needs_apparent_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        let sizes = DirectorySizes { on_disk: 8, apparent: 3 };
+
+        // An entry without an apparent size is recomputed
+        assert_eq!(try_cached_directory_size(&target, || Some(8)), Some(8));
+        assert_eq!(cached_directory_sizes(&target, || sizes), sizes);
+        assert_eq!(cached_directory_sizes(&target, || panic!("should be cached")), sizes);
+        assert_eq!(try_cached_directory_size(&target, || panic!("should be cached")), Some(8));
+    }
+
+    #[test]
+    fn test_try_cached_directory_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(try_cached_directory_size(temp_dir.path(), || None), None);
+        assert_eq!(try_cached_directory_size(temp_dir.path(), || Some(5)), Some(5));
+        assert_eq!(try_cached_directory_size(temp_dir.path(), || panic!("should be cached")), Some(5));
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::disk_size::allocated_size;
+
+/// Files modified more recently than this may still be downloading
+const IN_PROGRESS_WINDOW: Duration = Duration::from_secs(60);
+
+/// A file in Downloads that hasn't been opened or changed in a while
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleDownload {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub last_used: u64, // Unix timestamp of the last access or modification
+}
+
+/// The user's Downloads folder
+pub fn downloads_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Downloads"))
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// When a file was last used: its access time, or its modification time if
+/// that is later or the access time isn't available
+fn last_used(metadata: &fs::Metadata) -> Option<SystemTime> {
+    match (metadata.accessed().ok(), metadata.modified().ok()) {
+        (Some(accessed), Some(modified)) => Some(accessed.max(modified)),
+        (accessed, modified) => accessed.or(modified),
+    }
+}
+
+/// Top-level files in `directory` not used within `days` days of `now`, oldest
+/// first. Hidden files and files modified in the last minute, which may still
+/// be downloading, are skipped.
+pub fn scan_stale_in(directory: &Path, days: u64, now: SystemTime) -> Vec<StaleDownload> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let cutoff = now.checked_sub(Duration::from_secs(days.saturating_mul(24 * 60 * 60))).unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut stale: Vec<StaleDownload> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified = metadata.modified().ok()?;
+            if now.duration_since(modified).map_or(true, |age| age < IN_PROGRESS_WINDOW) {
+                return None;
+            }
+            let used = last_used(&metadata)?;
+            if used > cutoff {
+                return None;
+            }
+            Some(StaleDownload {
+                path: entry.path().to_string_lossy().to_string(),
+                name: entry.file_name().to_string_lossy().to_string(),
+                size: allocated_size(&metadata),
+                last_used: unix_seconds(used),
+            })
+        })
+        .collect();
+
+    stale.sort_by(|a, b| a.last_used.cmp(&b.last_used).then_with(|| a.path.cmp(&b.path)));
+    stale
+}
+
+/// Files in ~/Downloads not used in the last `days` days, oldest first
+pub fn scan_stale_downloads(days: u64) -> Vec<StaleDownload> {
+    downloads_dir()
+        .map(|dir| scan_stale_in(&dir, days, SystemTime::now()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{File, FileTimes};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn write_aged(path: &Path, accessed_days_ago: u64, modified_days_ago: u64) {
+        fs::write(path, vec![1u8; 4096]).unwrap();
+        let now = SystemTime::now();
+        let times = FileTimes::new()
+            .set_accessed(now - DAY * accessed_days_ago as u32)
+            .set_modified(now - DAY * modified_days_ago as u32);
+        File::options().write(true).open(path).unwrap().set_times(times).unwrap();
+    }
+
+    #[test]
+    fn test_scan_stale_in() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_aged(&temp_dir.path().join("old.dmg"), 400, 400);
+        write_aged(&temp_dir.path().join("older.zip"), 500, 500);
+        write_aged(&temp_dir.path().join("opened.pdf"), 2, 400);
+        write_aged(&temp_dir.path().join(".hidden"), 400, 400);
+        fs::write(temp_dir.path().join("downloading.part"), "partial").unwrap();
+        fs::create_dir(temp_dir.path().join("folder")).unwrap();
+
+        let stale = scan_stale_in(temp_dir.path(), 180, SystemTime::now());
+        let names: Vec<&str> = stale.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["older.zip", "old.dmg"]);
+        assert!(stale[0].last_used < stale[1].last_used);
+        assert!(scan_stale_in(temp_dir.path(), u64::MAX, SystemTime::now()).is_empty());
+    }
+
+    #[test]
+    fn test_scan_stale_in_skips_files_being_written() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("fresh.bin"), "data").unwrap();
+
+        assert!(scan_stale_in(temp_dir.path(), 0, SystemTime::now()).is_empty());
+        assert_eq!(scan_stale_in(temp_dir.path(), 0, SystemTime::now() + DAY).len(), 1);
+        assert!(scan_stale_in(&temp_dir.path().join("missing"), 0, SystemTime::now()).is_empty());
+    }
+}
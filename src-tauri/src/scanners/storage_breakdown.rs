@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use super::disk_size::{get_directory_size_bounded, BoundedSize, DEFAULT_MAX_ENTRIES};
+use super::scan_control::ScanControl;
+
+/// A broad slice of the disk shown on the dashboard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StorageBucket {
+    Applications,
+    Documents,
+    Photos,
+    Developer,
+    SystemCaches,
+    Trash,
+}
+
+/// Space used by one bucket
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BucketUsage {
+    pub bucket: StorageBucket,
+    pub size: u64,
+    /// Some folder was too large to walk fully, so `size` is a lower bound
+    pub truncated: bool,
+}
+
+/// Space used per bucket, largest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageBreakdown {
+    pub buckets: Vec<BucketUsage>,
+    pub total_bytes: u64,
+}
+
+/// Folders sized for each bucket under `home`. `developer_roots` are the
+/// developer cache folders, which take precedence over the cache bucket.
+pub fn bucket_roots(home: &Path, developer_roots: Vec<PathBuf>) -> Vec<(StorageBucket, Vec<PathBuf>)> {
+    let library = home.join("Library");
+    let mut developer = vec![library.join("Developer")];
+    developer.extend(developer_roots);
+
+    vec![
+        (StorageBucket::Applications, vec![PathBuf::from("/Applications"), home.join("Applications")]),
+        (
+            StorageBucket::Documents,
+            vec![home.join("Documents"), home.join("Desktop"), home.join("Downloads")],
+        ),
+        (StorageBucket::Photos, vec![home.join("Pictures")]),
+        (StorageBucket::Developer, developer),
+        (StorageBucket::SystemCaches, vec![library.join("Caches"), PathBuf::from("/Library/Caches")]),
+        (StorageBucket::Trash, super::trash_scanner::trash_directories()),
+    ]
+}
+
+/// Drop roots that sit inside another root in the list, so nothing is counted twice
+fn outermost(roots: &[PathBuf]) -> Vec<&PathBuf> {
+    roots
+        .iter()
+        .enumerate()
+        .filter(|(i, root)| {
+            !roots
+                .iter()
+                .enumerate()
+                .any(|(j, other)| (other != *root && root.starts_with(other)) || (other == *root && j < *i))
+        })
+        .map(|(_, root)| root)
+        .collect()
+}
+
+/// Add up `roots`, less any of `claimed` nested inside them
+fn bucket_size(roots: &[PathBuf], claimed: &[PathBuf], control: &ScanControl) -> BoundedSize {
+    let size = |path: &Path| get_directory_size_bounded(path, Some(control), Some(DEFAULT_MAX_ENTRIES));
+    let mut total = BoundedSize::default();
+    for root in outermost(roots) {
+        let used = size(root);
+        let inside: Vec<PathBuf> = claimed
+            .iter()
+            .filter(|c| c.starts_with(root) && *c != root)
+            .cloned()
+            .collect();
+        let nested: Vec<BoundedSize> = outermost(&inside).into_iter().map(|c| size(c)).collect();
+        let nested_size: u64 = nested.iter().map(|n| n.size).sum();
+        total.size += used.size.saturating_sub(nested_size);
+        total.truncated |= used.truncated || nested.iter().any(|n| n.truncated);
+    }
+    total
+}
+
+/// Size every bucket on its own thread, largest first. A folder inside
+/// another bucket's folder is counted only in the bucket listed first, e.g.
+/// the Homebrew cache counts toward Developer rather than the caches.
+/// Once `control` is cancelled the remaining walks stop and report partial sizes.
+pub fn storage_breakdown(buckets: &[(StorageBucket, Vec<PathBuf>)], control: &ScanControl) -> StorageBreakdown {
+    let mut usages: Vec<BucketUsage> = thread::scope(|scope| {
+        let handles: Vec<_> = buckets
+            .iter()
+            .enumerate()
+            .map(|(i, (bucket, roots))| {
+                let claimed: Vec<PathBuf> = buckets[..i].iter().flat_map(|(_, r)| r.iter().cloned()).collect();
+                scope.spawn(move || {
+                    let sized = bucket_size(roots, &claimed, control);
+                    BucketUsage {
+                        bucket: *bucket,
+                        size: sized.size,
+                        truncated: sized.truncated,
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|handle| handle.join().ok()).collect()
+    });
+
+    usages.sort_by(|a, b| b.size.cmp(&a.size));
+    let total_bytes = usages.iter().map(|u| u.size).sum();
+    StorageBreakdown { buckets: usages, total_bytes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_file(path: &Path, len: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![1u8; len]).unwrap();
+    }
+
+    #[test]
+    fn test_storage_breakdown_sizes_each_bucket() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let docs = temp_dir.path().join("Documents");
+        let pics = temp_dir.path().join("Pictures");
+        write_file(&docs.join("report.pdf"), 64 * 1024);
+        write_file(&pics.join("a.jpg"), 128 * 1024);
+
+        let buckets = vec![
+            (StorageBucket::Documents, vec![docs, temp_dir.path().join("missing")]),
+            (StorageBucket::Photos, vec![pics]),
+        ];
+        let breakdown = storage_breakdown(&buckets, &ScanControl::default());
+
+        assert_eq!(breakdown.buckets.len(), 2);
+        assert_eq!(breakdown.buckets[0].bucket, StorageBucket::Photos);
+        assert!(breakdown.buckets[0].size >= 128 * 1024);
+        assert!(breakdown.buckets[1].size >= 64 * 1024);
+        assert!(breakdown.buckets.iter().all(|b| !b.truncated));
+        assert_eq!(breakdown.total_bytes, breakdown.buckets[0].size + breakdown.buckets[1].size);
+    }
+
+    #[test]
+    fn test_nested_folder_counts_in_first_bucket_only() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let caches = temp_dir.path().join("Caches");
+        let homebrew = caches.join("Homebrew");
+        write_file(&homebrew.join("bottle.tar.gz"), 256 * 1024);
+        write_file(&caches.join("com.example").join("blob"), 1024);
+
+        let buckets = vec![
+            (StorageBucket::Developer, vec![homebrew.clone(), homebrew.join("downloads")]),
+            (StorageBucket::SystemCaches, vec![caches]),
+        ];
+        let breakdown = storage_breakdown(&buckets, &ScanControl::default());
+        let size_of = |bucket| breakdown.buckets.iter().find(|b| b.bucket == bucket).unwrap().size;
+
+        assert!(size_of(StorageBucket::Developer) >= 256 * 1024);
+        assert!(size_of(StorageBucket::SystemCaches) < 256 * 1024);
+    }
+
+    #[test]
+    fn test_cancelled_breakdown_is_truncated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let docs = temp_dir.path().join("Documents");
+        write_file(&docs.join("a.txt"), 1024);
+
+        let control = ScanControl::default();
+        control.cancel();
+        let breakdown = storage_breakdown(&[(StorageBucket::Documents, vec![docs])], &control);
+        assert!(breakdown.buckets[0].truncated);
+    }
+}
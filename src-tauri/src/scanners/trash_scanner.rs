@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::disk_size::{get_directory_size, get_path_size};
+use super::scan_options::is_read_only_volume;
+
+/// Whether an item can be moved to the trash, so the UI can offer the right action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TrashCapability {
+    Normal,
+    NeedsAdmin,     // Needs an administrator password to move
+    ReadOnlyVolume, // Can't be trashed at all
+}
+
+/// One volume's trash for the current user and how much emptying it would free
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashLocation {
+    pub volume: String, // Mount point; "/" for the home trash on the boot volume
+    pub path: String,
+    pub size: u64,
+    pub item_count: usize,
+}
+
+/// Get the current user's ID
+pub fn current_uid() -> u32 {
+    #[cfg(unix)]
+    {
+        unsafe { libc::getuid() }
+    }
+    #[cfg(not(unix))]
+    {
+        0
+    }
+}
+
+/// Existing trash directories as (volume mount point, trash directory): the
+/// home trash for "/" plus `.Trashes/<uid>` on each volume in `volumes_dir`
+fn trash_locations_in(home: Option<&Path>, volumes_dir: &Path, uid: &str) -> Vec<(PathBuf, PathBuf)> {
+    let mut locations = Vec::new();
+    
+    if let Some(home) = home {
+        locations.push((PathBuf::from("/"), home.join(".Trash")));
+    }
+    
+    if let Ok(volumes) = fs::read_dir(volumes_dir) {
+        for volume in volumes.filter_map(|e| e.ok()) {
+            let trash = volume.path().join(".Trashes").join(uid);
+            locations.push((volume.path(), trash));
+        }
+    }
+    
+    locations.into_iter().filter(|(_, trash)| trash.is_dir()).collect()
+}
+
+/// The current user's trash on every mounted volume, as (mount point, trash directory)
+fn trash_locations() -> Vec<(PathBuf, PathBuf)> {
+    trash_locations_in(dirs::home_dir().as_deref(), Path::new("/Volumes"), &current_uid().to_string())
+}
+
+/// List the trash directories for the current user: `~/.Trash` plus
+/// `.Trashes/<uid>` on every mounted volume under /Volumes
+pub fn trash_directories() -> Vec<PathBuf> {
+    trash_locations().into_iter().map(|(_, trash)| trash).collect()
+}
+
+/// Size each trash in `locations` on disk
+fn scan_trash_locations(locations: Vec<(PathBuf, PathBuf)>) -> Vec<TrashLocation> {
+    locations
+        .into_iter()
+        .map(|(volume, trash)| TrashLocation {
+            volume: volume.to_string_lossy().to_string(),
+            path: trash.to_string_lossy().to_string(),
+            size: get_directory_size(&trash),
+            item_count: fs::read_dir(&trash).map(|entries| entries.count()).unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Every trash of the current user with its on-disk size: the home trash
+/// plus one per mounted volume that has one
+pub fn scan_all_trashes() -> Vec<TrashLocation> {
+    scan_trash_locations(trash_locations())
+}
+
+/// The trash directory in `locations` belonging to the volume mounted at `mount`
+fn trash_for_volume_in(locations: Vec<(PathBuf, PathBuf)>, mount: &Path) -> Result<PathBuf, String> {
+    locations
+        .into_iter()
+        .find(|(volume, _)| volume == mount)
+        .map(|(_, trash)| trash)
+        .ok_or_else(|| format!("No trash found on {}", mount.display()))
+}
+
+/// Permanently empty the current user's trash on the volume mounted at
+/// `mount` ("/" for the home trash), returning the bytes freed
+pub fn empty_trash_for_volume(mount: &str) -> Result<u64, String> {
+    let trimmed = mount.trim_end_matches('/');
+    let mount = if trimmed.is_empty() { Path::new("/") } else { Path::new(trimmed) };
+    let trash = trash_for_volume_in(trash_locations(), mount)?;
+    Ok(empty_trash_dirs(&[trash]))
+}
+
+/// The trash directory an item at `path` belongs in: `.Trashes/<uid>` on the
+/// external volume holding it, or `~/.Trash` for the boot volume
+pub fn trash_dir_for(path: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    if let (Some(_), Some(volumes), Some(volume)) = (components.next(), components.next(), components.next()) {
+        if volumes.as_os_str() == "Volumes" {
+            return Some(
+                PathBuf::from("/Volumes")
+                    .join(volume.as_os_str())
+                    .join(".Trashes")
+                    .join(current_uid().to_string()),
+            );
+        }
+    }
+    dirs::home_dir().map(|home| home.join(".Trash"))
+}
+
+/// A destination inside `trash_dir` for `path` that doesn't collide with
+/// anything already in the trash, numbering the name like Finder does
+pub fn unique_trash_destination(trash_dir: &Path, path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let dest = trash_dir.join(&name);
+    if !dest.exists() {
+        return dest;
+    }
+
+    let (stem, extension) = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) => (stem.to_string_lossy().to_string(), format!(".{}", ext.to_string_lossy())),
+        _ => (name, String::new()),
+    };
+    (2..)
+        .map(|n| trash_dir.join(format!("{} {}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(dest)
+}
+
+/// Move a file to the trash with administrator privileges using AppleScript.
+///
+/// The item is moved into the trash of the volume it lives on rather than
+/// removed with `rm -rf`, so privileged deletions can still be restored from
+/// the Trash like any other item.
+pub fn trash_with_admin_privileges(path: &Path) -> Result<(), String> {
+    let trash_dir = trash_dir_for(path).ok_or("Could not find the trash directory")?;
+    let dest = unique_trash_destination(&trash_dir, path);
+    let trash_dir = trash_dir.to_string_lossy();
+    let source = path.to_string_lossy();
+    let dest = dest.to_string_lossy();
+
+    // This will prompt the user for their password
+    crate::privileges::run_commands_with_admin(&[&["mkdir", "-p", &trash_dir], &["mv", &source, &dest]])
+}
+
+/// Sum the on-disk size of the given trash directories
+pub fn get_trash_size_in(trash_dirs: &[PathBuf]) -> u64 {
+    trash_dirs.iter().map(|d| get_directory_size(d)).sum()
+}
+
+/// Permanently remove the contents of the given trash directories, keeping the
+/// directories themselves. Items that cannot be removed are skipped.
+/// Returns the number of bytes freed.
+pub fn empty_trash_dirs(trash_dirs: &[PathBuf]) -> u64 {
+    trash_dirs
+        .iter()
+        .map(|dir| {
+            let freed = remove_dir_contents(dir);
+            crate::audit::record("empty_trash", &dir.to_string_lossy(), freed, &Ok(()));
+            freed
+        })
+        .sum()
+}
+
+/// Permanently remove everything inside `dir`, keeping `dir` itself.
+/// Items that cannot be removed are logged and skipped. Returns the bytes freed.
+pub fn remove_dir_contents(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    
+    let mut freed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let size = get_path_size(&path);
+        let result = if path.is_dir() && !path.is_symlink() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        
+        match result {
+            Ok(_) => freed += size,
+            Err(e) => log::warn!("Failed to remove {}: {}", path.display(), e),
+        }
+    }
+    
+    freed
+}
+
+/// Whether the owner write bit is missing on `path`. Unreadable paths count as writable.
+pub fn lacks_write_permission(path: &Path) -> bool {
+    let Ok(metadata) = path.metadata() else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // Check if we have write permission (owner write bit)
+        metadata.permissions().mode() & 0o200 == 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.permissions().readonly()
+    }
+}
+
+/// How an item could be moved to the trash. Moving it needs write access to
+/// both the item and the folder holding it.
+pub fn can_trash(path: &Path) -> Result<TrashCapability, String> {
+    if fs::symlink_metadata(path).is_err() {
+        return Err(format!("{} no longer exists", path.display()));
+    }
+    if is_read_only_volume(path) {
+        return Ok(TrashCapability::ReadOnlyVolume);
+    }
+    let parent_locked = path.parent().is_some_and(lacks_write_permission);
+    if parent_locked || lacks_write_permission(path) {
+        return Ok(TrashCapability::NeedsAdmin);
+    }
+    Ok(TrashCapability::Normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_trash() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("old.txt"), vec![1u8; 8192]).unwrap();
+        let folder = temp_dir.path().join("folder");
+        fs::create_dir(&folder).unwrap();
+        fs::write(folder.join("inner.bin"), vec![1u8; 8192]).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_get_trash_size_in() {
+        let trash = make_trash();
+        let size = get_trash_size_in(&[trash.path().to_path_buf()]);
+        assert!(size >= 16384);
+    }
+
+    #[test]
+    fn test_trash_dir_for() {
+        let uid = current_uid().to_string();
+        assert_eq!(
+            trash_dir_for(Path::new("/Volumes/Backup/old/file.txt")),
+            Some(PathBuf::from("/Volumes/Backup/.Trashes").join(&uid))
+        );
+        assert_eq!(
+            trash_dir_for(Path::new("/Library/Application Support/Old")),
+            dirs::home_dir().map(|home| home.join(".Trash"))
+        );
+    }
+
+    #[test]
+    fn test_unique_trash_destination() {
+        let trash = make_trash();
+        assert_eq!(
+            unique_trash_destination(trash.path(), Path::new("/src/new.txt")),
+            trash.path().join("new.txt")
+        );
+        assert_eq!(
+            unique_trash_destination(trash.path(), Path::new("/src/old.txt")),
+            trash.path().join("old 2.txt")
+        );
+        assert_eq!(
+            unique_trash_destination(trash.path(), Path::new("/src/folder")),
+            trash.path().join("folder 2")
+        );
+    }
+
+    #[test]
+    fn test_empty_trash_dirs() {
+        let trash = make_trash();
+        let dirs = vec![trash.path().to_path_buf()];
+        let before = get_trash_size_in(&dirs);
+
+        let freed = empty_trash_dirs(&dirs);
+
+        assert_eq!(freed, before);
+        assert!(trash.path().exists());
+        assert_eq!(fs::read_dir(trash.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_can_trash() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "x").unwrap();
+        assert_eq!(can_trash(&file).unwrap(), TrashCapability::Normal);
+
+        let locked = temp_dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        let inner = locked.join("inner.txt");
+        fs::write(&inner, "x").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        assert_eq!(can_trash(&inner).unwrap(), TrashCapability::NeedsAdmin);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(can_trash(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_trash_locations_per_volume() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let volumes = temp_dir.path().join("Volumes");
+        let backup = volumes.join("Backup");
+        fs::create_dir_all(home.join(".Trash")).unwrap();
+        fs::create_dir_all(backup.join(".Trashes").join("501")).unwrap();
+        fs::create_dir_all(backup.join(".Trashes").join("502")).unwrap();
+        fs::create_dir_all(volumes.join("NoTrash")).unwrap();
+        fs::write(backup.join(".Trashes").join("501").join("old.mov"), vec![1u8; 8192]).unwrap();
+
+        let locations = trash_locations_in(Some(&home), &volumes, "501");
+        assert_eq!(locations.len(), 2);
+
+        let scanned = scan_trash_locations(locations.clone());
+        let on_backup = scanned.iter().find(|t| t.volume == backup.to_string_lossy()).unwrap();
+        assert!(on_backup.size >= 8192);
+        assert_eq!(on_backup.item_count, 1);
+        assert_eq!(scanned.iter().find(|t| t.volume == "/").unwrap().size, 0);
+
+        assert_eq!(
+            trash_for_volume_in(locations.clone(), &backup).unwrap(),
+            backup.join(".Trashes").join("501")
+        );
+        assert_eq!(trash_for_volume_in(locations.clone(), Path::new("/")).unwrap(), home.join(".Trash"));
+        assert!(trash_for_volume_in(locations, &volumes.join("NoTrash")).is_err());
+    }
+
+    #[test]
+    fn test_trash_directories_exist() {
+        for dir in trash_directories() {
+            assert!(dir.is_dir());
+        }
+    }
+}
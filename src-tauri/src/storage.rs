@@ -0,0 +1,72 @@
+// Persistence helpers for files under the app support directory
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Folder name under ~/Library/Application Support
+const APP_SUPPORT_FOLDER: &str = "macos-quick-cleaner";
+
+/// Get (and create if needed) ~/Library/Application Support/macos-quick-cleaner
+pub fn app_support_dir() -> Result<PathBuf, String> {
+    // Tests write under the temp dir so they never touch the user's real data
+    let base = if cfg!(test) {
+        std::env::temp_dir().join("macos-quick-cleaner-tests")
+    } else {
+        dirs::home_dir().ok_or("Could not determine home directory")?
+    };
+    let dir = base
+        .join("Library")
+        .join("Application Support")
+        .join(APP_SUPPORT_FOLDER);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Path of a file inside the app support directory
+pub fn app_support_file(name: &str) -> Result<PathBuf, String> {
+    Ok(app_support_dir()?.join(name))
+}
+
+/// Read a JSON file, returning None if it is missing or malformed
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Write a value as JSON atomically (temp file + rename) so a crash mid-write
+/// never leaves a truncated file behind
+pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_read_json() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("data.json");
+
+        write_json_atomic(&path, &vec![1u64, 2, 3]).unwrap();
+        let value: Option<Vec<u64>> = read_json(&path);
+
+        assert_eq!(value, Some(vec![1, 2, 3]));
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_read_json_missing_or_malformed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("data.json");
+        assert_eq!(read_json::<Vec<u64>>(&path), None);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(read_json::<Vec<u64>>(&path), None);
+    }
+}
@@ -34,6 +34,10 @@ export function getCategoryClass(category: string): string {
     Document: "badge-document",
     Application: "badge-other",
     DiskImage: "badge-archive",
+    Font: "badge-other",
+    Ebook: "badge-document",
+    VirtualMachine: "badge-archive",
+    Project: "badge-document",
     Other: "badge-other",
   };
   return classes[category] || "badge-other";
@@ -9,6 +9,7 @@ import type {
   DuplicateGroup,
   SystemInfo,
   NavSection,
+  Page,
 } from "../types";
 import type { Toast, ToastType } from "../components/common/Toast";
 
@@ -168,7 +169,9 @@ export const useAppStore = create<AppState>((set, get) => ({
   scanOrphanFiles: async () => {
     set({ isLoadingOrphans: true });
     try {
-      const orphanFiles = await invoke<OrphanFile[]>("scan_orphan_files");
+      const { items: orphanFiles } = await invoke<Page<OrphanFile>>(
+        "scan_orphan_files"
+      );
       set({ orphanFiles });
       get().addToast(
         "success",
@@ -16,16 +16,101 @@ export interface CacheEntry {
   is_developer_related: boolean;
   is_safe_to_delete: boolean;
   description: string;
+  possibly_corrupt: boolean;
+  truncated: boolean; // Sizing stopped early; size is a lower bound
+}
+
+export interface CacheChild {
+  name: string;
+  path: string;
+  size: number;
+  is_dir: boolean;
+}
+
+export interface CacheContents {
+  children: CacheChild[];
+  total_children: number;
+  total_size: number;
+  truncated: boolean;
+}
+
+export interface GroupedCaches {
+  user: CacheEntry[];
+  system: CacheEntry[];
+}
+
+export type CacheChange = "New" | "Grown" | "Shrunk" | "Removed";
+
+export interface CacheDelta {
+  path: string;
+  name: string;
+  change: CacheChange;
+  previous_size: number;
+  current_size: number;
+  delta: number;
+}
+
+export interface SharedJunkLocation {
+  name: string;
+  path: string;
+  size: number;
+  description: string;
+  exists: boolean;
+  safe_to_clean: boolean; // False for folders that may hold data someone needs
+  requires_admin: boolean;
 }
 
 // Developer cache types
 export interface DeveloperCache {
   name: string;
   path: string;
-  size: number;
+  size: number; // On disk
+  apparent_size: number; // Sum of file lengths
   description: string;
   exists: boolean;
   safe_to_clean: boolean;
+  docker_usage: DockerUsage | null;
+}
+
+export interface DeveloperCacheProgress {
+  name: string;
+  size: number;
+  completed: number;
+  total: number;
+}
+
+export interface DerivedDataProject {
+  path: string;
+  folder_name: string;
+  project_name: string;
+  size: number;
+  last_modified: number | null;
+  is_module_cache: boolean;
+}
+
+export interface DockerUsageCategory {
+  kind: string;
+  total_count: number;
+  active: number;
+  size: number;
+  reclaimable: number;
+}
+
+export interface HomebrewCleanupItem {
+  path: string;
+  size: number;
+  file_count: number | null;
+}
+
+export interface HomebrewCleanupReport {
+  items: HomebrewCleanupItem[];
+  total_size: number;
+}
+
+export interface DockerUsage {
+  categories: DockerUsageCategory[];
+  total_size: number;
+  total_reclaimable: number;
 }
 
 // App types
@@ -35,6 +120,14 @@ export interface InstalledApp {
   path: string;
 }
 
+export interface AppFootprint {
+  app: InstalledApp;
+  bundle_size: number;
+  data_size: number;
+  total: number;
+  is_running: boolean;
+}
+
 export type OrphanType =
   | "ApplicationSupport"
   | "Preferences"
@@ -54,10 +147,18 @@ export interface OrphanFile {
 export interface LargeAppData {
   path: string;
   name: string;
-  size: number;
+  size: number; // On disk
+  apparent_size: number; // Sum of file lengths
   location: string; // "ApplicationSupport" | "Containers" | "Caches"
 }
 
+export interface OrphanLaunchItem {
+  path: string;
+  label: string;
+  program: string;
+  requires_admin: boolean;
+}
+
 // Large file types
 export type FileCategory =
   | "Video"
@@ -67,6 +168,10 @@ export type FileCategory =
   | "Document"
   | "Application"
   | "DiskImage"
+  | "Font"
+  | "Ebook"
+  | "VirtualMachine"
+  | "Project"
   | "Other";
 
 export interface LargeFile {
@@ -76,12 +181,43 @@ export interface LargeFile {
   category: FileCategory;
   last_modified: number | null;
   extension: string;
+  on_disk_size: number; // Below size for sparse files
+  is_sparse: boolean;
+}
+
+export interface CategoryTotal {
+  count: number;
+  total_bytes: number;
+}
+
+export interface LargeFileReport {
+  files: LargeFile[];
+  totals_by_category: Record<string, CategoryTotal>;
+  skipped_mounts: string[];
+}
+
+export interface LargeFileScanParams {
+  categories?: string[];
+  max_depth?: number;
+  exclude?: string[];
+  follow_symlinks?: boolean;
+  skip_network_mounts?: boolean;
+  filter?: string;
+  sort_by?: SortOrder;
+  offset?: number;
+  limit?: number;
+  include_hidden?: boolean;
+  include_dismissed?: boolean;
+  exclude_categories?: string[];
 }
 
 // Duplicate types
+export type HashAlgorithm = "Sha256" | "Sha512";
+
 export interface DuplicateFile {
   path: string;
   name: string;
+  is_symlink: boolean;
 }
 
 export interface DuplicateGroup {
@@ -91,12 +227,43 @@ export interface DuplicateGroup {
   total_wasted: number;
 }
 
+export type KeepStrategy =
+  | "OldestModified"
+  | "NewestModified"
+  | "ShortestPath"
+  | "OutsideDownloads";
+
+export interface KeepSuggestion {
+  keep_index: number;
+  delete: string[];
+}
+
+export interface GroupResolution {
+  kept: string;
+  trashed: string[];
+  bytes_freed: number;
+}
+
 // System info types
+export type UnitSystem = "Binary" | "Decimal";
+
 export interface DiskUsage {
   total_bytes: number;
   free_bytes: number;
   used_bytes: number;
   used_percentage: number;
+  finder_available_bytes: number | null;
+}
+
+export interface FinderFreeSpace {
+  available_bytes: number;
+  statvfs_free_bytes: number;
+  note: string;
+}
+
+export interface DiskUsageSnapshot {
+  timestamp: number;
+  disk_usage: DiskUsage;
 }
 
 export interface SystemInfo {
@@ -105,6 +272,263 @@ export interface SystemInfo {
   username: string;
   home_directory: string;
   disk_usage: DiskUsage;
+  model_identifier: string;
+  cpu_model: string;
+  cpu_cores: number;
+  total_memory_bytes: number;
+  used_memory_bytes: number;
+}
+
+// Download types
+export interface QuarantinedDownload {
+  path: string;
+  name: string;
+  size: number;
+  downloaded_at: number | null;
+  agent: string | null;
+  origin_url: string | null;
+  referrer_url: string | null;
+}
+
+export interface StaleDownload {
+  path: string;
+  name: string;
+  size: number;
+  last_used: number;
+}
+
+// Trash types
+export type TrashCapability = "Normal" | "NeedsAdmin" | "ReadOnlyVolume";
+
+export interface TrashLocation {
+  volume: string;
+  path: string;
+  size: number;
+  item_count: number;
+}
+
+// Mail types
+export interface MailIndex {
+  version: string;
+  path: string;
+  size: number;
+}
+
+export interface MailIndexReport {
+  indexes: MailIndex[];
+  total_size: number;
+  mail_running: boolean;
+}
+
+// QuickLook types
+export interface QuickLookCacheResult {
+  cache_path: string | null;
+  bytes_freed: number | null;
+}
+
+// Browser cache types
+export type BrowserDataType =
+  | "Cache"
+  | "CodeCache"
+  | "GpuCache"
+  | "ServiceWorker"
+  | "StartupCache";
+
+export interface BrowserCache {
+  path: string;
+  data_type: BrowserDataType;
+  size: number;
+  logs_out: boolean;
+}
+
+export interface BrowserProfile {
+  browser: string;
+  profile: string;
+  caches: BrowserCache[];
+  total_size: number;
+}
+
+// Startup types
+export type LoginItemSource =
+  | "UserLaunchAgent"
+  | "SystemLaunchAgent"
+  | "OpenAtLogin";
+
+export interface LoginItem {
+  name: string;
+  label: string;
+  path: string;
+  enabled: boolean;
+  source: LoginItemSource;
+}
+
+// Pagination types
+export type SortOrder =
+  | "SizeDesc"
+  | "SizeAsc"
+  | "NameAsc"
+  | "DateNewest"
+  | "DateOldest";
+
+export interface Page<T> {
+  items: T[];
+  total_count: number;
+}
+
+export interface ScanStats {
+  files_considered: number;
+  size_matched: number;
+  partial_hashed: number;
+  full_hashed: number;
+}
+
+export interface DuplicateScanResult extends Page<DuplicateGroup> {
+  stats: ScanStats;
+}
+
+export interface DuplicateScanOptions {
+  max_depth?: number;
+  exclude?: string[];
+  follow_symlinks?: boolean;
+  skip_network_mounts?: boolean;
+  filter?: string;
+  sort_by?: SortOrder;
+  offset?: number;
+  limit?: number;
+  scan_id?: string;
+  include_hidden?: boolean;
+  min_wasted_mb?: number;
+  max_groups?: number;
+  include_dismissed?: boolean;
+  min_size_bytes?: number;
+}
+
+// Batch operation types
+export interface DeleteOutcome {
+  path: string;
+  result: { Ok: null } | { Err: string };
+}
+
+export interface PathSize {
+  path: string;
+  size: number;
+  error: string | null;
+}
+
+export interface DirEntryUsage {
+  path: string;
+  name: string;
+  size: number;
+  is_dir: boolean;
+}
+
+export type StorageBucket =
+  | "Applications"
+  | "Documents"
+  | "Photos"
+  | "Developer"
+  | "SystemCaches"
+  | "Trash";
+
+export interface BucketUsage {
+  bucket: StorageBucket;
+  size: number;
+  truncated: boolean;
+}
+
+export interface StorageBreakdown {
+  buckets: BucketUsage[];
+  total_bytes: number;
+}
+
+// Cleanup types
+export type CleanupSource = "UserCache" | "DeveloperCache" | "Trash";
+
+export interface CleanupItem {
+  path: string;
+  name: string;
+  size: number;
+  source: CleanupSource;
+  description: string;
+}
+
+export interface CleanupPlan {
+  items: CleanupItem[];
+  total_bytes: number;
+}
+
+export interface CleanupError {
+  path: string;
+  error: string;
+}
+
+export interface CleanupResult {
+  bytes_freed: number;
+  errors: CleanupError[];
+}
+
+export type OverviewCategory =
+  | "UserCaches"
+  | "DeveloperCaches"
+  | "Orphans"
+  | "Duplicates"
+  | "Trash"
+  | "LargeFiles";
+
+export interface CategoryEstimate {
+  category: OverviewCategory;
+  item_count: number;
+  bytes: number;
+  percent_of_disk: number;
+}
+
+export interface CleanupOverview {
+  categories: CategoryEstimate[];
+  total_bytes: number;
+  percent_of_disk: number;
+}
+
+// Settings types
+export interface Settings {
+  large_file_min_size_mb: number;
+  duplicate_min_size_mb: number;
+  scan_roots: string[];
+  duplicate_scan_roots: string[];
+  exclude_patterns: string[];
+  protected_paths: string[];
+  unit_system: UnitSystem;
+  confirm_before_delete: boolean;
+  disk_history_retention_days: number;
+  extension_categories: Record<string, FileCategory>;
+  custom_developer_caches: CustomDeveloperCache[];
+  max_scan_concurrency: number;
+  cache_safety_overrides: Record<string, boolean>;
+  safe_mode: boolean;
+}
+
+export interface CustomDeveloperCache {
+  name: string;
+  path: string; // "~/" is expanded
+  description: string;
+}
+
+// Quarantine types
+export interface QuarantinedItem {
+  id: string;
+  name: string;
+  original_path: string;
+  size: number;
+  quarantined_at: number;
+}
+
+// Audit types
+export interface AuditEntry {
+  timestamp: number;
+  operation: string;
+  path: string;
+  size: number;
+  success: boolean;
+  error: string | null;
 }
 
 // Navigation